@@ -1,71 +1,75 @@
 use samp::amx::Amx;
-use samp::cell::{AmxCell, AmxString, Ref, UnsizedBuffer};
+use samp::cell::{AmxString, Ref, UnsizedBuffer};
 use samp::error::AmxResult;
+use samp::handle::HandleTable;
 use samp::plugin::SampPlugin;
-use samp::{initialize_plugin, native};
+use samp::{AmxCell, initialize_plugin, native};
 
 use log::info;
 
 use memcache::Client;
 
-#[derive(Debug, Clone, Copy)]
+/// Status returned to Pawn; each variant is sent as its discriminant.
+#[derive(Debug, Clone, Copy, AmxCell)]
 enum MemcacheResult {
-    Success(i32),
-    NoData,
-    NoClient,
-    NoKey,
-}
-
-impl AmxCell<'_> for MemcacheResult {
-    fn as_cell(&self) -> i32 {
-        match self {
-            MemcacheResult::Success(result) => *result,
-            MemcacheResult::NoData => -1,
-            MemcacheResult::NoClient => -2,
-            MemcacheResult::NoKey => -3,
-        }
-    }
+    Success = 1,
+    NoData = -1,
+    NoClient = -2,
+    NoKey = -3,
 }
 
 struct Memcached {
-    clients: Vec<Client>,
+    clients: HandleTable<Client>,
 }
 
 impl Memcached {
+    /// Looks up a connection ID from Pawn. Natives take it as a plain `i32`
+    /// rather than `Handle<Client>` so an unknown or closed ID still
+    /// answers `MemcacheResult::NoClient` instead of failing to parse.
+    fn client(&self, con: i32) -> Option<std::cell::Ref<'_, Client>> {
+        self.clients.handle(con).and_then(|con| self.clients.get(con))
+    }
+
+    /// Returns the connection handle, or `MemcacheResult::NoClient` (`-2`).
     #[native(name = "Memcached_Connect")]
-    pub fn connect(&mut self, _: &Amx, address: &AmxString) -> MemcacheResult {
+    pub fn connect(&mut self, amx: &Amx, address: &AmxString) -> i32 {
         // Client::connect is generic over Connectable (impl'd for &str);
         // since Rust does not apply deref coerce on generic parameters, we force
         // &str explicitly with &**address.
         match Client::connect(&**address) {
-            Ok(client) => {
-                self.clients.push(client);
-                let idx = i32::try_from(self.clients.len()).unwrap_or(i32::MAX);
-                MemcacheResult::Success(idx - 1)
-            }
-            Err(_) => MemcacheResult::NoClient,
+            // Owned by the calling script: the SDK closes the connection
+            // when that script unloads.
+            Ok(client) => self.clients.insert_owned(amx, client).id(),
+            Err(_) => MemcacheResult::NoClient as i32,
         }
     }
 
+    #[native(name = "Memcached_Close")]
+    pub fn close(&mut self, _: &Amx, con: i32) -> bool {
+        self.clients
+            .handle(con)
+            .and_then(|con| self.clients.remove(con))
+            .is_some()
+    }
+
     #[native(name = "Memcached_Get")]
     pub fn get(
         &mut self,
         _: &Amx,
-        con: usize,
+        con: i32,
         key: &AmxString,
         mut value: Ref<i32>,
     ) -> MemcacheResult {
-        if con < self.clients.len() {
-            match self.clients[con].get(key) {
-                Ok(Some(data)) => {
-                    *value = data;
-                    MemcacheResult::Success(1)
-                }
-                Ok(None) => MemcacheResult::NoData,
-                Err(_) => MemcacheResult::NoKey,
+        let Some(client) = self.client(con) else {
+            return MemcacheResult::NoClient;
+        };
+        match client.get(key) {
+            Ok(Some(data)) => {
+                *value = data;
+                MemcacheResult::Success
             }
-        } else {
-            MemcacheResult::NoClient
+            Ok(None) => MemcacheResult::NoData,
+            Err(_) => MemcacheResult::NoKey,
         }
     }
 
@@ -73,23 +77,22 @@ impl Memcached {
     pub fn get_string(
         &mut self,
         _: &Amx,
-        con: usize,
+        con: i32,
         key: &AmxString,
         buffer: UnsizedBuffer,
         size: usize,
     ) -> AmxResult<MemcacheResult> {
-        if con < self.clients.len() {
-            match self.clients[con].get::<String>(key) {
-                Ok(Some(data)) => {
-                    // write_str: combines into_sized_buffer + write in a single step
-                    buffer.write_str(size, &data)?;
-                    Ok(MemcacheResult::Success(1))
-                }
-                Ok(None) => Ok(MemcacheResult::NoData),
-                Err(_) => Ok(MemcacheResult::NoKey),
+        let Some(client) = self.client(con) else {
+            return Ok(MemcacheResult::NoClient);
+        };
+        match client.get::<String>(key) {
+            Ok(Some(data)) => {
+                // write_str: combines into_sized_buffer + write in a single step
+                buffer.write_str(size, &data)?;
+                Ok(MemcacheResult::Success)
             }
-        } else {
-            Ok(MemcacheResult::NoClient)
+            Ok(None) => Ok(MemcacheResult::NoData),
+            Err(_) => Ok(MemcacheResult::NoKey),
         }
     }
 
@@ -97,18 +100,17 @@ impl Memcached {
     pub fn set(
         &mut self,
         _: &Amx,
-        con: usize,
+        con: i32,
         key: &AmxString,
         value: i32,
         expire: u32,
     ) -> MemcacheResult {
-        if con < self.clients.len() {
-            match self.clients[con].set(key, value, expire) {
-                Ok(()) => MemcacheResult::Success(1),
-                Err(_) => MemcacheResult::NoKey,
-            }
-        } else {
-            MemcacheResult::NoClient
+        let Some(client) = self.client(con) else {
+            return MemcacheResult::NoClient;
+        };
+        match client.set(key, value, expire) {
+            Ok(()) => MemcacheResult::Success,
+            Err(_) => MemcacheResult::NoKey,
         }
     }
 
@@ -116,22 +118,21 @@ impl Memcached {
     pub fn set_string(
         &mut self,
         _: &Amx,
-        con: usize,
+        con: i32,
         key: &AmxString,
         value: &AmxString,
         expire: u32,
     ) -> MemcacheResult {
-        if con < self.clients.len() {
-            // `key` is &AmxString — deref coerce to &str (parameter `key` expects &str).
-            // `value` needs ToMemcacheValue, impl'd for &str but not for
-            // &AmxString; on generic parameters Rust does not apply deref coerce,
-            // so we force &str via &**value.
-            match self.clients[con].set(key, &**value, expire) {
-                Ok(()) => MemcacheResult::Success(1),
-                Err(_) => MemcacheResult::NoKey,
-            }
-        } else {
-            MemcacheResult::NoClient
+        let Some(client) = self.client(con) else {
+            return MemcacheResult::NoClient;
+        };
+        // `key` is &AmxString — deref coerce to &str (parameter `key` expects &str).
+        // `value` needs ToMemcacheValue, impl'd for &str but not for
+        // &AmxString; on generic parameters Rust does not apply deref coerce,
+        // so we force &str via &**value.
+        match client.set(key, &**value, expire) {
+            Ok(()) => MemcacheResult::Success,
+            Err(_) => MemcacheResult::NoKey,
         }
     }
 
@@ -139,30 +140,28 @@ impl Memcached {
     pub fn increment(
         &mut self,
         _: &Amx,
-        con: usize,
+        con: i32,
         key: &AmxString,
         value: i32,
     ) -> MemcacheResult {
-        if con < self.clients.len() {
-            match self.clients[con].increment(key, u64::from(value.cast_unsigned())) {
-                Ok(_) => MemcacheResult::Success(1),
-                Err(_) => MemcacheResult::NoKey,
-            }
-        } else {
-            MemcacheResult::NoClient
+        let Some(client) = self.client(con) else {
+            return MemcacheResult::NoClient;
+        };
+        match client.increment(key, u64::from(value.cast_unsigned())) {
+            Ok(_) => MemcacheResult::Success,
+            Err(_) => MemcacheResult::NoKey,
         }
     }
 
     #[native(name = "Memcached_Delete")]
-    pub fn delete(&mut self, _: &Amx, con: usize, key: &AmxString) -> MemcacheResult {
-        if con < self.clients.len() {
-            match self.clients[con].delete(key) {
-                Ok(true) => MemcacheResult::Success(1),
-                Ok(false) => MemcacheResult::NoData,
-                Err(_) => MemcacheResult::NoKey,
-            }
-        } else {
-            MemcacheResult::NoClient
+    pub fn delete(&mut self, _: &Amx, con: i32, key: &AmxString) -> MemcacheResult {
+        let Some(client) = self.client(con) else {
+            return MemcacheResult::NoClient;
+        };
+        match client.delete(key) {
+            Ok(true) => MemcacheResult::Success,
+            Ok(false) => MemcacheResult::NoData,
+            Err(_) => MemcacheResult::NoKey,
         }
     }
 }
@@ -201,6 +200,7 @@ impl SampPlugin for Memcached {
 initialize_plugin!(
     natives: [
         Memcached::connect,
+        Memcached::close,
         Memcached::get,
         Memcached::set,
         Memcached::get_string,
@@ -235,7 +235,7 @@ initialize_plugin!(
             .apply();
 
         return Memcached {
-            clients: Vec::new(),
+            clients: HandleTable::new(),
         };
     }
 );
@@ -6,6 +6,13 @@
 //! - `initialize_plugin!` with constructor block
 //! - `Ref<i32>` for output by reference (`&value` in PAWN)
 //! - Multiple natives with real logic
+//! - Per-script state via `AmxExt::state` (`Counter_ScriptIncrements`)
+//! - `#[derive(AmxCell)]` enum as a native argument (`Counter_Step`)
+//! - `#[derive(AmxStruct)]` enum-structured array taken as `&mut T`
+//!   (`Counter_Sync`)
+//! - `#[event]` handlers, with `after`, `priority`, `define` and `scope`, and
+//!   a runtime subscription turned on and off by a native
+//!   (`Counter_WatchSpawns`)
 //!
 //! Natives exposed to PAWN:
 //! ```pawn
@@ -15,17 +22,57 @@
 //! native Counter_Get(&out);
 //! native Counter_SetMax(max);
 //! native bool:Counter_IsAtMax();
+//! native Counter_ScriptIncrements();
+//! native Counter_Step(step);
+//! native Counter_Sync(info[E_COUNTER_INFO]);
+//! native Counter_WatchSpawns(bool:enable);
 //! ```
 
+use std::cell::Cell;
+use std::rc::Rc;
+
 use log::info;
+use samp::events::Subscription;
 use samp::plugin::TickContext;
 use samp::prelude::*;
-use samp::{event, initialize_plugin, native};
+use samp::{AmxCell, AmxStruct, event, initialize_plugin, native};
 
 struct Counter {
     count: i32,
     max: i32,
     ticks: u32,
+    /// Spawns seen while `Counter_WatchSpawns` was on.
+    spawns: Rc<Cell<i32>>,
+    spawn_watch: Option<Subscription>,
+}
+
+/// Kept per script through `amx.state::<ScriptStats>()`: the SDK creates it
+/// on the script's first increment and drops it when the script unloads.
+#[derive(Default)]
+struct ScriptStats {
+    increments: i32,
+}
+
+/// Direction for `Counter_Step`. Any other value fails argument parsing, so
+/// the native returns `0` without touching the counter.
+#[derive(AmxCell, Clone, Copy)]
+enum Step {
+    Up = 1,
+    Down = -1,
+}
+
+/// Mirrors the Pawn array passed to `Counter_Sync`:
+///
+/// ```pawn
+/// enum E_COUNTER_INFO { E_COUNT, E_MAX, bool:E_AT_MAX, E_LABEL[16] }
+/// ```
+#[derive(AmxStruct)]
+struct CounterInfo {
+    count: i32,
+    max: i32,
+    at_max: bool,
+    #[amx(string = 16)]
+    label: String,
 }
 
 impl SampPlugin for Counter {
@@ -52,11 +99,12 @@ impl SampPlugin for Counter {
 impl Counter {
     /// Increments the counter. Returns the new value, or -1 if already at the maximum.
     #[native(name = "Counter_Increment")]
-    fn increment(&mut self, _amx: &Amx) -> i32 {
+    fn increment(&mut self, amx: &Amx) -> i32 {
         if self.count >= self.max {
             return -1;
         }
         self.count += 1;
+        amx.state::<ScriptStats>().increments += 1;
         self.count
     }
 
@@ -110,14 +158,75 @@ impl Counter {
         self.count >= self.max
     }
 
+    /// Returns how many successful increments the calling script made.
+    /// Starts again from zero when the script is reloaded.
+    #[native(name = "Counter_ScriptIncrements")]
+    fn script_increments(&mut self, amx: &Amx) -> i32 {
+        amx.state::<ScriptStats>().increments
+    }
+
+    /// Increments (`1`) or decrements (`-1`) the counter. Returns what
+    /// `Counter_Increment`/`Counter_Decrement` would.
+    #[native(name = "Counter_Step")]
+    fn step(&mut self, amx: &Amx, step: Step) -> i32 {
+        match step {
+            Step::Up => self.increment(amx),
+            Step::Down => self.decrement(amx),
+        }
+    }
+
+    /// Applies `info[E_MAX]` as the new maximum when it is positive, then
+    /// fills the rest of `info` with the counter's state. `E_LABEL` gets
+    /// `"count/max"`.
+    ///
+    /// ```pawn
+    /// new info[E_COUNTER_INFO];
+    /// Counter_Sync(info);
+    /// print(info[E_LABEL]); // "4/100"
+    /// ```
+    #[native(name = "Counter_Sync")]
+    fn sync(&mut self, amx: &Amx, info: &mut CounterInfo) -> bool {
+        if info.max > 0 {
+            self.set_max(amx, info.max);
+        }
+        info.count = self.count;
+        info.max = self.max;
+        info.at_max = self.count >= self.max;
+        info.label = format!("{}/{}", self.count, self.max);
+        true
+    }
+
+    /// Turns spawn counting on or off and returns the spawns counted so far.
+    /// Turning it on subscribes a closure to `OnPlayerSpawn` at runtime;
+    /// turning it off drops the `Subscription`, which unsubscribes it.
+    #[native(name = "Counter_WatchSpawns")]
+    fn watch_spawns(&mut self, _amx: &Amx, enable: bool) -> i32 {
+        if !enable {
+            self.spawn_watch = None;
+        } else if self.spawn_watch.is_none() {
+            let spawns = Rc::clone(&self.spawns);
+            self.spawn_watch = Some(samp::events::subscribe(
+                "OnPlayerSpawn",
+                move |_amx, _args| {
+                    spawns.set(spawns.get() + 1);
+                    EventReturn::Continue
+                },
+            ));
+        }
+        self.spawns.get()
+    }
+
     /// Observes the gamemode's `OnPlayerConnect` callback. The handler runs
     /// before the gamemode's own public — here it just logs the connecting
     /// player. Registered via the `events: [...]` list below.
     ///
+    /// `scope = gamemode` keeps it to the gamemode: a filterscript defining
+    /// `OnPlayerConnect` as well would otherwise log every connection twice.
+    ///
     /// ```pawn
     /// public OnPlayerConnect(playerid) { return 1; }
     /// ```
-    #[event(name = "OnPlayerConnect")]
+    #[event(name = "OnPlayerConnect", scope = gamemode)]
     fn on_player_connect(&mut self, _amx: &Amx, playerid: i32) -> AmxResult<i32> {
         info!("[event] OnPlayerConnect: player {playerid} connected");
         Ok(1)
@@ -143,6 +252,46 @@ impl Counter {
             EventReturn::Continue
         }
     }
+
+    /// Post-callback hook: runs after the gamemode's `OnPlayerCommandText` and
+    /// receives what it returned. A `/count` the gamemode did not handle (`0`)
+    /// is answered here, and `EventReturn::suppress(true)` makes the callback
+    /// report it as handled.
+    ///
+    /// ```pawn
+    /// public OnPlayerCommandText(playerid, cmdtext[]) { return 0; }
+    /// ```
+    #[event(name = "OnPlayerCommandText", after)]
+    fn on_player_command_text(
+        &mut self,
+        _amx: &Amx,
+        handled: bool,
+        playerid: i32,
+        cmdtext: &AmxString,
+    ) -> EventReturn {
+        if handled || &**cmdtext != "/count" {
+            return EventReturn::Continue;
+        }
+        info!("[event] /count for player {playerid}: {}", self.count);
+        EventReturn::suppress(true)
+    }
+
+    /// Logs every command. `priority = 10` runs it ahead of the other
+    /// `OnPlayerCommandText` handlers (the default priority is `0`), whatever
+    /// their place in the `events: [...]` list.
+    #[event(name = "OnPlayerCommandText", priority = 10)]
+    fn log_command(&mut self, _amx: &Amx, playerid: i32, cmdtext: &AmxString) {
+        info!("[event] player {playerid} typed {}", &**cmdtext);
+    }
+
+    /// `define` delivers the callback even though the gamemode below does not
+    /// declare `OnPlayerDisconnect` — the plugin does not depend on the Pawn
+    /// author remembering an empty public. `scope = gamemode` keeps it to one
+    /// delivery per player, not one per script.
+    #[event(name = "OnPlayerDisconnect", define, scope = gamemode)]
+    fn on_player_disconnect(&mut self, _amx: &Amx, playerid: i32, reason: i32) {
+        info!("[event] player {playerid} left (reason {reason})");
+    }
 }
 
 initialize_plugin!(
@@ -153,10 +302,17 @@ initialize_plugin!(
         Counter::get,
         Counter::set_max,
         Counter::is_at_max,
+        Counter::script_increments,
+        Counter::step,
+        Counter::sync,
+        Counter::watch_spawns,
     ],
     events: [
         Counter::on_player_connect,
         Counter::on_player_text,
+        Counter::on_player_command_text,
+        Counter::log_command,
+        Counter::on_player_disconnect,
     ],
     {
         samp::plugin::enable_tick();
@@ -172,6 +328,277 @@ initialize_plugin!(
             count: 0,
             max: 100,
             ticks: 0,
+            spawns: Rc::default(),
+            spawn_watch: None,
         };
     }
 );
+
+#[cfg(test)]
+mod tests {
+    /// Loads the plugin as a native open.mp component on a mock server and
+    /// walks it through the server's lifecycle.
+    #[test]
+    fn omp_component_lifecycle() {
+        use samp::amx::{AmxExt, AmxKind};
+        use samp::error::AmxError;
+        use samp::test_vm::omp::{MockServer, MockTimer};
+        use samp::test_vm::{ImageBuilder, TestVm, opcodes as op};
+
+        // public Test() { Counter_Increment(); return Counter_Increment(); }
+        let mut image = ImageBuilder::new();
+        let increment = image.native("Counter_Increment");
+        let script_increments = image.native("Counter_ScriptIncrements");
+        let step = image.native("Counter_Step");
+        let sync = image.native("Counter_Sync");
+        image.public("Test").code(&[
+            op::PROC,
+            op::PUSH_C,
+            0,
+            op::SYSREQ_C,
+            increment,
+            op::STACK,
+            4,
+            op::PUSH_C,
+            0,
+            op::SYSREQ_C,
+            increment,
+            op::STACK,
+            4,
+            op::RETN,
+        ]);
+        // public Mine() { return Counter_ScriptIncrements(); }
+        image.public("Mine").code(&[
+            op::PROC,
+            op::PUSH_C,
+            0,
+            op::SYSREQ_C,
+            script_increments,
+            op::STACK,
+            4,
+            op::RETN,
+        ]);
+        // public Step(step) { return Counter_Step(step); }
+        image.public("Step").code(&[
+            op::PROC,
+            op::PUSH_S,
+            12,
+            op::PUSH_C,
+            4,
+            op::SYSREQ_C,
+            step,
+            op::STACK,
+            8,
+            op::RETN,
+        ]);
+        // new info[E_COUNTER_INFO] = { 0, 3, false, "" };
+        // public Sync() { return Counter_Sync(info); }
+        let mut cells = vec![0, 3, 0];
+        cells.resize(3 + 16, 0);
+        let info = image.data(&cells);
+        let label = info + 3 * 4;
+        image.public("Sync").code(&[
+            op::PROC,
+            op::PUSH_C,
+            info,
+            op::PUSH_C,
+            4,
+            op::SYSREQ_C,
+            sync,
+            op::STACK,
+            8,
+            op::RETN,
+        ]);
+        // public OnPlayerCommandText(playerid, cmdtext[]) { return 0; }
+        image
+            .public("OnPlayerCommandText")
+            .code(&[op::PROC, op::CONST_PRI, 0, op::RETN]);
+        let command = |text: &str| text.bytes().map(i32::from).chain([0]).collect::<Vec<_>>();
+        let count_cmd = image.data(&command("/count"));
+        let help_cmd = image.data(&command("/help"));
+        // public Watch(enable) { return Counter_WatchSpawns(enable); }
+        let watch_spawns = image.native("Counter_WatchSpawns");
+        image.public("Watch").code(&[
+            op::PROC,
+            op::PUSH_S,
+            12,
+            op::PUSH_C,
+            4,
+            op::SYSREQ_C,
+            watch_spawns,
+            op::STACK,
+            8,
+            op::RETN,
+        ]);
+        // public OnPlayerSpawn(playerid) { return 1; }
+        image
+            .public("OnPlayerSpawn")
+            .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        // public OnPlayerConnect(playerid) { return 1; }
+        image
+            .public("OnPlayerConnect")
+            .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        // main() {} — the entry point that makes it a gamemode.
+        image.main().code(&[op::PROC, op::ZERO_PRI, op::RETN]);
+        let vm = TestVm::from_bytes(&image.build()).unwrap();
+        // A filterscript with the same `OnPlayerConnect`, and a `main` of
+        // its own.
+        let mut side_image = ImageBuilder::new();
+        side_image
+            .public("OnPlayerConnect")
+            .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        side_image.main().code(&[op::PROC, op::ZERO_PRI, op::RETN]);
+        let side = TestVm::from_bytes(&side_image.build()).unwrap();
+
+        let mut server = MockServer::new();
+        server.load(super::__omp_component::ComponentEntryPoint);
+        // `info!` reaches the server console through `ICore`'s logger.
+        let log = server.take_log();
+        assert!(
+            log.iter()
+                .any(|l| l.ends_with("Counter plugin loaded. Max=100")),
+            "{log:?}"
+        );
+        server.init();
+        assert_eq!(server.event_handlers(), 1);
+        // A `define` handler listens on the player pool.
+        assert_eq!(server.connect_handlers(), 1);
+
+        // open.mp loads the scripts before `onReady`, while
+        // `getAmxFunctions()` still returns null: the AMXs wait in the queue.
+        // The side script comes first; `mainScript()` still names the gamemode.
+        server.load_side_script(&side);
+        server.load_script(&vm);
+        assert!(matches!(vm.call("Test", &[]), Err(AmxError::NotFound)));
+
+        // `onReady` drains the queue and starts the `on_tick` timer.
+        server.ready();
+        assert_eq!(vm.call("Test", &[]).unwrap(), 2);
+        assert_eq!(
+            server.timers(),
+            [MockTimer {
+                interval_ms: 5,
+                repeating: true,
+                killed: false,
+            }]
+        );
+        server.tick();
+        assert_eq!(vm.call("Mine", &[]).unwrap(), 2);
+
+        // Unloading drops the script's `ScriptStats`; the plugin-wide count
+        // carries on.
+        server.unload_script(&vm);
+        server.load_script(&vm);
+        assert_eq!(vm.call("Mine", &[]).unwrap(), 0);
+        assert_eq!(vm.call("Test", &[]).unwrap(), 4);
+        assert_eq!(vm.call("Mine", &[]).unwrap(), 2);
+
+        // `Step` is parsed by the derived `AmxCell`; 5 is not a variant.
+        assert_eq!(vm.call("Step", &[1]).unwrap(), 5);
+        assert_eq!(vm.call("Step", &[-1]).unwrap(), 4);
+        assert_eq!(vm.call("Step", &[5]).unwrap(), 0);
+        assert_eq!(vm.call("Mine", &[]).unwrap(), 3);
+
+        // `Counter_Sync` reads `E_MAX` (3 clamps the count) and writes the
+        // whole array back, `E_LABEL` included.
+        assert_eq!(vm.call("Sync", &[]).unwrap(), 1);
+        let cell = |offset: i32| *vm.amx().get_ref::<i32>(info + offset * 4).unwrap();
+        assert_eq!((cell(0), cell(1), cell(2)), (3, 3, 1));
+        assert_eq!(vm.read_string(label).as_deref(), Some("3/3"));
+
+        // The `after` hook sees the gamemode's `0` and answers `/count` itself.
+        assert_eq!(vm.call("OnPlayerCommandText", &[0, count_cmd]).unwrap(), 1);
+        assert_eq!(vm.call("OnPlayerCommandText", &[0, help_cmd]).unwrap(), 0);
+        assert!(
+            server
+                .take_log()
+                .iter()
+                .any(|l| l.ends_with("player 0 typed /help"))
+        );
+
+        // The script has no `OnPlayerDisconnect`, and still has none; the
+        // player pool delivers it to the `define` handler all the same.
+        assert!(matches!(
+            vm.call("OnPlayerDisconnect", &[3, 1]),
+            Err(AmxError::NotFound)
+        ));
+        server.connect_player(3);
+        server.disconnect_player(3, 1);
+        assert!(
+            server
+                .take_log()
+                .iter()
+                .any(|l| l.ends_with("player 3 left (reason 1)"))
+        );
+        assert!(matches!(
+            vm.call("OnPlayerDeath", &[0, 1, 2]),
+            Err(AmxError::NotFound)
+        ));
+        let order: Vec<_> = samp::events::handler_order(&vm.amx())
+            .iter()
+            .map(|event| (event.handler_name, event.priority))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("counter::log_command", 10),
+                ("counter::on_player_command_text", 0),
+                ("counter::on_player_connect", 0),
+            ]
+        );
+
+        // `scope = gamemode`: the side script runs its own `OnPlayerConnect`
+        // without the handler, which only logs for the gamemode.
+        assert_eq!(vm.amx().kind(), AmxKind::Gamemode);
+        assert_eq!(side.amx().kind(), AmxKind::SideScript);
+        assert_eq!(side.call("OnPlayerConnect", &[5]).unwrap(), 1);
+        assert!(
+            !server
+                .take_log()
+                .iter()
+                .any(|l| l.ends_with("player 5 connected"))
+        );
+        assert_eq!(vm.call("OnPlayerConnect", &[5]).unwrap(), 1);
+        assert!(
+            server
+                .take_log()
+                .iter()
+                .any(|l| l.ends_with("player 5 connected"))
+        );
+        // Nor does `OnPlayerDisconnect`: the scoped handler runs once, for
+        // the gamemode.
+        assert!(samp::events::handler_order(&side.amx()).is_empty());
+        server.connect_player(4);
+        server.disconnect_player(4, 2);
+        let log = server.take_log();
+        assert_eq!(
+            log.iter()
+                .filter(|l| l.ends_with("player 4 left (reason 2)"))
+                .count(),
+            1,
+            "{log:?}"
+        );
+        server.unload_script(&side);
+
+        // A subscription made from a native sees the already-loaded script
+        // until it is dropped.
+        assert_eq!(vm.call("OnPlayerSpawn", &[0]).unwrap(), 1);
+        assert_eq!(vm.call("Watch", &[1]).unwrap(), 0);
+        assert_eq!(vm.call("OnPlayerSpawn", &[0]).unwrap(), 1);
+        assert_eq!(vm.call("OnPlayerSpawn", &[1]).unwrap(), 1);
+        assert_eq!(vm.call("Watch", &[0]).unwrap(), 2);
+        assert_eq!(vm.call("OnPlayerSpawn", &[0]).unwrap(), 1);
+        assert_eq!(vm.call("Watch", &[1]).unwrap(), 2);
+        // Scripts loaded later resolve it as well.
+        server.unload_script(&vm);
+        server.load_script(&vm);
+        assert_eq!(vm.call("OnPlayerSpawn", &[0]).unwrap(), 1);
+        assert_eq!(vm.call("Watch", &[0]).unwrap(), 3);
+
+        server.unload_script(&vm);
+        server.free();
+        assert!(server.timers()[0].killed);
+        assert_eq!(server.event_handlers(), 0);
+        assert_eq!(server.connect_handlers(), 0);
+    }
+}
@@ -5,12 +5,19 @@
 //! - `initialize_plugin!(type: T, ...)` — constructor via `Default::default()`
 //! - `AmxString` with `Deref<Target=str>` — `&str` methods without allocation
 //! - `UnsizedBuffer::write_str` — write output string in a single step
+//! - `#[native(async = "...")]` — build the reply on a worker thread and
+//!   deliver it to a Pawn callback on the next tick
+//! - `Variadic` — a trailing `...` parameter read as strings
 //!
 //! Natives exposed to PAWN:
 //! ```pawn
 //! native Hello_Greet(const name[], greeting[] = "", size = sizeof(greeting));
+//! native Hello_GreetAsync(playerid, const name[]);
+//! native Hello_GreetMany(greeting[], size = sizeof greeting, ...);
+//! forward OnHelloGreeted(playerid, const greeting[]);
 //! ```
 
+use samp::args::Variadic;
 use samp::prelude::*;
 use samp::{SampPlugin, initialize_plugin, native};
 
@@ -33,20 +40,234 @@ impl Hello {
         size: usize,
     ) -> AmxResult<bool> {
         // AmxString implements Deref<Target=str> — &str methods available directly
-        let msg = if name.is_empty() {
-            "Hello, Anonymous!".to_string()
-        } else if name.starts_with("Admin") {
-            format!("[ADMIN] Welcome, {}!", &**name)
-        } else {
-            format!("Hello, {}! ({} letters)", &**name, name.len())
+        greeting.write_str(size, &greeting_for(name))?;
+        Ok(true)
+    }
+
+    /// Greets every name passed after `size` in one message. Returns how
+    /// many names there were.
+    ///
+    /// ```pawn
+    /// new msg[64];
+    /// Hello_GreetMany(msg, sizeof msg, "Alice", "Bob");
+    /// // msg == "Hello, Alice and Bob!"
+    /// ```
+    #[native(name = "Hello_GreetMany")]
+    fn greet_many(
+        _amx: &Amx,
+        greeting: UnsizedBuffer,
+        size: usize,
+        names: Variadic,
+    ) -> AmxResult<i32> {
+        // Pawn passes `...` by reference; each name is read where it lives.
+        let names = names
+            .iter()
+            .map(|name| name.string().map(|name| name.to_string()))
+            .collect::<AmxResult<Vec<String>>>()?;
+        let message = match names.split_last() {
+            None => "Hello, nobody!".to_string(),
+            Some((last, [])) => format!("Hello, {last}!"),
+            Some((last, rest)) => format!("Hello, {} and {last}!", rest.join(", ")),
         };
+        greeting.write_str(size, &message)?;
+        Ok(i32::try_from(names.len()).unwrap_or(i32::MAX))
+    }
 
-        greeting.write_str(size, &msg)?;
-        Ok(true)
+    /// Same greeting, built off the server thread. Returns immediately; the
+    /// result arrives in `OnHelloGreeted` on a later server tick.
+    ///
+    /// ```pawn
+    /// Hello_GreetAsync(playerid, "World");
+    ///
+    /// public OnHelloGreeted(playerid, const greeting[]) {
+    ///     SendClientMessage(playerid, -1, greeting);
+    /// }
+    /// ```
+    #[native(name = "Hello_GreetAsync", async = "OnHelloGreeted")]
+    fn greet_async(playerid: i32, name: String) -> (i32, String) {
+        (playerid, greeting_for(&name))
+    }
+}
+
+fn greeting_for(name: &str) -> String {
+    if name.is_empty() {
+        "Hello, Anonymous!".to_string()
+    } else if name.starts_with("Admin") {
+        format!("[ADMIN] Welcome, {name}!")
+    } else {
+        format!("Hello, {name}! ({} letters)", name.len())
     }
 }
 
 initialize_plugin!(
     type: Hello,
-    natives: [Hello::greet],
+    natives: [Hello::greet, Hello::greet_many, Hello::greet_async],
+    forwards: ["OnHelloGreeted(playerid, const greeting[])"],
 );
+
+#[cfg(test)]
+mod tests {
+    /// The `.inc` is derived from the `#[native]` signatures — write it to
+    /// `pawno/include/` with `samp::pawn_include!().write(path)`.
+    #[test]
+    fn pawn_include_declares_greet() {
+        assert_eq!(
+            samp::pawn_include!().natives(),
+            [
+                "native bool:Hello_Greet(const name[], greeting[], size = sizeof greeting);",
+                "native Hello_GreetMany(greeting[], size = sizeof greeting, ...);",
+                "native Hello_GreetAsync(playerid, const name[]);",
+            ]
+        );
+        // `Hello_GreetAsync` declares `OnHelloGreeted(arg0, const arg1[])`;
+        // the `forwards: [...]` entry names its parameters instead.
+        assert_eq!(
+            samp::pawn_include!().forwards(),
+            ["forward OnHelloGreeted(playerid, const greeting[]);"]
+        );
+    }
+
+    /// Loads the plugin the way the server does and calls `Hello_Greet`,
+    /// `Hello_GreetMany` and `Hello_GreetAsync` from a script running on the
+    /// test VM.
+    #[test]
+    fn greet_from_pawn() {
+        use samp::consts::Supports;
+        use samp::test_vm::{ImageBuilder, TestVm, opcodes as op, server_data};
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        // public Test() { return Hello_Greet("World", out); }
+        let mut image = ImageBuilder::new();
+        let greet = image.native("Hello_Greet");
+        let name = image.string("World");
+        let out = image.zeroed(64);
+        image.public("Test").code(&[
+            op::PROC,
+            op::PUSH_C,
+            64,
+            op::PUSH_C,
+            out,
+            op::PUSH_C,
+            name,
+            op::PUSH_C,
+            12,
+            op::SYSREQ_C,
+            greet,
+            op::STACK,
+            16,
+            op::RETN,
+        ]);
+        // public TestMany() { return Hello_GreetMany(many, 64, "World", "Pawn", "Rust"); }
+        let greet_many = image.native("Hello_GreetMany");
+        let pawn = image.string("Pawn");
+        let rust = image.string("Rust");
+        let many = image.zeroed(64);
+        image.public("TestMany").code(&[
+            op::PROC,
+            op::PUSH_C,
+            rust,
+            op::PUSH_C,
+            pawn,
+            op::PUSH_C,
+            name,
+            op::PUSH_C,
+            64,
+            op::PUSH_C,
+            many,
+            op::PUSH_C,
+            20,
+            op::SYSREQ_C,
+            greet_many,
+            op::STACK,
+            24,
+            op::RETN,
+        ]);
+        // public TestAsync(playerid) { return Hello_GreetAsync(playerid, "World"); }
+        let greet_async = image.native("Hello_GreetAsync");
+        image.public("TestAsync").code(&[
+            op::PROC,
+            op::PUSH_C,
+            name,
+            op::PUSH_S,
+            12,
+            op::PUSH_C,
+            8,
+            op::SYSREQ_C,
+            greet_async,
+            op::STACK,
+            12,
+            op::RETN,
+        ]);
+        // public OnHelloGreeted(playerid, const greeting[]) — copies both
+        // arguments into globals; "Hello, World! (5 letters)" is 26 cells.
+        let player = image.zeroed(1);
+        let delivered = image.zeroed(64);
+        image.public("OnHelloGreeted").code(&[
+            op::PROC,
+            op::LOAD_S_PRI,
+            12,
+            op::STOR_PRI,
+            player,
+            op::LOAD_S_PRI,
+            16,
+            op::CONST_ALT,
+            delivered,
+            op::MOVS,
+            26 * 4,
+            op::RETN,
+        ]);
+        let vm = TestVm::from_bytes(&image.build()).unwrap();
+
+        // An async native keeps `ProcessTick` running for result delivery.
+        let supports = Supports::from_bits_truncate(super::Supports());
+        assert!(supports.contains(Supports::PROCESS_TICK));
+        super::Load(server_data());
+        super::AmxLoad(vm.as_ptr());
+
+        assert_eq!(vm.call("Test", &[]).unwrap(), 1);
+        assert_eq!(
+            vm.read_string(out).as_deref(),
+            Some("Hello, World! (5 letters)")
+        );
+
+        assert_eq!(vm.call("TestMany", &[]).unwrap(), 3);
+        assert_eq!(
+            vm.read_string(many).as_deref(),
+            Some("Hello, World, Pawn and Rust!")
+        );
+
+        assert_eq!(vm.call("TestAsync", &[7]).unwrap(), 1);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while vm.read_string(delivered).is_none_or(|s| s.is_empty()) {
+            assert!(Instant::now() < deadline, "OnHelloGreeted never fired");
+            std::thread::sleep(Duration::from_millis(1));
+            super::ProcessTick();
+        }
+        assert_eq!(vm.amx().get_ref::<i32>(player).map(|r| *r).ok(), Some(7));
+        assert_eq!(
+            vm.read_string(delivered).as_deref(),
+            Some("Hello, World! (5 letters)")
+        );
+
+        // `run_with_plugin` hands out the plugin only as its own type.
+        #[derive(samp::SampPlugin, Default)]
+        struct NotHello;
+        let ran = Arc::new(AtomicUsize::new(0));
+        let main = samp::plugin::main_thread();
+        let hits = Arc::clone(&ran);
+        main.run_with_plugin(move |_: &mut super::Hello| {
+            hits.fetch_add(1, Ordering::SeqCst);
+        });
+        let hits = Arc::clone(&ran);
+        main.run_with_plugin(move |_: &mut NotHello| {
+            hits.fetch_add(10, Ordering::SeqCst);
+        });
+        super::ProcessTick();
+        assert_eq!(ran.load(Ordering::SeqCst), 1);
+
+        super::AmxUnload(vm.as_ptr());
+        super::Unload();
+    }
+}
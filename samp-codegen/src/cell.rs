@@ -0,0 +1,236 @@
+//! Implementation of the `#[derive(AmxCell)]` proc macro.
+//!
+//! Two shapes are accepted:
+//! - **fieldless enums** — each variant maps to its discriminant (explicit or
+//!   implicit, exactly as `Variant as i32` would give). A cell that matches no
+//!   variant is a parse error, unless one variant is marked
+//!   `#[amx(fallback)]`, in which case it becomes that variant;
+//! - **newtypes** — a struct with a single field delegates every conversion to
+//!   the field's type (`struct PlayerId(i32)`).
+//!
+//! Both get `AmxCell` (arguments and return values of `#[native]`) and
+//! `samp::tasks::AsyncArg` (arguments of async natives). `CellConvert` is
+//! infallible, so enums only get it when they have a fallback variant;
+//! newtypes always get it and require it from the field's type.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DataEnum, DeriveInput, Error, Fields, Ident, Result, parse_macro_input,
+};
+
+pub fn derive_amx_cell(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    // The `'amx` lifetime of `AmxCell` is the only one the impls can carry; a
+    // type with parameters of its own would need bounds the derive cannot guess.
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[derive(AmxCell)]` does not support generic types",
+        ));
+    }
+
+    let name = &input.ident;
+    match &input.data {
+        Data::Enum(data) => expand_enum(name, data),
+        Data::Struct(data) => expand_newtype(name, &data.fields),
+        Data::Union(_) => Err(Error::new(
+            name.span(),
+            "`#[derive(AmxCell)]` supports fieldless enums and single-field structs",
+        )),
+    }
+}
+
+fn expand_enum(name: &Ident, data: &DataEnum) -> Result<TokenStream2> {
+    if data.variants.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`#[derive(AmxCell)]` needs at least one variant",
+        ));
+    }
+
+    let mut fallback = None;
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.fields.span(),
+                "`#[derive(AmxCell)]` only supports fieldless variants",
+            ));
+        }
+        if is_fallback(&variant.attrs)? {
+            if fallback.is_some() {
+                return Err(Error::new(
+                    variant.span(),
+                    "only one variant can be marked `#[amx(fallback)]`",
+                ));
+            }
+            fallback = Some(&variant.ident);
+        }
+    }
+
+    let variants: Vec<&Ident> = data.variants.iter().map(|v| &v.ident).collect();
+    let unknown = match fallback {
+        Some(variant) => quote!(Ok(Self::#variant)),
+        None => quote!(Err(samp::error::AmxError::Params)),
+    };
+
+    let cell_convert = fallback.map(|fallback| {
+        quote! {
+            impl samp::cell::CellConvert for #name {
+                fn from_cell(raw: i32) -> Self {
+                    #( if raw == Self::#variants as i32 { return Self::#variants; } )*
+                    Self::#fallback
+                }
+
+                fn into_cell(self) -> i32 {
+                    samp::cell::AmxCell::as_cell(&self)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl<'amx> samp::cell::AmxCell<'amx> for #name {
+            fn from_raw(_amx: &'amx samp::amx::Amx, cell: i32) -> samp::error::AmxResult<Self> {
+                #( if cell == Self::#variants as i32 { return Ok(Self::#variants); } )*
+                #unknown
+            }
+
+            fn as_cell(&self) -> i32 {
+                match self {
+                    #( Self::#variants => Self::#variants as i32, )*
+                }
+            }
+        }
+
+        #cell_convert
+
+        impl samp::tasks::AsyncArg for #name {
+            fn from_args(args: &mut samp::args::Args<'_>) -> Option<Self> {
+                args.next_arg::<Self>()
+            }
+        }
+    })
+}
+
+fn expand_newtype(name: &Ident, fields: &Fields) -> Result<TokenStream2> {
+    let mut iter = fields.iter();
+    let (Some(field), None) = (iter.next(), iter.next()) else {
+        return Err(Error::new(
+            name.span(),
+            "`#[derive(AmxCell)]` on a struct needs exactly one field",
+        ));
+    };
+
+    let ty = &field.ty;
+    let (member, wrap) = match &field.ident {
+        Some(ident) => (quote!(#ident), quote!(Self { #ident: inner })),
+        None => (quote!(0), quote!(Self(inner))),
+    };
+
+    Ok(quote! {
+        impl<'amx> samp::cell::AmxCell<'amx> for #name {
+            fn from_raw(amx: &'amx samp::amx::Amx, cell: i32) -> samp::error::AmxResult<Self> {
+                let inner = <#ty as samp::cell::AmxCell<'amx>>::from_raw(amx, cell)?;
+                Ok(#wrap)
+            }
+
+            fn as_cell(&self) -> i32 {
+                samp::cell::AmxCell::as_cell(&self.#member)
+            }
+        }
+
+        impl samp::cell::CellConvert for #name {
+            fn from_cell(raw: i32) -> Self {
+                let inner = <#ty as samp::cell::CellConvert>::from_cell(raw);
+                #wrap
+            }
+
+            fn into_cell(self) -> i32 {
+                samp::cell::CellConvert::into_cell(self.#member)
+            }
+        }
+
+        impl samp::tasks::AsyncArg for #name {
+            fn from_args(args: &mut samp::args::Args<'_>) -> Option<Self> {
+                args.next_arg::<Self>()
+            }
+        }
+    })
+}
+
+/// Whether the variant carries `#[amx(fallback)]`. Any other `amx` option is
+/// rejected so a typo does not silently turn into a parse error at runtime.
+fn is_fallback(attrs: &[Attribute]) -> Result<bool> {
+    let mut fallback = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("amx")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("fallback") {
+                fallback = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown `amx` option, expected `fallback`"))
+            }
+        })?;
+    }
+    Ok(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_str(src: &str) -> Result<String> {
+        let input: DeriveInput = syn::parse_str(src).expect("valid item");
+        expand(&input).map(|tokens| tokens.to_string())
+    }
+
+    fn error(src: &str) -> String {
+        expand_str(src).expect_err("derive should fail").to_string()
+    }
+
+    #[test]
+    fn enum_without_fallback_rejects_unknown_cells() {
+        let out = expand_str("enum Slot { Melee = 1, Pistol = 2 }").unwrap();
+        assert!(out.contains("Err (samp :: error :: AmxError :: Params)"));
+        assert!(!out.contains("CellConvert for Slot"));
+        assert!(out.contains("AsyncArg for Slot"));
+    }
+
+    #[test]
+    fn enum_fallback_covers_unknown_cells_and_enables_cell_convert() {
+        let out = expand_str("enum Slot { Melee = 1, #[amx(fallback)] Unknown = -1 }").unwrap();
+        assert!(out.contains("Ok (Self :: Unknown)"));
+        assert!(out.contains("CellConvert for Slot"));
+    }
+
+    #[test]
+    fn newtype_delegates_to_the_field() {
+        let out = expand_str("struct PlayerId(i32);").unwrap();
+        assert!(out.contains("< i32 as samp :: cell :: AmxCell < 'amx >> :: from_raw"));
+        assert!(out.contains("self . 0"));
+
+        let out = expand_str("struct PlayerId { id: i32 }").unwrap();
+        assert!(out.contains("self . id"));
+    }
+
+    #[test]
+    fn unsupported_shapes_are_rejected() {
+        assert!(error("enum E { A(i32) }").contains("fieldless"));
+        assert!(error("enum E {}").contains("at least one variant"));
+        assert!(error("struct P(i32, i32);").contains("exactly one field"));
+        assert!(error("struct P;").contains("exactly one field"));
+        assert!(error("struct P<T>(T);").contains("generic"));
+        assert!(error("enum E { #[amx(fallback)] A, #[amx(fallback)] B }").contains("only one"));
+        assert!(error("enum E { #[amx(default)] A }").contains("unknown `amx` option"));
+    }
+}
@@ -8,7 +8,10 @@
 //! For each marked method this macro generates:
 //! - a **handler wrapper** `__samp_event_<fn>(amx, args)` that parses the
 //!   callback arguments (via [`Args`], exactly like `#[native]`) and invokes
-//!   the original method, returning its value as an AMX cell;
+//!   the original method, returning its value as an AMX cell. With `after`
+//!   the wrapper also takes the public's return value
+//!   (`__samp_event_<fn>(amx, args, retval)`) and hands it to the method as
+//!   the parameter right after `amx`;
 //! - a **registration function** `__samp_event_reg_<fn>()` that produces a
 //!   [`samp::events::EventInfo`] (Pawn callback name + wrapper pointer)
 //!   consumed by `initialize_plugin!(events: [...])`.
@@ -26,23 +29,37 @@ use quote::{quote, quote_spanned};
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 use syn::{
-    Error, FnArg, Ident, ItemFn, LitStr, Pat, Result as SynResult, ReturnType, Token, Type,
+    Error, Expr, FnArg, Ident, ItemFn, LitStr, Pat, Result as SynResult, ReturnType, Token, Type,
     parse_macro_input,
 };
 
 use crate::{EVENT_PREFIX, EVENT_REG_PREFIX};
 
-/// Args of `#[event(...)]`: `name = "..."` (the Pawn callback name) and an
-/// optional `raw` flag (hand the handler the `Args` cursor unparsed).
+/// Args of `#[event(...)]`: `name = "..."` (the Pawn callback name), an
+/// optional `raw` flag (hand the handler the `Args` cursor unparsed), an
+/// optional `after` flag (run after the public, with its return value), an
+/// optional `define` flag (deliver the callback even when the script does
+/// not declare it), an optional `priority = N` (any `i32` constant
+/// expression; higher runs first) and an optional `scope = gamemode |
+/// filterscripts | all` (which scripts the handler runs for; `all` when
+/// omitted).
 struct EventName {
     name: String,
     raw: bool,
+    after: bool,
+    define: bool,
+    priority: Option<Expr>,
+    scope: Option<Ident>,
 }
 
 impl Parse for EventName {
     fn parse(input: ParseStream) -> SynResult<Self> {
         let mut name = String::new();
         let mut raw = false;
+        let mut after = false;
+        let mut define = false;
+        let mut priority = None;
+        let mut scope: Option<Ident> = None;
 
         while !input.is_empty() {
             let ident: Ident = input.parse()?;
@@ -63,10 +80,27 @@ impl Parse for EventName {
                 name = value;
             } else if ident == "raw" {
                 raw = true;
+            } else if ident == "after" {
+                after = true;
+            } else if ident == "define" {
+                define = true;
+            } else if ident == "priority" {
+                let _: Token![=] = input.parse()?;
+                priority = Some(input.parse()?);
+            } else if ident == "scope" {
+                let _: Token![=] = input.parse()?;
+                let value: Ident = input.parse()?;
+                if !["gamemode", "filterscripts", "all"].contains(&value.to_string().as_str()) {
+                    return Err(Error::new(
+                        value.span(),
+                        "`scope` must be `gamemode`, `filterscripts` or `all`",
+                    ));
+                }
+                scope = Some(value);
             } else {
                 return Err(Error::new(
                     ident.span(),
-                    "Unexpected argument name. `#[event]` supports only \"name\" and \"raw\".",
+                    "Unexpected argument name. `#[event]` supports only \"name\", \"raw\", \"after\", \"define\", \"priority\" and \"scope\".",
                 ));
             }
 
@@ -77,7 +111,14 @@ impl Parse for EventName {
             return Err(input.error("`#[event]` requires `name = \"OnSomething\"`"));
         }
 
-        Ok(EventName { name, raw })
+        Ok(EventName {
+            name,
+            raw,
+            after,
+            define,
+            priority,
+            scope,
+        })
     }
 }
 
@@ -95,12 +136,30 @@ pub fn create_event(args: TokenStream, input: TokenStream) -> TokenStream {
 
     // Accept both `fn(&mut self, amx: &Amx, ...)` and `fn(amx: &Amx, ...)`.
     let has_self = matches!(origin_fn.sig.inputs.first(), Some(FnArg::Receiver(_)));
-    let skip_count = if has_self { 2 } else { 1 };
+    let mut skip_count = if has_self { 2 } else { 1 };
+
+    // `after` handlers take the public's return value right after `amx`.
+    let (retval_param, retval_binding, retval_ident) = if event.after {
+        let (binding, ident) = match gen_retval_binding(&origin_fn, skip_count) {
+            Ok(retval) => retval,
+            Err(err) => return err.into_compile_error().into(),
+        };
+        skip_count += 1;
+        (quote!(, retval: i32), binding, quote!(#ident,))
+    } else {
+        Default::default()
+    };
 
     let fn_input_idents = gen_fn_input_idents(&origin_fn, skip_count);
     let args_parsing = gen_args_parsing(&origin_fn, skip_count, event.raw, callback_name);
     let plugin_binding = gen_plugin_binding(has_self);
-    let call_origin = gen_call_origin(origin_name, has_self, event.raw, &fn_input_idents);
+    let call_origin = gen_call_origin(
+        origin_name,
+        has_self,
+        event.raw,
+        &retval_ident,
+        &fn_input_idents,
+    );
     let invocation = gen_invocation(&origin_fn, &call_origin, callback_name);
 
     // The wrapper is a plain Rust fn (not `extern "C"`): the dispatcher calls it
@@ -108,9 +167,11 @@ pub fn create_event(args: TokenStream, input: TokenStream) -> TokenStream {
     let wrapper = quote! {
         #vis fn #wrapper_name(
             amx: &samp::amx::Amx,
-            args: &mut samp::args::Args,
+            args: &mut samp::args::Args
+            #retval_param
         ) -> samp::events::EventReturn {
             #plugin_binding
+            #retval_binding
             #args_parsing
             unsafe {
                 #invocation
@@ -118,7 +179,7 @@ pub fn create_event(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
 
-    let reg = gen_reg_event(vis, &reg_name, &wrapper_name, callback_name);
+    let reg = gen_reg_event(vis, &reg_name, &wrapper_name, origin_name, &event);
 
     let generated = quote! {
         #origin_fn
@@ -155,6 +216,34 @@ fn gen_fn_input_idents(origin_fn: &ItemFn, skip_count: usize) -> Vec<proc_macro2
         .collect()
 }
 
+/// For an `after` handler, the binding of the return-value parameter (the one
+/// right after `amx`), converted from the raw cell through `CellConvert` so it
+/// may be declared as `i32`, `bool`, `f32`, … — plus its identifier.
+fn gen_retval_binding(
+    origin_fn: &ItemFn,
+    skip_count: usize,
+) -> SynResult<(proc_macro2::TokenStream, Ident)> {
+    let Some(FnArg::Typed(pat_type)) = origin_fn.sig.inputs.iter().nth(skip_count) else {
+        return Err(Error::new(
+            origin_fn.sig.inputs.span(),
+            "`#[event(after)]` handlers take the callback's return value after `amx`",
+        ));
+    };
+    let Pat::Ident(pat_ident) = &*pat_type.pat else {
+        return Err(Error::new(
+            pat_type.pat.span(),
+            "the return value parameter must be a plain identifier",
+        ));
+    };
+    let ident = &pat_ident.ident;
+    let ty = &pat_type.ty;
+    let binding = quote_spanned! {
+        pat_type.span() =>
+            let #ident: #ty = <#ty as samp::cell::CellConvert>::from_cell(retval);
+    };
+    Ok((binding, ident.clone()))
+}
+
 /// Generates the `let Some(arg) = args.next_arg() else { log; return 0; };` for
 /// each callback argument. A parse failure means the callback signature does not
 /// match what the gamemode actually pushed — logged and skipped.
@@ -213,22 +302,24 @@ fn gen_plugin_binding(has_self: bool) -> proc_macro2::TokenStream {
 /// Form of the call: `plugin.as_mut().method(amx, ...)` for methods,
 /// `Self::function(amx, ...)` for associated functions. In `raw` mode the
 /// handler receives the `Args` cursor directly instead of parsed arguments.
+/// `retval` is `retval_ident,` for an `after` handler and empty otherwise.
 fn gen_call_origin(
     origin_name: &Ident,
     has_self: bool,
     raw: bool,
+    retval: &proc_macro2::TokenStream,
     fn_input_idents: &[proc_macro2::TokenStream],
 ) -> proc_macro2::TokenStream {
     if raw {
         if has_self {
-            quote!(plugin.as_mut().#origin_name(amx, args))
+            quote!(plugin.as_mut().#origin_name(amx, #retval args))
         } else {
-            quote!(Self::#origin_name(amx, args))
+            quote!(Self::#origin_name(amx, #retval args))
         }
     } else if has_self {
-        quote!(plugin.as_mut().#origin_name(amx, #(#fn_input_idents),*))
+        quote!(plugin.as_mut().#origin_name(amx, #retval #(#fn_input_idents),*))
     } else {
-        quote!(Self::#origin_name(amx, #(#fn_input_idents),*))
+        quote!(Self::#origin_name(amx, #retval #(#fn_input_idents),*))
     }
 }
 
@@ -285,21 +376,43 @@ fn gen_invocation(
 }
 
 /// `__samp_event_reg_*` function producing the [`EventInfo`] (callback name +
-/// wrapper pointer). Consumed by `initialize_plugin!(events: [...])`.
+/// wrapper pointer, tagged with when it runs, its priority and the method
+/// path). Consumed by `initialize_plugin!(events: [...])`.
 ///
 /// [`EventInfo`]: samp::events::EventInfo
 fn gen_reg_event(
     vis: &syn::Visibility,
     reg_name: &Ident,
     wrapper_name: &Ident,
-    callback_name: &str,
+    origin_name: &Ident,
+    event: &EventName,
 ) -> proc_macro2::TokenStream {
+    let callback_name = &event.name;
+    let hook = if event.after {
+        quote!(After)
+    } else {
+        quote!(Before)
+    };
+    let priority = match &event.priority {
+        Some(priority) => quote!(#priority),
+        None => quote!(0),
+    };
+    let define = event.define;
+    let scope = match event.scope.as_ref().map(Ident::to_string).as_deref() {
+        Some("gamemode") => quote!(Gamemode),
+        Some("filterscripts") => quote!(Filterscripts),
+        _ => quote!(All),
+    };
     quote! {
         #vis fn #reg_name() -> samp::events::EventInfo {
-            samp::events::EventInfo {
-                name: #callback_name,
-                handler: Self::#wrapper_name,
-            }
+            samp::events::EventInfo::new(
+                #callback_name,
+                samp::events::EventHook::#hook(Self::#wrapper_name),
+                concat!(module_path!(), "::", stringify!(#origin_name)),
+            )
+            .priority(#priority)
+            .define(#define)
+            .scope(samp::events::EventScope::#scope)
         }
     }
 }
@@ -0,0 +1,356 @@
+//! Pawn declaration rendering for the `.inc` generator.
+//!
+//! `#[native]` resolves the Pawn prototype of each native at proc-macro time
+//! (the Rust signature is all the information there is) and emits it through
+//! the `__samp_inc_*` function, which adds it to a `samp::include::Include` —
+//! along with the `forward` of the callback an async native calls.
+//! `initialize_plugin!` passes the include through every native's function.
+//!
+//! The mapping is purely syntactic — it looks at the last path segment of each
+//! argument type, so type aliases or custom `AmxCell` impls fall back to an
+//! untagged cell (`name`), which is what Pawn assumes by default anyway.
+
+use quote::ToTokens;
+use syn::{FnArg, GenericArgument, Pat, PathArguments, ReturnType, Type};
+
+/// Renders `native Tag:Name(params);` for a `#[native]` signature.
+/// `raw` natives parse their own arguments, so the prototype is `(...)`.
+pub(crate) fn native_decl(name: &str, inputs: &[&FnArg], output: &ReturnType, raw: bool) -> String {
+    let params = if raw {
+        "...".to_owned()
+    } else {
+        render_params(inputs).join(", ")
+    };
+    format!("native {}{name}({params});", return_tag(output))
+}
+
+/// Renders `forward Callback(args);` for the callback of an async native from
+/// what its body returns: nothing for `()`, one parameter per tuple element,
+/// one for any other value, after unwrapping `AmxResult<T>` / `Result<T, E>`.
+/// The Rust side has no names for them, so they are `arg0`, `arg1`, …
+pub(crate) fn callback_forward(callback: &str, output: &ReturnType) -> String {
+    let values: Vec<&Type> = match output {
+        ReturnType::Default => Vec::new(),
+        ReturnType::Type(_, ty) => {
+            let ty = match last_segment(ty).as_deref() {
+                Some("Result" | "AmxResult") => first_generic(ty).unwrap_or(ty),
+                _ => ty,
+            };
+            match ty {
+                Type::Tuple(tuple) => tuple.elems.iter().collect(),
+                Type::Paren(paren) => vec![&*paren.elem],
+                _ => vec![ty],
+            }
+        }
+    };
+    let params: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(
+            |(index, ty)| match last_segment(strip_reference(ty)).as_deref() {
+                Some("String" | "str") => format!("const arg{index}[]"),
+                _ => format!("{}arg{index}", tag_of(ty)),
+            },
+        )
+        .collect();
+    format!("forward {callback}({});", params.join(", "))
+}
+
+/// Maps each argument to its Pawn form. An `UnsizedBuffer` followed by a
+/// `usize` becomes the canonical `dest[], size = sizeof dest` pair.
+fn render_params(inputs: &[&FnArg]) -> Vec<String> {
+    let typed: Vec<(String, &Type)> = inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => {
+                    Some((pawn_ident(&pat_ident.ident.to_string()), &*pat_type.ty))
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let mut params = Vec::with_capacity(typed.len());
+    let mut last_buffer: Option<&str> = None;
+    for (name, ty) in &typed {
+        // `&mut T` is an enum-structured array (`data[E_DATA]`).
+        if matches!(ty, Type::Reference(r) if r.mutability.is_some()) {
+            params.push(format!("{name}[]"));
+            last_buffer = None;
+            continue;
+        }
+        let ty = strip_reference(ty);
+        let segment = last_segment(ty);
+        match segment.as_deref() {
+            Some("usize") if last_buffer.is_some() => {
+                let buffer = last_buffer.take().unwrap_or_default();
+                params.push(format!("{name} = sizeof {buffer}"));
+                continue;
+            }
+            Some("AmxString" | "EncodedString" | "String") => {
+                params.push(format!("const {name}[]"))
+            }
+            Some("UnsizedBuffer") => {
+                params.push(format!("{name}[]"));
+                last_buffer = Some(name);
+                continue;
+            }
+            Some("Buffer" | "StructRef") => params.push(format!("{name}[]")),
+            Some("Buffer2D") => params.push(format!("{name}[][]")),
+            Some("ArrayView") => match row_len(ty) {
+                Some(len) => params.push(format!("{name}[][{len}]")),
+                None => params.push(format!("{name}[][]")),
+            },
+            Some("Variadic") => params.push("...".to_owned()),
+            Some("Ref") => {
+                let tag = first_generic(ty).map(tag_of).unwrap_or_default();
+                params.push(format!("&{tag}{name}"));
+            }
+            _ => params.push(format!("{}{name}", tag_of(ty))),
+        }
+        last_buffer = None;
+    }
+    params
+}
+
+/// Tag of the native's return value: unwraps `AmxResult<T>` / `Result<T, E>`
+/// so `AmxResult<f32>` still yields `Float:`.
+fn return_tag(output: &ReturnType) -> &'static str {
+    let ReturnType::Type(_, ty) = output else {
+        return "";
+    };
+    let ty = strip_reference(ty);
+    match last_segment(ty).as_deref() {
+        Some("Result" | "AmxResult") => first_generic(ty).map(tag_of).unwrap_or_default(),
+        _ => tag_of(ty),
+    }
+}
+
+/// Pawn tag for a scalar type: `f32` → `Float:`, `bool` → `bool:`, otherwise none.
+fn tag_of(ty: &Type) -> &'static str {
+    match last_segment(strip_reference(ty)).as_deref() {
+        Some("f32") => "Float:",
+        Some("bool") => "bool:",
+        _ => "",
+    }
+}
+
+/// Rust's `_unused` convention has no meaning in Pawn — drop the leading
+/// underscores so the prototype reads naturally (`_playerid` → `playerid`).
+fn pawn_ident(ident: &str) -> String {
+    let trimmed = ident.trim_start_matches('_');
+    if trimmed.is_empty() {
+        ident.to_owned()
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+fn strip_reference(ty: &Type) -> &Type {
+    match ty {
+        Type::Reference(reference) => strip_reference(&reference.elem),
+        _ => ty,
+    }
+}
+
+fn last_segment(ty: &Type) -> Option<String> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    tp.path.segments.last().map(|s| s.ident.to_string())
+}
+
+fn first_generic(ty: &Type) -> Option<&Type> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(args) = &tp.path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// `N` of `ArrayView<N>`, as written (`24`, `MAX_PLAYER_NAME`).
+fn row_len(ty: &Type) -> Option<String> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(args) = &tp.path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Const(expr) => Some(expr.to_token_stream().to_string()),
+        // A bare constant name parses as a type.
+        GenericArgument::Type(ty) => Some(ty.to_token_stream().to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::ItemFn;
+
+    fn decl(src: &str, raw: bool) -> String {
+        let item: ItemFn = syn::parse_str(src).expect("valid fn");
+        let inputs: Vec<&FnArg> = item.sig.inputs.iter().skip(1).collect();
+        native_decl("Native", &inputs, &item.sig.output, raw)
+    }
+
+    #[test]
+    fn scalars_map_to_tags() {
+        assert_eq!(
+            decl(
+                "fn f(amx: &Amx, id: i32, x: f32, ok: bool) -> AmxResult<bool> {}",
+                false
+            ),
+            "native bool:Native(id, Float:x, bool:ok);"
+        );
+    }
+
+    #[test]
+    fn no_args_and_no_return() {
+        assert_eq!(decl("fn f(amx: &Amx) {}", false), "native Native();");
+    }
+
+    #[test]
+    fn float_return_through_result() {
+        assert_eq!(
+            decl("fn f(amx: &Amx) -> Result<f32, String> {}", false),
+            "native Float:Native();"
+        );
+    }
+
+    #[test]
+    fn string_is_const_array() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, name: &AmxString) -> i32 {}", false),
+            "native Native(const name[]);"
+        );
+    }
+
+    #[test]
+    fn encoded_string_is_const_array() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, text: EncodedString<Cp1251>) {}", false),
+            "native Native(const text[]);"
+        );
+    }
+
+    #[test]
+    fn owned_string_is_const_array() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, url: String) {}", false),
+            "native Native(const url[]);"
+        );
+    }
+
+    #[test]
+    fn unsized_buffer_with_size_uses_sizeof() {
+        assert_eq!(
+            decl(
+                "fn f(amx: &Amx, dest: UnsizedBuffer, size: usize) -> i32 {}",
+                false
+            ),
+            "native Native(dest[], size = sizeof dest);"
+        );
+    }
+
+    #[test]
+    fn unsized_buffer_without_size_is_plain_array() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, dest: UnsizedBuffer, id: i32) {}", false),
+            "native Native(dest[], id);"
+        );
+    }
+
+    #[test]
+    fn usize_not_after_buffer_is_plain() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, count: usize) {}", false),
+            "native Native(count);"
+        );
+    }
+
+    #[test]
+    fn refs_are_by_reference_with_tag() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, out: Ref<i32>, pos: Ref<f32>) {}", false),
+            "native Native(&out, &Float:pos);"
+        );
+    }
+
+    #[test]
+    fn leading_underscores_are_dropped() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, _playerid: i32) {}", false),
+            "native Native(playerid);"
+        );
+    }
+
+    #[test]
+    fn variadic_param_renders_as_ellipsis() {
+        assert_eq!(
+            decl(
+                "fn f(amx: &Amx, format: &AmxString, values: Variadic) -> i32 {}",
+                false
+            ),
+            "native Native(const format[], ...);"
+        );
+    }
+
+    #[test]
+    fn struct_params_render_as_arrays() {
+        assert_eq!(
+            decl(
+                "fn f(amx: &Amx, id: i32, data: &mut PlayerData, copy: StructRef<PlayerData>) {}",
+                false
+            ),
+            "native Native(id, data[], copy[]);"
+        );
+    }
+
+    #[test]
+    fn two_dimensional_arrays_keep_their_row_length() {
+        assert_eq!(
+            decl(
+                "fn f(amx: &Amx, names: ArrayView<24>, ids: ArrayView<'_, MAX_IDS>, grid: Buffer2D) {}",
+                false
+            ),
+            "native Native(names[][24], ids[][MAX_IDS], grid[][]);"
+        );
+    }
+
+    fn forward(src: &str) -> String {
+        let item: ItemFn = syn::parse_str(src).expect("valid fn");
+        callback_forward("OnDone", &item.sig.output)
+    }
+
+    #[test]
+    fn async_callbacks_take_the_result_in_order() {
+        assert_eq!(forward("fn f() {}"), "forward OnDone();");
+        assert_eq!(forward("fn f() -> () {}"), "forward OnDone();");
+        assert_eq!(forward("fn f() -> f32 {}"), "forward OnDone(Float:arg0);");
+        assert_eq!(
+            forward("fn f() -> Result<(i32, bool, String), Error> {}"),
+            "forward OnDone(arg0, bool:arg1, const arg2[]);"
+        );
+        assert_eq!(
+            forward("fn f() -> AmxResult<String> {}"),
+            "forward OnDone(const arg0[]);"
+        );
+    }
+
+    #[test]
+    fn raw_natives_are_variadic() {
+        assert_eq!(
+            decl("fn f(amx: &Amx, args: &mut Args) -> f32 {}", true),
+            "native Float:Native(...);"
+        );
+    }
+}
@@ -6,6 +6,8 @@
 //!   (`Supports`/`Load`/`Unload`/`AmxLoad`/`AmxUnload`/`ProcessTick` on SA-MP
 //!   and `ComponentEntryPoint` + vtable on Open Multiplayer), as well as native registration.
 //! - `#[derive(SampPlugin)]` — shortcut for an empty `impl SampPlugin for T {}`.
+//! - `#[derive(AmxCell)]` — cell conversions for fieldless enums and newtypes.
+//! - `#[derive(AmxStruct)]` — maps a struct onto an enum-structured Pawn array.
 //!
 //! This crate only compiles when loaded by `samp` via reexport — it has no
 //! runtime API of its own.
@@ -16,9 +18,12 @@
 
 use proc_macro::TokenStream;
 
+mod cell;
 mod event;
+mod include;
 mod native;
 mod plugin;
+mod structure;
 
 /// Prefix applied to the name of the `extern "C"` wrapper function generated by `#[native]`.
 /// Avoids collision with any function name declared by the developer.
@@ -27,6 +32,15 @@ pub(crate) const NATIVE_PREFIX: &str = "__samp_native_";
 /// Prefix applied to the name of the native registration block in `initialize_plugin!`.
 pub(crate) const REG_PREFIX: &str = "__samp_reg_";
 
+/// Prefix applied to the function generated by `#[native]` that adds the
+/// native's Pawn prototype (`native Foo(...);`), and the callback `forward` of
+/// an async native, to the plugin's `.inc` file in `initialize_plugin!`.
+pub(crate) const INC_PREFIX: &str = "__samp_inc_";
+
+/// Prefix applied to the function generated by `#[native]` that reports whether
+/// the native is async, so `initialize_plugin!` knows to turn on result delivery.
+pub(crate) const ASYNC_PREFIX: &str = "__samp_async_";
+
 /// Prefix applied to the handler wrapper generated by `#[event]`.
 pub(crate) const EVENT_PREFIX: &str = "__samp_event_";
 
@@ -77,3 +91,62 @@ pub fn initialize_plugin(input: TokenStream) -> TokenStream {
 pub fn derive_samp_plugin(input: TokenStream) -> TokenStream {
     plugin::derive_samp_plugin(input)
 }
+
+/// Generates `AmxCell`, `CellConvert` and `AsyncArg` for a fieldless enum or a
+/// single-field newtype, so the type can appear directly in `#[native]`
+/// signatures.
+///
+/// Enum variants map to their discriminants. A cell that matches none of them
+/// fails to parse (the native logs it and returns `0`) unless one variant is
+/// marked `#[amx(fallback)]`. `CellConvert` cannot fail, so enums only get it
+/// when they have a fallback. Newtypes delegate to the field's type.
+///
+/// ```rust,ignore
+/// use samp::AmxCell;
+///
+/// #[derive(AmxCell, Clone, Copy)]
+/// enum WeaponSlot {
+///     Melee = 1,
+///     Pistol = 2,
+///     #[amx(fallback)]
+///     Unknown = -1,
+/// }
+///
+/// #[derive(AmxCell, Clone, Copy)]
+/// struct PlayerId(i32);
+///
+/// #[native(name = "GiveSlot")]
+/// fn give_slot(&mut self, _amx: &Amx, player: PlayerId, slot: WeaponSlot) -> bool { true }
+/// ```
+#[proc_macro_derive(AmxCell, attributes(amx))]
+pub fn derive_amx_cell(input: TokenStream) -> TokenStream {
+    cell::derive_amx_cell(input)
+}
+
+/// Generates `AmxStruct` for a struct laid out like an enum-structured Pawn
+/// array (`new Data[E_PLAYER_DATA]`). Fields take cells in declaration order:
+/// one per scalar, `N` per `[T; N]`, and `N` per `String` marked
+/// `#[amx(string = N)]`. A native then takes the array as `&mut T`.
+///
+/// ```rust,ignore
+/// use samp::AmxStruct;
+///
+/// // enum E_PLAYER_DATA { E_LEVEL, Float:E_HEALTH, E_NAME[MAX_PLAYER_NAME] }
+/// #[derive(AmxStruct)]
+/// struct PlayerData {
+///     level: i32,
+///     health: f32,
+///     #[amx(string = 24)]
+///     name: String,
+/// }
+///
+/// #[native(name = "Player_LevelUp")]
+/// fn level_up(&mut self, _amx: &Amx, data: &mut PlayerData) -> i32 {
+///     data.level += 1;
+///     data.level
+/// }
+/// ```
+#[proc_macro_derive(AmxStruct, attributes(amx))]
+pub fn derive_amx_struct(input: TokenStream) -> TokenStream {
+    structure::derive_amx_struct(input)
+}
@@ -7,9 +7,22 @@
 //! - **Registration function** with prefix `__samp_reg_` that produces an
 //!   `AMX_NATIVE_INFO` (name as a C-string + wrapper pointer) consumed by
 //!   `initialize_plugin!`.
+//! - **Include function** with prefix `__samp_inc_` that adds the Pawn
+//!   prototype (`native Foo(...);`) derived from the signature — and, for an
+//!   async native, the `forward` of its callback — to the plugin's `.inc`.
+//!
+//! - **Async flag function** with prefix `__samp_async_` that tells
+//!   `initialize_plugin!` whether the native is async.
 //!
 //! `raw` mode skips parsing and hands `Args` directly to the method — useful for
-//! variadic natives or those that need to validate arguments manually.
+//! natives that need to validate arguments manually. A trailing
+//! `samp::args::Variadic` parameter receives the `...` arguments without
+//! resorting to `raw`. A `&mut T` parameter is an enum-structured array, read
+//! through `samp::cell::StructRef<T>` and written back when the native returns.
+//!
+//! `async = "Callback"` mode copies the arguments into owned values, runs the
+//! body on the `samp::tasks` worker pool and calls the `Callback` public with
+//! the result on a later tick.
 
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
@@ -21,21 +34,40 @@ use syn::{
     parse_macro_input,
 };
 
-use crate::NATIVE_PREFIX;
-use crate::REG_PREFIX;
+use crate::include::{callback_forward, native_decl};
+use crate::{ASYNC_PREFIX, INC_PREFIX, NATIVE_PREFIX, REG_PREFIX};
 
-/// Args of `#[native(...)]`: `name = "..."` (Pawn name) and optional `raw`.
+/// Args of `#[native(...)]`: `name = "..."` (Pawn name), optional `raw` and
+/// optional `async = "..."` (callback public of an async native).
 struct NativeName {
     pub name: String,
     pub raw: bool,
+    pub callback: Option<LitStr>,
 }
 
 impl Parse for NativeName {
     fn parse(input: ParseStream) -> SynResult<Self> {
         let mut name = String::new();
         let mut raw = false;
+        let mut callback = None;
 
         while !input.is_empty() {
+            // `async` is a keyword, so it never parses as an `Ident`.
+            if input.peek(Token![async]) {
+                let _: Token![async] = input.parse()?;
+                let _: Token![=] = input.parse()?;
+                let public: LitStr = input.parse()?;
+                if public.value().is_empty() || public.value().contains('\0') {
+                    return Err(Error::new(
+                        public.span(),
+                        "async callback must be a non-empty public name without null bytes",
+                    ));
+                }
+                callback = Some(public);
+                let _: Option<Token![,]> = input.parse()?;
+                continue;
+            }
+
             let ident: Ident = input.parse()?;
 
             if ident == "name" {
@@ -57,14 +89,22 @@ impl Parse for NativeName {
             } else {
                 return Err(Error::new(
                     ident.span(),
-                    "Unexpected argument name. Currently supports only \"name\" and \"raw\".",
+                    "Unexpected argument name. Currently supports only \"name\", \"raw\" and \"async\".",
                 ));
             }
 
             let _: Option<Token![,]> = input.parse()?;
         }
 
-        Ok(NativeName { name, raw })
+        if raw && callback.is_some() {
+            return Err(input.error("`raw` and `async` cannot be combined"));
+        }
+
+        Ok(NativeName {
+            name,
+            raw,
+            callback,
+        })
     }
 }
 
@@ -79,19 +119,49 @@ pub fn create_native(args: TokenStream, input: TokenStream) -> TokenStream {
     let origin_name = &origin_fn.sig.ident;
     let native_name = prepend(&origin_fn.sig.ident, NATIVE_PREFIX);
     let reg_name = prepend(&origin_fn.sig.ident, REG_PREFIX);
+    let inc_name = prepend(&origin_fn.sig.ident, INC_PREFIX);
+    let async_name = prepend(&origin_fn.sig.ident, ASYNC_PREFIX);
     let amx_name = &native.name;
 
     // `#[native]` accepts both methods (`fn foo(&mut self, _amx: &Amx, ...)`)
     // and associated functions (`fn foo(_amx: &Amx, ...)`) — stateless natives
     // look cleaner without the ceremonial `self`.
     let has_self = matches!(origin_fn.sig.inputs.first(), Some(FnArg::Receiver(_)));
-    let skip_count = if has_self { 2 } else { 1 };
 
-    let fn_input_idents = gen_fn_input_idents(&origin_fn, skip_count);
-    let args_parsing = gen_args_parsing(&origin_fn, skip_count, native.raw, amx_name);
-    let plugin_binding = gen_plugin_binding(has_self);
-    let call_origin = gen_call_origin(origin_name, has_self, native.raw, &fn_input_idents);
-    let invocation = gen_invocation(&origin_fn, &call_origin, amx_name);
+    if let Some(err) = check_variadic(&origin_fn, native.callback.is_some()) {
+        return err.to_compile_error().into();
+    }
+
+    // Async natives run off the server thread: no `self`, no `&Amx`, every
+    // parameter is a Pawn argument.
+    let (skip_count, args_parsing, plugin_binding, invocation) =
+        if let Some(callback) = &native.callback {
+            if has_self {
+                return Error::new(
+                    origin_fn.sig.inputs.span(),
+                    "async natives run on a worker thread and cannot take `self`",
+                )
+                .to_compile_error()
+                .into();
+            }
+            let fn_input_idents = gen_fn_input_idents(&origin_fn, 0);
+            (
+                0,
+                gen_async_args_parsing(&origin_fn, amx_name),
+                proc_macro2::TokenStream::new(),
+                gen_async_invocation(&origin_fn, &fn_input_idents, amx_name, &callback.value()),
+            )
+        } else {
+            let skip_count = if has_self { 2 } else { 1 };
+            let fn_input_idents = gen_fn_input_idents(&origin_fn, skip_count);
+            let call_origin = gen_call_origin(origin_name, has_self, native.raw, &fn_input_idents);
+            (
+                skip_count,
+                gen_args_parsing(&origin_fn, skip_count, native.raw, amx_name),
+                gen_plugin_binding(has_self),
+                gen_invocation(&origin_fn, &call_origin, amx_name),
+            )
+        };
 
     let native_generated = quote! {
         #vis extern "C" fn #native_name(amx: *mut samp::raw::types::AMX, args: *mut i32) -> i32 {
@@ -117,10 +187,19 @@ pub fn create_native(args: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     let reg_native = gen_reg_native(vis, &reg_name, &native_name, amx_name);
+    let inc_native = gen_inc_native(vis, &inc_name, &origin_fn, skip_count, &native);
+    let is_async = native.callback.is_some();
 
     let generated = quote! {
         #origin_fn
         #reg_native
+        #inc_native
+
+        #[doc(hidden)]
+        #vis fn #async_name() -> bool {
+            #is_async
+        }
+
         #native_generated
     };
 
@@ -128,7 +207,8 @@ pub fn create_native(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// For each "real" function arg (after `self`/`amx`), generates the token to use
-/// in the call: `&ident` if the signature declares `&T`, `ident` if it declares an owned `T`.
+/// in the call: `&ident` if the signature declares `&T`, `&mut ident` for
+/// `&mut T`, `ident` if it declares an owned `T`.
 fn gen_fn_input_idents(origin_fn: &ItemFn, skip_count: usize) -> Vec<proc_macro2::TokenStream> {
     origin_fn
         .sig
@@ -141,11 +221,12 @@ fn gen_fn_input_idents(origin_fn: &ItemFn, skip_count: usize) -> Vec<proc_macro2
                     return None;
                 };
                 let ident = &pat_ident.ident;
-                let by_ref = matches!(&*pat_type.ty, Type::Reference(_));
-                Some(if by_ref {
-                    quote_spanned!(pat_type.span() => &#ident)
-                } else {
-                    quote_spanned!(pat_type.span() => #ident)
+                Some(match &*pat_type.ty {
+                    Type::Reference(r) if r.mutability.is_some() => {
+                        quote_spanned!(pat_type.span() => &mut #ident)
+                    }
+                    Type::Reference(_) => quote_spanned!(pat_type.span() => &#ident),
+                    _ => quote_spanned!(pat_type.span() => #ident),
                 })
             }
             FnArg::Receiver(_) => None,
@@ -153,6 +234,45 @@ fn gen_fn_input_idents(origin_fn: &ItemFn, skip_count: usize) -> Vec<proc_macro2
         .collect()
 }
 
+/// `Variadic` (by its last path segment, like the `.inc` mapping) — the
+/// `...` of a variadic native, filled by `Args::rest`.
+fn is_variadic(ty: &Type) -> bool {
+    let Type::Path(tp) = ty else {
+        return false;
+    };
+    tp.path
+        .segments
+        .last()
+        .is_some_and(|s| s.ident == "Variadic")
+}
+
+/// A `Variadic` parameter takes every remaining argument, so it must come
+/// last; async natives cannot take one because it points into AMX memory.
+fn check_variadic(origin_fn: &ItemFn, is_async: bool) -> Option<Error> {
+    let inputs = &origin_fn.sig.inputs;
+    inputs.iter().enumerate().find_map(|(idx, arg)| {
+        let FnArg::Typed(pat_type) = arg else {
+            return None;
+        };
+        if !is_variadic(&pat_type.ty) {
+            return None;
+        }
+        if is_async {
+            Some(Error::new(
+                pat_type.span(),
+                "async natives cannot take `Variadic`: it points into AMX memory",
+            ))
+        } else if idx + 1 != inputs.len() {
+            Some(Error::new(
+                pat_type.span(),
+                "`Variadic` must be the last parameter",
+            ))
+        } else {
+            None
+        }
+    })
+}
+
 /// Generates the `let Some(arg) = args.next_arg() else { log; return 0; };` for
 /// each "real" arg. `raw` mode skips this (the native receives `Args` directly).
 fn gen_args_parsing(
@@ -177,9 +297,24 @@ fn gen_args_parsing(
                 };
                 let ident = &pat_ident.ident;
                 let ty = &pat_type.ty;
+                if is_variadic(ty) {
+                    return Some(quote_spanned!(pat_type.span() => let #ident = args.rest();));
+                }
+                // `&mut T` borrows a `StructRef<T>` that writes the array back
+                // when it drops at the end of the native.
+                let (binding, parse) = match &**ty {
+                    Type::Reference(r) if r.mutability.is_some() => {
+                        let elem = &r.elem;
+                        (
+                            quote!(mut #ident),
+                            quote!(args.next_arg::<samp::cell::StructRef<#elem>>()),
+                        )
+                    }
+                    _ => (quote!(#ident), quote!(args.next_arg())),
+                };
                 Some(quote_spanned! {
                     pat_type.span() =>
-                        let Some(#ident) = args.next_arg() else {
+                        let Some(#binding) = #parse else {
                             samp::log::error!(
                                 "[{}] failed to parse argument #{} '{}' (expected type: {})",
                                 #amx_name,
@@ -275,6 +410,63 @@ fn gen_invocation(
     }
 }
 
+/// Async counterpart of [`gen_args_parsing`]: every argument is read through
+/// `samp::tasks::AsyncArg`, producing owned values the worker can keep.
+fn gen_async_args_parsing(origin_fn: &ItemFn, amx_name: &str) -> proc_macro2::TokenStream {
+    origin_fn
+        .sig
+        .inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, arg)| match arg {
+            FnArg::Typed(pat_type) => {
+                let Pat::Ident(pat_ident) = &*pat_type.pat else {
+                    return None;
+                };
+                let ident = &pat_ident.ident;
+                let ty = &pat_type.ty;
+                Some(quote_spanned! {
+                    pat_type.span() =>
+                        let Some(#ident) = <#ty as samp::tasks::AsyncArg>::from_args(&mut args) else {
+                            samp::log::error!(
+                                "[{}] failed to parse argument #{} '{}' (expected type: {})",
+                                #amx_name,
+                                #idx,
+                                stringify!(#ident),
+                                stringify!(#ty),
+                            );
+                            return 0;
+                        };
+                })
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Hands the body to `samp::tasks::spawn` and returns `1` to Pawn. The job
+/// yields `Result<R, String>` — `Err` values are stringified on the worker
+/// so only `Send` data crosses back to the server thread.
+fn gen_async_invocation(
+    origin_fn: &ItemFn,
+    fn_input_idents: &[proc_macro2::TokenStream],
+    amx_name: &str,
+    callback: &str,
+) -> proc_macro2::TokenStream {
+    let origin_name = &origin_fn.sig.ident;
+    let call_origin = quote!(Self::#origin_name(#(#fn_input_idents),*));
+    let job = if returns_result(&origin_fn.sig.output) {
+        quote!(#call_origin.map_err(|err| err.to_string()))
+    } else {
+        quote!(Ok(#call_origin))
+    };
+
+    quote! {
+        samp::tasks::spawn(amx, #amx_name, #callback, move || #job);
+        return 1;
+    }
+}
+
 /// `__samp_reg_*` function that produces the `AMX_NATIVE_INFO` (name as a C-string
 /// + wrapper pointer). Consumed by `initialize_plugin!`.
 fn gen_reg_native(
@@ -301,6 +493,36 @@ fn gen_reg_native(
     }
 }
 
+/// `__samp_inc_*` function adding the Pawn prototype, resolved here at
+/// proc-macro time from the signature, to an `Include` — plus the callback's
+/// `forward` for an async native. Consumed by `initialize_plugin!`.
+fn gen_inc_native(
+    vis: &syn::Visibility,
+    inc_name: &Ident,
+    origin_fn: &ItemFn,
+    skip_count: usize,
+    native: &NativeName,
+) -> proc_macro2::TokenStream {
+    let inputs: Vec<&FnArg> = origin_fn.sig.inputs.iter().skip(skip_count).collect();
+    // An async native returns `1` to Pawn; its result goes to the callback.
+    let output = if native.callback.is_some() {
+        &ReturnType::Default
+    } else {
+        &origin_fn.sig.output
+    };
+    let decl = native_decl(&native.name, &inputs, output, native.raw);
+    let forward = native.callback.as_ref().map(|callback| {
+        let forward = callback_forward(&callback.value(), &origin_fn.sig.output);
+        quote!(.forward(#forward))
+    });
+    quote! {
+        #[doc(hidden)]
+        #vis fn #inc_name(include: samp::include::Include) -> samp::include::Include {
+            include.native(#decl) #forward
+        }
+    }
+}
+
 fn prepend(ident: &Ident, prefix: &str) -> Ident {
     Ident::new(&format!("{prefix}{ident}"), ident.span())
 }
@@ -21,7 +21,7 @@ use syn::{
     parse_macro_input,
 };
 
-use crate::{EVENT_REG_PREFIX, REG_PREFIX};
+use crate::{ASYNC_PREFIX, EVENT_REG_PREFIX, INC_PREFIX, REG_PREFIX};
 
 // ---------------------------------------------------------------------------
 // Helpers for automatic Open Multiplayer metadata resolution
@@ -171,6 +171,9 @@ struct InitPlugin {
     natives_list: Option<Punctuated<Path, Token![,]>>,
     /// Optional `events: [...]` list of `#[event]` handlers to register.
     events_list: Option<Punctuated<Path, Token![,]>>,
+    /// Optional `forwards: ["OnFoo(playerid)", ...]` list of publics the plugin
+    /// calls via `exec_public!`, emitted as `forward` lines in the `.inc`.
+    forwards_list: Option<Punctuated<LitStr, Token![,]>>,
     constructor: Constructor,
     /// Explicit UID in the macro (`uid: 0x...`). Overrides Cargo.toml and the automatic fallback.
     explicit_uid: Option<Expr>,
@@ -184,6 +187,7 @@ impl Parse for InitPlugin {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut natives_list = None;
         let mut events_list = None;
+        let mut forwards_list = None;
         let mut default_type: Option<Path> = None;
         let mut explicit_uid: Option<Expr> = None;
         let mut explicit_name: Option<LitStr> = None;
@@ -216,6 +220,19 @@ impl Parse for InitPlugin {
                         events_list = Some(Punctuated::parse_terminated(&content)?);
                         let _: Option<Token![,]> = input.parse()?;
                     }
+                    "forwards" => {
+                        let _: Ident = input.parse()?;
+                        let _: Token![:] = input.parse()?;
+                        let content;
+                        let _ = bracketed!(content in input);
+                        let list: Punctuated<LitStr, Token![,]> =
+                            Punctuated::parse_terminated(&content)?;
+                        for forward in &list {
+                            validate_forward(forward)?;
+                        }
+                        forwards_list = Some(list);
+                        let _: Option<Token![,]> = input.parse()?;
+                    }
                     "uid" => {
                         let _: Ident = input.parse()?;
                         let _: Token![:] = input.parse()?;
@@ -261,6 +278,7 @@ impl Parse for InitPlugin {
         Ok(InitPlugin {
             natives_list,
             events_list,
+            forwards_list,
             constructor,
             explicit_uid,
             explicit_name,
@@ -269,6 +287,21 @@ impl Parse for InitPlugin {
     }
 }
 
+/// A forward must look like a Pawn prototype (`Name(params)`) — catching a
+/// typo here beats a confusing error from the Pawn compiler later.
+fn validate_forward(forward: &LitStr) -> Result<()> {
+    let value = forward.value();
+    let prototype = value.trim().trim_end_matches(';').trim_end();
+    let prototype = prototype.strip_prefix("forward ").unwrap_or(prototype);
+    match prototype.split_once('(') {
+        Some((name, _)) if !name.trim().is_empty() && prototype.ends_with(')') => Ok(()),
+        _ => Err(syn::Error::new(
+            forward.span(),
+            "expected a Pawn prototype such as \"OnFoo(playerid, const name[])\"",
+        )),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Code generation
 // ---------------------------------------------------------------------------
@@ -278,8 +311,9 @@ pub fn create_plugin(input: TokenStream) -> TokenStream {
 
     let natives = gen_natives_list(&plugin);
     let events = gen_events_list(&plugin);
+    let async_flags = gen_async_flags(&plugin);
     let supports_body = gen_samp_constructor(&plugin.constructor);
-    let samp_entry_points = gen_samp_entry_points(&natives, &events, &supports_body);
+    let samp_entry_points = gen_samp_entry_points(&natives, &events, &async_flags, &supports_body);
 
     // Native Open Multiplayer entry point.
     //
@@ -296,12 +330,15 @@ pub fn create_plugin(input: TokenStream) -> TokenStream {
     let omp_entry_point = if samp_only {
         quote! {}
     } else {
-        gen_omp_entry_point(&plugin, &cargo_meta, &natives, &events)
+        gen_omp_entry_point(&plugin, &cargo_meta, &natives, &events, &async_flags)
     };
 
+    let pawn_include = gen_pawn_include(&plugin, &cargo_meta);
+
     let generated = quote! {
         #samp_entry_points
         #omp_entry_point
+        #pawn_include
     };
 
     generated.into()
@@ -331,6 +368,24 @@ fn gen_natives_list(plugin: &InitPlugin) -> proc_macro2::TokenStream {
         .collect()
 }
 
+/// Converts the paths in the `natives: [...]` list into `__samp_async_*()`
+/// calls — one flag per native, `true` for the async ones.
+fn gen_async_flags(plugin: &InitPlugin) -> proc_macro2::TokenStream {
+    plugin
+        .natives_list
+        .iter()
+        .flatten()
+        .map(|path| {
+            let mut path = path.clone();
+            if let Some(last_part) = path.segments.last_mut() {
+                let span = last_part.ident.span();
+                last_part.ident = Ident::new(&format!("{}{}", ASYNC_PREFIX, last_part.ident), span);
+            }
+            quote!(#path(),)
+        })
+        .collect()
+}
+
 /// Converts the paths in the `events: [...]` list into `__samp_event_reg_*()`
 /// calls, emitting `path1(), path2(), ...` — each producing an `EventInfo`.
 fn gen_events_list(plugin: &InitPlugin) -> proc_macro2::TokenStream {
@@ -350,6 +405,48 @@ fn gen_events_list(plugin: &InitPlugin) -> proc_macro2::TokenStream {
         .collect()
 }
 
+/// `mod __samp_include` with the plugin's `.inc`, built by passing it through
+/// the `__samp_inc_*` function of every native, then the `forwards: [...]`
+/// list (which replaces a generated forward of the same public).
+/// Reached through `samp::pawn_include!()`, so nothing lands in the user's
+/// namespace. The include guard uses the same name as the Open Multiplayer
+/// component.
+fn gen_pawn_include(plugin: &InitPlugin, cargo_meta: &SampMetadata) -> proc_macro2::TokenStream {
+    let name = resolve_component_name(plugin, cargo_meta);
+    let natives = plugin.natives_list.iter().flatten().map(|path| {
+        let mut path = path.clone();
+        if let Some(last_part) = path.segments.last_mut() {
+            let span = last_part.ident.span();
+            last_part.ident = Ident::new(&format!("{}{}", INC_PREFIX, last_part.ident), span);
+        }
+        quote!(let include = #path(include);)
+    });
+    let forwards = plugin
+        .forwards_list
+        .iter()
+        .flatten()
+        .map(|forward| quote!(.forward(#forward)));
+
+    quote! {
+        mod __samp_include {
+            // Empty when the plugin registers no natives.
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Pawn include (`.inc`) declaring this plugin's natives and
+            /// forwards. Generated by `initialize_plugin!` — see `samp::include`.
+            // Only called from tests or tooling, through `samp::pawn_include!()`.
+            #[allow(dead_code)]
+            #[must_use]
+            pub(crate) fn pawn_include() -> samp::include::Include {
+                let include = samp::include::Include::new(#name);
+                #(#natives)*
+                include #(#forwards)*
+            }
+        }
+    }
+}
+
 /// Block that initializes `samp::plugin` in the `Supports` entry point (SA-MP).
 fn gen_samp_constructor(constructor: &Constructor) -> proc_macro2::TokenStream {
     match constructor {
@@ -368,6 +465,7 @@ fn gen_samp_constructor(constructor: &Constructor) -> proc_macro2::TokenStream {
 fn gen_samp_entry_points(
     natives: &proc_macro2::TokenStream,
     events: &proc_macro2::TokenStream,
+    async_flags: &proc_macro2::TokenStream,
     supports_body: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
     quote! {
@@ -397,6 +495,7 @@ fn gen_samp_entry_points(
         #[unsafe(no_mangle)]
         pub extern "system" fn Supports() -> u32 {
             #supports_body
+            samp::interlayer::enable_tasks(&[#async_flags]);
             samp::interlayer::supports()
         }
 
@@ -477,6 +576,7 @@ fn gen_omp_entry_point(
     cargo_meta: &SampMetadata,
     natives: &proc_macro2::TokenStream,
     events: &proc_macro2::TokenStream,
+    async_flags: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
     let uid_expr = resolve_uid_expr(plugin, cargo_meta);
     let name_str = resolve_component_name(plugin, cargo_meta);
@@ -692,6 +792,7 @@ fn gen_omp_entry_point(
                 #omp_initialize
                 samp::interlayer::omp_store_natives(vec![#natives]);
                 samp::interlayer::register_events(vec![#events]);
+                samp::interlayer::enable_tasks(&[#async_flags]);
                 let component = Box::new(OmpComponent::new(&VTABLE, &UID_VTABLE, #uid_expr));
                 Box::into_raw(component)
             }
@@ -878,4 +979,25 @@ mod tests {
         let meta = read_samp_metadata_from_content(content);
         assert_eq!(meta.name.as_deref(), Some("MinhaPlugin"));
     }
+
+    // --- validate_forward ---
+
+    fn forward(s: &str) -> Result<()> {
+        validate_forward(&LitStr::new(s, proc_macro2::Span::call_site()))
+    }
+
+    #[test]
+    fn forward_accepts_prototypes() {
+        assert!(forward("OnFoo()").is_ok());
+        assert!(forward("OnFoo(playerid, const name[])").is_ok());
+        assert!(forward("forward OnFoo(playerid);").is_ok());
+    }
+
+    #[test]
+    fn forward_rejects_malformed() {
+        assert!(forward("").is_err());
+        assert!(forward("OnFoo").is_err());
+        assert!(forward("(playerid)").is_err());
+        assert!(forward("OnFoo(playerid").is_err());
+    }
 }
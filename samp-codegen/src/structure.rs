@@ -0,0 +1,214 @@
+//! Implementation of the `#[derive(AmxStruct)]` proc macro.
+//!
+//! Maps a struct onto an enum-structured Pawn array. Fields take cells in
+//! declaration order, the same way the Pawn `enum` lays them out:
+//! - a scalar field (`i32`, `f32`, `bool`, a derived `AmxCell` type, …) takes
+//!   one cell and converts through `CellConvert`;
+//! - an array field `[T; N]` (`Float:E_POS[3]`) takes `N` cells;
+//! - a `String` field marked `#[amx(string = N)]` (`E_NAME[N]`) takes `N`
+//!   cells and goes through `samp::cell::structure::{read_string, write_string}`
+//!   with the script's `Codec`.
+//!
+//! Offsets are emitted as sums of the field sizes, so `N` may be any constant
+//! expression (`MAX_PLAYER_NAME`).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, quote};
+
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Field, Fields, Member, Result, Type,
+    parse_macro_input,
+};
+
+pub fn derive_amx_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a field is stored in the array.
+enum Layout<'a> {
+    Cell(&'a Type),
+    Array(&'a Type, &'a Expr),
+    Str(Expr),
+}
+
+impl Layout<'_> {
+    fn size(&self) -> TokenStream2 {
+        match self {
+            Layout::Cell(_) => quote!(1),
+            Layout::Array(_, len) => const_expr(len),
+            Layout::Str(len) => const_expr(len),
+        }
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[derive(AmxStruct)]` does not support generic types",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            name.span(),
+            "`#[derive(AmxStruct)]` only supports structs",
+        ));
+    };
+    if matches!(data.fields, Fields::Unit) || data.fields.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`#[derive(AmxStruct)]` needs at least one field",
+        ));
+    }
+
+    let mut offset: Vec<TokenStream2> = Vec::new();
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::from(index),
+        };
+        let layout = layout(field)?;
+        let size = layout.size();
+        let start = sum(&offset);
+        offset.push(size);
+        let end = sum(&offset);
+
+        let read = match &layout {
+            Layout::Cell(ty) => quote!(<#ty as samp::cell::CellConvert>::from_cell(cells[#start])),
+            Layout::Array(elem, _) => quote! {{
+                let field = &cells[#start..#end];
+                ::std::array::from_fn(|i| <#elem as samp::cell::CellConvert>::from_cell(field[i]))
+            }},
+            Layout::Str(_) => quote!(samp::cell::structure::read_string(&cells[#start..#end], codec)),
+        };
+        reads.push(quote!(#member: #read));
+
+        writes.push(match &layout {
+            Layout::Cell(_) => {
+                quote!(cells[#start] = samp::cell::CellConvert::into_cell(self.#member);)
+            }
+            Layout::Array(..) => quote! {
+                for (cell, value) in cells[#start..#end].iter_mut().zip(self.#member.iter()) {
+                    *cell = samp::cell::CellConvert::into_cell(*value);
+                }
+            },
+            Layout::Str(_) => quote! {
+                samp::cell::structure::write_string(&mut cells[#start..#end], &self.#member, codec);
+            },
+        });
+    }
+    let cells = sum(&offset);
+
+    Ok(quote! {
+        impl samp::cell::AmxStruct for #name {
+            const CELLS: usize = #cells;
+
+            fn read(cells: &[i32], codec: samp::cell::string::Codec) -> Self {
+                Self { #(#reads,)* }
+            }
+
+            fn write(&self, cells: &mut [i32], codec: samp::cell::string::Codec) {
+                #(#writes)*
+            }
+        }
+    })
+}
+
+fn layout(field: &Field) -> Result<Layout<'_>> {
+    if let Some(len) = string_len(&field.attrs)? {
+        return Ok(Layout::Str(len));
+    }
+    Ok(match &field.ty {
+        Type::Array(array) => Layout::Array(&array.elem, &array.len),
+        ty => Layout::Cell(ty),
+    })
+}
+
+/// `N` from `#[amx(string = N)]`, if present.
+fn string_len(attrs: &[Attribute]) -> Result<Option<Expr>> {
+    let mut len = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("amx")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("string") {
+                len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown `amx` option, expected `string = N`"))
+            }
+        })?;
+    }
+    Ok(len)
+}
+
+/// `a + b + c`, or `0` for an empty list.
+fn sum(sizes: &[TokenStream2]) -> TokenStream2 {
+    if sizes.is_empty() {
+        quote!(0)
+    } else {
+        quote!(#(#sizes)+*)
+    }
+}
+
+/// A size as an operand of `+`: literals and paths go in bare, anything else
+/// is parenthesised so `a * b` keeps its meaning inside the sum.
+fn const_expr(expr: &Expr) -> TokenStream2 {
+    match expr {
+        Expr::Lit(_) | Expr::Path(_) => expr.to_token_stream(),
+        _ => quote!((#expr)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_str(src: &str) -> Result<String> {
+        let input: DeriveInput = syn::parse_str(src).expect("valid item");
+        expand(&input).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn fields_take_consecutive_offsets() {
+        let out = expand_str(
+            "struct Data { level: i32, pos: [f32; 3], #[amx(string = NAME)] name: String, vip: bool }",
+        )
+        .unwrap();
+        assert!(out.contains("const CELLS : usize = 1 + 3 + NAME + 1 ;"));
+        assert!(out.contains("from_cell (cells [0])"));
+        assert!(out.contains("& cells [1 .. 1 + 3]"));
+        assert!(out.contains("read_string (& cells [1 + 3 .. 1 + 3 + NAME] , codec)"));
+        assert!(out.contains(
+            "cells [1 + 3 + NAME] = samp :: cell :: CellConvert :: into_cell (self . vip)"
+        ));
+    }
+
+    #[test]
+    fn compound_sizes_are_parenthesised() {
+        let out =
+            expand_str("struct Data { #[amx(string = 2 * 8)] name: String, id: i32 }").unwrap();
+        assert!(out.contains("cells [(2 * 8)]"));
+    }
+
+    #[test]
+    fn tuple_structs_use_field_indices() {
+        let out = expand_str("struct Pos([f32; 3], i32);").unwrap();
+        assert!(out.contains("self . 1"));
+    }
+
+    #[test]
+    fn unsupported_shapes_are_rejected() {
+        let error = |src| expand_str(src).expect_err("derive should fail").to_string();
+        assert!(error("enum E { A }").contains("only supports structs"));
+        assert!(error("struct E;").contains("at least one field"));
+        assert!(error("struct E<T> { a: T }").contains("generic"));
+        assert!(error("struct E { #[amx(str = 4)] a: String }").contains("unknown `amx` option"));
+    }
+}
@@ -1,7 +1,9 @@
 //! Benchmarks for AMX string parsing.
 //!
 //! Compares performance of the unpacked (1 byte/cell) and packed (4 bytes/cell) paths,
-//! the cost of `Buffer::write_str` / `UnsizedBuffer::write_str`, and a baseline without AMX.
+//! the cost of `Buffer::write_str` / `UnsizedBuffer::write_str`, the zero-copy
+//! reads (`bytes`, `starts_with_bytes`, `eq_ignore_ascii_case_bytes`) against decoding, and a
+//! baseline without AMX.
 //!
 //! Run with:
 //! ```sh
@@ -193,6 +195,90 @@ fn bench_deref_cached(c: &mut Criterion) {
     group.finish();
 }
 
+// ---------------------------------------------------------------------------
+// bench: zero-copy reads vs decode — a fresh `AmxString` per iteration, as a
+// native sees it on every call (no warm decode cache)
+// ---------------------------------------------------------------------------
+
+fn unpacked_cells(bytes: &[u8]) -> Vec<i32> {
+    bytes
+        .iter()
+        .map(|&b| i32::from(b))
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+fn bench_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes");
+
+    for size in [8usize, 64, 256, 1024] {
+        let input: Vec<u8> = (b'A'..=b'Z').cycle().take(size).collect();
+        let mut unpacked = unpacked_cells(&input);
+        let mut packed = build_packed_cells(&input);
+
+        group.bench_with_input(BenchmarkId::new("unpacked", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut unpacked), size);
+                s.bytes().fold(0u32, |acc, byte| acc + u32::from(byte))
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("packed", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut packed), size);
+                s.bytes().fold(0u32, |acc, byte| acc + u32::from(byte))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_starts_with(c: &mut Criterion) {
+    let mut group = c.benchmark_group("starts_with");
+
+    for size in [8usize, 64, 256, 1024] {
+        let input: Vec<u8> = b"/cmd ".iter().copied().cycle().take(size).collect();
+        let mut data = unpacked_cells(&input);
+
+        group.bench_with_input(BenchmarkId::new("zero_copy", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut data), size);
+                s.starts_with_bytes(black_box("/cmd"))
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("decoded", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut data), size);
+                s.as_str().starts_with(black_box("/cmd"))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_eq_ignore_ascii_case(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eq_ignore_ascii_case");
+
+    for size in [8usize, 64, 256, 1024] {
+        let input: Vec<u8> = (b'a'..=b'z').cycle().take(size).collect();
+        let other = String::from_utf8(input.to_ascii_uppercase()).unwrap();
+        let mut data = unpacked_cells(&input);
+
+        group.bench_with_input(BenchmarkId::new("zero_copy", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut data), size);
+                s.eq_ignore_ascii_case_bytes(black_box(&other))
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("decoded", size), &size, |b, &size| {
+            b.iter(|| {
+                let s = AmxString::from_buffer_parts(make_buffer(&mut data), size);
+                s.as_str().eq_ignore_ascii_case(black_box(&other))
+            });
+        });
+    }
+    group.finish();
+}
+
 // ---------------------------------------------------------------------------
 // bench: baseline — String::from_utf8_lossy without AMX (comparison with samp-rs)
 // ---------------------------------------------------------------------------
@@ -218,6 +304,9 @@ criterion_group!(
     bench_to_bytes_packed,
     bench_deref_first_access,
     bench_deref_cached,
+    bench_bytes,
+    bench_starts_with,
+    bench_eq_ignore_ascii_case,
     bench_baseline_from_utf8,
 );
 criterion_main!(benches);
@@ -4,7 +4,8 @@
 //! Each method here resolves the corresponding `amx_*` function on demand (via
 //! [`crate::exports`]) and invokes it with idiomatic Rust error handling.
 
-use crate::cell::{AmxCell, AmxPrimitive, AmxString, Buffer, Ref};
+use crate::cell::string::{Codec, packed_cells};
+use crate::cell::{AmxCell, AmxPrimitive, AmxString, Buffer, Buffer2D, Ref};
 use crate::consts::{AmxExecIdx, AmxFlags};
 use crate::error::{AmxError, AmxResult};
 // Intentional wildcard: brings in the 40+ marker types of the exported AMX
@@ -15,12 +16,12 @@ use crate::exports::*;
 use crate::raw::functions::AmxNative;
 use crate::raw::types::{AMX, AMX_HEADER, AMX_NATIVE_INFO};
 
-#[cfg(feature = "encoding")]
-use crate::encoding;
-
 use std::borrow::Cow;
-use std::ffi::CString;
+use std::ffi::{CStr, CString, c_char};
+use std::mem::MaybeUninit;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
 
 macro_rules! amx_try {
     ($call:expr) => {
@@ -91,7 +92,7 @@ impl Amx {
         let allot = Allot::from_table(self.fn_table);
 
         let mut amx_addr = 0;
-        let mut phys_addr = 0;
+        let mut phys_addr: *mut i32 = std::ptr::null_mut();
 
         // `cells` was validated above as `<= i32::MAX`; cast is safe.
         #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
@@ -103,11 +104,11 @@ impl Amx {
             &raw mut phys_addr
         ));
 
-        if phys_addr == 0 {
+        if phys_addr.is_null() {
             return Err(AmxError::Memory);
         }
 
-        unsafe { Ok(Ref::new(amx_addr, phys_addr as *mut T)) }
+        unsafe { Ok(Ref::new(amx_addr, phys_addr.cast::<T>())) }
     }
 
     /// Executes the public function identified by `index` in the VM.
@@ -200,28 +201,18 @@ impl Amx {
     /// (first cell = `argc * sizeof(cell)`, then the arguments), and
     /// invokes the native.
     ///
-    /// Integer arguments are passed as their `i32` value. Floats are
-    /// passed bit-cast to `i32` (use [`f32::to_bits`] then
-    /// [`i32::from_ne_bytes`] on `to_ne_bytes`, or `f32::to_bits() as i32`).
-    /// String and array arguments are AMX cell addresses returned by
-    /// [`Allocator::allot_string`]/[`Allocator::allot_buffer`] — same
-    /// marshalling as for [`exec_public`](crate::exec_public).
+    /// `params` are raw cells: floats bit-cast, strings and arrays as the
+    /// AMX addresses of heap copies. [`call_native!`](crate::call_native)
+    /// does that marshalling (and reads by-reference outputs back), so
+    /// prefer it unless the cells are already at hand.
     ///
     /// # Example
     /// ```rust,ignore
-    /// // Calling Streamer_CreateDynamicObject from a Rust plugin
-    /// fn on_amx_load(&mut self, amx: &Amx) -> AmxResult<()> {
-    ///     let model_id: i32 = 1337;
-    ///     #[allow(clippy::cast_possible_wrap)]
-    ///     let x = 100.0_f32.to_bits() as i32;
-    ///     let y = 200.0_f32.to_bits() as i32;
-    ///     let z =  10.0_f32.to_bits() as i32;
-    ///     let object_id = amx.call_native(
-    ///         "Streamer_CreateDynamicObject",
-    ///         &[model_id, x, y, z, 0, 0, 0],
-    ///     )?;
-    ///     log::info!("created dynamic object id={object_id}");
-    ///     Ok(())
+    /// // Forwarding a native's own arguments unchanged
+    /// #[native(name = "Proxy_CreateObject", raw)]
+    /// fn proxy_create_object(&mut self, amx: &Amx, args: Args) -> AmxResult<i32> {
+    ///     let params: Vec<i32> = (0..args.count()).filter_map(|i| args.get(i)).collect();
+    ///     amx.call_native("CreateDynamicObject", &params)
     /// }
     /// ```
     ///
@@ -239,7 +230,48 @@ impl Amx {
         if index < 0 {
             return Err(AmxError::NotFound);
         }
+        let native = self.native_address(index)?;
+        // SAFETY: `self` wraps a live AMX for as long as it is borrowed.
+        unsafe { call_host(self.ptr, native, params) }
+    }
+
+    /// Resolves a native once for repeated calls — the part of
+    /// [`call_native`](Amx::call_native) that looks it up by name and reads
+    /// the host function pointer from the natives table.
+    ///
+    /// The handle stays valid until the script is unloaded (the `samp`
+    /// runtime calls [`invalidate_natives`] then); a stale handle fails
+    /// instead of calling into freed memory. Keep it per script, e.g. in
+    /// `AmxExt::state`:
+    ///
+    /// ```rust,ignore
+    /// let update = amx.resolve_native("Streamer_UpdateEx")?;
+    /// for player in players {
+    ///     call_native!(amx, &update, player.id, player.x, player.y, player.z)?;
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// The lookup errors of [`call_native`](Amx::call_native):
+    /// `AmxError::NotFound`, `AmxError::MemoryAccess` or `AmxError::Index`.
+    pub fn resolve_native(&self, name: &str) -> AmxResult<NativeHandle> {
+        let index = self.find_native(name)?;
+        if index < 0 {
+            return Err(AmxError::NotFound);
+        }
 
+        let native = self.native_address(index)?;
+
+        Ok(NativeHandle {
+            amx: self.ptr,
+            index,
+            native,
+            alive: native_liveness(self.ptr),
+        })
+    }
+
+    /// Host function pointer of native `index`, read from the natives table.
+    fn native_address(&self, index: i32) -> AmxResult<AmxNative> {
         let header_ptr = self.header().ok_or(AmxError::MemoryAccess)?;
         // SAFETY: `header()` returned NonNull, and the AMX is alive for
         // the duration of `&self`.
@@ -275,38 +307,44 @@ impl Amx {
         // the AMX-mapped region pointed to by `base`.
         let entry_ptr = unsafe { base.offset(entry_off as isize) };
 
-        // First 4 bytes of each entry — both `AMX_FUNCSTUB` and
-        // `ANX_FUNCSTUBNT` start with `u32 address`, the host function
-        // pointer written by `amx_Register`.
-        let address = unsafe { std::ptr::read_unaligned(entry_ptr.cast::<u32>()) };
-        if address == 0 {
-            return Err(AmxError::NotFound);
-        }
+        // SAFETY: `entry_ptr` is the start of entry `index`, checked above.
+        unsafe { bound_address(self.ptr, self.fn_table, index, entry_ptr) }.ok_or(AmxError::NotFound)
+    }
 
-        // SAFETY: SA-MP / open.mp are 32-bit; the AMX cell width and host
-        // function pointer width are both 4 bytes. `address` came from
-        // `amx_Register`, which writes a valid `AmxNative` pointer.
-        let native: AmxNative = unsafe { std::mem::transmute(address as usize) };
-
-        // Build the params block: `[argc * sizeof(cell), arg0, arg1, ...]`.
-        // Bytes, not cells — matches the convention every AMX native
-        // implementation reads (`params[0] / sizeof(cell)` to recover argc).
-        let mut buf: Vec<i32> = Vec::with_capacity(params.len() + 1);
-        // `params.len()` bounded by `i32::MAX` in practice; the AMX
-        // would have failed long before reaching 2 billion args.
-        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-        let argc_bytes = (params.len() as i32) * 4;
-        buf.push(argc_bytes);
-        buf.extend_from_slice(params);
-
-        let retval = native(self.ptr, buf.as_mut_ptr());
-        // Surface VM-side errors set by the native into `amx.error`.
-        // SAFETY: `amx_ptr` already validated above.
-        let err = unsafe { (*amx_ptr.as_ptr()).error };
-        if err > 0 {
-            return Err(err.into());
+    /// Every native of the script that has a host function bound, with that
+    /// function — what `amx_Register` wrote into the natives table.
+    ///
+    /// # Errors
+    /// `AmxError::MemoryAccess` if the AMX header cannot be read, or the
+    /// error of `amx_NumNatives` / `amx_GetNative`.
+    pub fn bound_natives(&self) -> AmxResult<Vec<(String, AmxNative)>> {
+        let num_natives = NumNatives::from_table(self.fn_table);
+        let name_length = NameLength::from_table(self.fn_table);
+        let get_native = GetNative::from_table(self.fn_table);
+
+        let mut count = 0;
+        amx_try!(num_natives(self.ptr, &raw mut count));
+        let mut length = 0;
+        amx_try!(name_length(self.ptr, &raw mut length));
+        // `amx_GetNative` may write up to `sNAMEMAX` (31) bytes plus the NUL,
+        // whatever the script's own longest name is.
+        let mut name: Vec<c_char> = vec![0; usize::try_from(length).unwrap_or(0).max(31) + 1];
+
+        let mut natives = Vec::new();
+        for index in 0..count {
+            amx_try!(get_native(self.ptr, index, name.as_mut_ptr()));
+            match self.native_address(index) {
+                Ok(native) => {
+                    // SAFETY: `amx_GetNative` wrote a NUL-terminated name.
+                    let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+                    natives.push((name.to_string_lossy().into_owned(), native));
+                }
+                // Declared by the script but not registered by anyone.
+                Err(AmxError::NotFound) => {}
+                Err(err) => return Err(err),
+            }
         }
-        Ok(retval)
+        Ok(natives)
     }
 
     /// Index of a public function by name — pass the result to [`exec`].
@@ -734,6 +772,7 @@ impl<'amx> Allocator<'amx> {
     }
 
     /// Allocates `size` cells on the heap and returns a [`Buffer`] covering that region.
+    /// The buffer writes strings in the script's encoding.
     ///
     /// # Errors
     /// `AmxError::Memory` if the VM heap is exhausted or if `size` exceeds
@@ -741,7 +780,7 @@ impl<'amx> Allocator<'amx> {
     pub fn allot_buffer(&self, size: usize) -> AmxResult<Buffer<'_>> {
         let buffer = self.amx.allot(size)?;
 
-        Ok(Buffer::new(buffer, size))
+        Ok(Buffer::new(buffer, size).with_codec(self.codec()))
     }
 
     /// Allocates space for `array.len()` cells and copies the content (`AmxCell::as_cell`).
@@ -763,24 +802,128 @@ impl<'amx> Allocator<'amx> {
         Ok(buffer)
     }
 
-    /// Allocates space for a string and copies `string` (configured encoding),
+    /// Allocates space for a string and copies `string` (script's encoding),
     /// adding the `0` terminator at the end.
     ///
     /// # Errors
-    /// `AmxError::Memory` if the VM heap is exhausted.
+    /// - `AmxError::Memory` if the VM heap is exhausted.
+    /// - `AmxError::Unmappable` if a character cannot be encoded and the
+    ///   policy is `Unmappable::Error`.
     pub fn allot_string(&self, string: &str) -> AmxResult<AmxString<'_>> {
-        let bytes = Allocator::string_bytes(string);
+        let bytes = self.codec().encode(string)?;
         let buffer = self.allot_buffer(bytes.len() + 1)?;
 
         Ok(unsafe { AmxString::new(buffer, bytes.as_ref()) })
     }
 
-    fn string_bytes(string: &str) -> Cow<'_, [u8]> {
-        #[cfg(feature = "encoding")]
-        return encoding::get().encode(string).0;
+    /// Allocates a packed string (4 characters per cell, a quarter of the
+    /// heap [`allot_string`](Allocator::allot_string) takes) and copies
+    /// `string` (script's encoding) into it, `0` terminated.
+    ///
+    /// # Errors
+    /// - `AmxError::Memory` if the VM heap is exhausted.
+    /// - `AmxError::Unmappable` if a character cannot be encoded and the
+    ///   policy is `Unmappable::Error`.
+    pub fn allot_packed_string(&self, string: &str) -> AmxResult<AmxString<'_>> {
+        let bytes = self.codec().encode(string)?;
+        let buffer = self.allot_buffer(packed_cells(bytes.len()))?;
+
+        Ok(unsafe { AmxString::new_packed(buffer, bytes.as_ref()) })
+    }
+
+    /// Allocates a Pawn 2-D array (`new rows[count][len]`) and copies `rows`
+    /// into it, with the indirection vector in front as the compiler lays it
+    /// out. Every row is as long as the longest one; shorter rows are padded
+    /// with `0`.
+    ///
+    /// Pawn has no empty arrays, so an empty `rows` (or rows that are all
+    /// empty) allocates a single row of one `0` cell.
+    ///
+    /// # Errors
+    /// `AmxError::Memory` if the VM heap is exhausted.
+    pub fn allot_array_2d<T, R>(&self, rows: &[R]) -> AmxResult<Buffer2D<'_>>
+    where
+        T: AmxCell<'amx> + AmxPrimitive,
+        R: AsRef<[T]>,
+    {
+        let row_len = rows.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+        self.allot_rows(rows.len(), row_len, |index, cells| {
+            for (cell, item) in cells.iter_mut().zip(rows[index].as_ref()) {
+                *cell = item.as_cell();
+            }
+        })
+    }
+
+    /// Allocates a Pawn string array (`new names[count][len]`) holding
+    /// `strings` (script's encoding, unpacked, `0` terminated). `len` is
+    /// the longest string plus its terminator.
+    ///
+    /// Pawn has no empty arrays, so an empty `strings` allocates a single
+    /// empty string.
+    ///
+    /// # Errors
+    /// - `AmxError::Memory` if the VM heap is exhausted.
+    /// - `AmxError::Unmappable` if a character cannot be encoded and the
+    ///   policy is `Unmappable::Error`.
+    pub fn allot_string_array<S: AsRef<str>>(&self, strings: &[S]) -> AmxResult<Buffer2D<'_>> {
+        let codec = self.codec();
+        let encoded = strings
+            .iter()
+            .map(|string| codec.encode(string.as_ref()))
+            .collect::<AmxResult<Vec<Cow<'_, [u8]>>>>()?;
+        let row_len = encoded.iter().map(|bytes| bytes.len()).max().unwrap_or(0) + 1;
+        self.allot_rows(encoded.len(), row_len, |index, cells| {
+            for (cell, &byte) in cells.iter_mut().zip(encoded[index].iter()) {
+                *cell = i32::from(byte);
+            }
+        })
+    }
+
+    /// Allocates `count` zeroed rows of `row_len` cells behind their
+    /// indirection vector and lets `fill` write each row.
+    fn allot_rows(
+        &self,
+        count: usize,
+        row_len: usize,
+        fill: impl Fn(usize, &mut [i32]),
+    ) -> AmxResult<Buffer2D<'_>> {
+        let filled = count;
+        let (count, row_len) = (count.max(1), row_len.max(1));
+        let total = count
+            .checked_mul(row_len)
+            .and_then(|cells| cells.checked_add(count))
+            .filter(|&cells| cells <= i32::MAX as usize / 4)
+            .ok_or(AmxError::Memory)?;
+
+        let mut buffer = self.allot_buffer(total)?;
+        let address = buffer.as_cell();
+        let cells = buffer.as_mut_slice();
+        cells.fill(0);
+        let (vector, data) = cells.split_at_mut(count);
+
+        // `total * 4` fits in an `i32` (checked above), so the offsets do too.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let bytes = |cells: usize| (cells * 4) as i32;
+        let mut rows = Vec::with_capacity(count);
+        for (index, (slot, row)) in vector
+            .iter_mut()
+            .zip(data.chunks_exact_mut(row_len))
+            .enumerate()
+        {
+            let row_start = count + index * row_len;
+            *slot = bytes(row_start - index);
+            rows.push(address + bytes(row_start));
+            if index < filled {
+                fill(index, row);
+            }
+        }
 
-        #[cfg(not(feature = "encoding"))]
-        return Cow::from(string.as_bytes());
+        Ok(Buffer2D::from_parts(self.amx, address, rows, row_len))
+    }
+
+    /// Strings pushed to a script are encoded the way it reads them.
+    fn codec(&self) -> Codec {
+        Codec::for_amx(self.amx)
     }
 }
 
@@ -793,6 +936,352 @@ impl Drop for Allocator<'_> {
     }
 }
 
+/// Liveness flag of every script with resolved natives, keyed by the `AMX`
+/// pointer. Read when a native is resolved; handles keep their own `Arc`, so
+/// a call never touches the table.
+static NATIVE_SCRIPTS: RwLock<Vec<(usize, Arc<AtomicBool>)>> = RwLock::new(Vec::new());
+
+/// The flag shared by every handle resolved in `amx` since its last
+/// [`invalidate_natives`].
+fn native_liveness(amx: *mut AMX) -> Arc<AtomicBool> {
+    let key = amx as usize;
+    let found = NATIVE_SCRIPTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, alive)| Arc::clone(alive));
+    if let Some(alive) = found {
+        return alive;
+    }
+    let mut scripts = NATIVE_SCRIPTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Another thread may have added it between the two locks.
+    if let Some((_, alive)) = scripts.iter().find(|(k, _)| *k == key) {
+        return Arc::clone(alive);
+    }
+    let alive = Arc::new(AtomicBool::new(true));
+    scripts.push((key, Arc::clone(&alive)));
+    alive
+}
+
+/// Invalidates every [`NativeHandle`] resolved in `amx`. The `samp` runtime
+/// calls it when a script unloads; plugins built on the bare SDK call it
+/// from their own `AmxUnload`.
+///
+/// Skipping it is not unsound: a handle only ever calls its host function
+/// against a live script passed in by the caller. It does mean a handle from
+/// an unloaded script keeps working for the next script loaded at the same
+/// address.
+pub fn invalidate_natives(amx: &Amx) {
+    let key = amx.ptr as usize;
+    NATIVE_SCRIPTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|(k, alive)| {
+            if *k == key {
+                alive.store(false, Ordering::Release);
+            }
+            *k != key
+        });
+}
+
+/// A native resolved once by [`Amx::resolve_native`], callable without the
+/// name lookup.
+///
+/// Tied to the script it was resolved in: it fails with
+/// `AmxError::InvalidState` once that script is unloaded, or when called with
+/// another script.
+#[derive(Debug, Clone)]
+pub struct NativeHandle {
+    amx: *mut AMX,
+    index: i32,
+    native: AmxNative,
+    alive: Arc<AtomicBool>,
+}
+
+impl NativeHandle {
+    /// Index of the native in the script's natives table.
+    #[must_use]
+    pub fn index(&self) -> i32 {
+        self.index
+    }
+
+    /// `false` once the script the native was resolved in is unloaded.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    /// Calls the native with raw cells in `amx`, like [`Amx::call_native`].
+    ///
+    /// # Errors
+    /// - `AmxError::InvalidState` if the script was unloaded, or `amx` is
+    ///   not the script the native was resolved in.
+    /// - Any [`AmxError`] propagated from the called native via
+    ///   `amx.error`.
+    pub fn call(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        if amx.ptr != self.amx || !self.is_valid() {
+            return Err(AmxError::InvalidState);
+        }
+
+        // SAFETY: `amx` wraps a live AMX for as long as it is borrowed; the
+        // handle's own pointer is only compared, never dereferenced.
+        unsafe { call_host(amx.ptr, self.native, params) }
+    }
+}
+
+/// Reads the host function bound to native `index` of a VM that keeps it
+/// outside the natives table.
+pub(crate) type BoundNativeReader = fn(*mut AMX, i32) -> Option<AmxNative>;
+
+/// The `amx_Register` of a VM whose natives table cannot hold a host pointer,
+/// and how to read the functions it binds. Set at most once, by that VM.
+static BOUND_NATIVE_READER: OnceLock<(usize, BoundNativeReader)> = OnceLock::new();
+
+/// Lets a VM whose `amx_Register` keeps host functions outside the natives
+/// table report them. Only AMXs whose `amx_Exports` carry that `register`
+/// are read through `reader`; every other AMX reads the table.
+#[cfg(any(test, feature = "test-vm"))]
+pub(crate) fn set_bound_native_reader(register: usize, reader: BoundNativeReader) {
+    let _ = BOUND_NATIVE_READER.set((register, reader));
+}
+
+/// The host function `amx_Register` wrote into a natives-table entry, or
+/// `None` if the native is still unbound.
+///
+/// # Safety
+/// `entry` must point to the entry of native `index` in `amx`, whose exports
+/// are `fn_table`.
+unsafe fn bound_address(
+    amx: *mut AMX,
+    fn_table: usize,
+    index: i32,
+    entry: *const u8,
+) -> Option<AmxNative> {
+    if let Some(&(register, reader)) = BOUND_NATIVE_READER.get()
+        && Register::from_table(fn_table) as usize == register
+    {
+        return reader(amx, index);
+    }
+
+    // First 4 bytes of each entry — both `AMX_FUNCSTUB` and
+    // `ANX_FUNCSTUBNT` start with `u32 address`, the host function
+    // pointer written by `amx_Register`.
+    let address = unsafe { std::ptr::read_unaligned(entry.cast::<u32>()) };
+    if address == 0 {
+        return None;
+    }
+
+    // SAFETY: SA-MP / open.mp are 32-bit; the AMX cell width and host
+    // function pointer width are both 4 bytes. `address` came from
+    // `amx_Register`, which writes a valid `AmxNative` pointer.
+    Some(unsafe { std::mem::transmute::<usize, AmxNative>(address as usize) })
+}
+
+/// The params block of a native call: `[argc * sizeof(cell), arg0, arg1, ...]`.
+/// Bytes, not cells — matches the convention every AMX native implementation
+/// reads (`params[0] / sizeof(cell)` to recover argc).
+fn params_block(params: &[i32]) -> Vec<i32> {
+    let mut buf: Vec<i32> = Vec::with_capacity(params.len() + 1);
+    // `params.len()` bounded by `i32::MAX` in practice; the AMX
+    // would have failed long before reaching 2 billion args.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let argc_bytes = (params.len() as i32) * 4;
+    buf.push(argc_bytes);
+    buf.extend_from_slice(params);
+    buf
+}
+
+/// Calls the host function of a native directly, against `amx`.
+///
+/// # Safety
+/// `amx` must point to a live AMX.
+unsafe fn call_host(amx: *mut AMX, native: AmxNative, params: &[i32]) -> AmxResult<i32> {
+    let mut buf = params_block(params);
+    let retval = native(amx, buf.as_mut_ptr());
+    // Surface VM-side errors set by the native into `amx.error`.
+    let err = unsafe { std::ptr::addr_of!((*amx).error).read_unaligned() };
+    if err > 0 {
+        return Err(err.into());
+    }
+    Ok(retval)
+}
+
+/// What [`call_native!`](crate::call_native) accepts as the native: a name
+/// (`&str`, `String`), looked up on every call, a [`NativeHandle`], or a
+/// host function ([`AmxNative`]) called directly.
+pub trait NativeTarget {
+    /// Calls the native in `amx` with raw cells.
+    ///
+    /// # Errors
+    /// See [`Amx::call_native`] and [`NativeHandle::call`].
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32>;
+}
+
+impl NativeTarget for str {
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        amx.call_native(self, params)
+    }
+}
+
+impl NativeTarget for String {
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        amx.call_native(self, params)
+    }
+}
+
+impl NativeTarget for NativeHandle {
+    /// Fails with `AmxError::InvalidState` if the handle belongs to another
+    /// script — the arguments were allocated in `amx`.
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        self.call(amx, params)
+    }
+}
+
+impl NativeTarget for AmxNative {
+    /// Calls the function with `amx` as its script, whether or not the script
+    /// declares the native — e.g. a [`ScratchAmx`].
+    ///
+    /// Nothing checks that the function is still there. `AmxNative` is a
+    /// plain `extern "C" fn`, callable from safe code, so a pointer into a
+    /// plugin that has been unloaded jumps into unmapped memory. Servers
+    /// only unload plugins at shutdown; a plugin that keeps host functions
+    /// must drop them by then (`Unload`, or `on_free` on open.mp).
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        if amx.ptr.is_null() {
+            return Err(AmxError::InvalidState);
+        }
+        // SAFETY: `amx` wraps a live AMX for as long as it is borrowed.
+        unsafe { call_host(amx.ptr, *self, params) }
+    }
+}
+
+impl<T: NativeTarget + ?Sized> NativeTarget for &T {
+    fn invoke(&self, amx: &Amx, params: &[i32]) -> AmxResult<i32> {
+        (**self).invoke(amx, params)
+    }
+}
+
+/// An AMX with no script in it — no code, publics or natives, only heap and
+/// stack for the arguments of native calls. Stands in for a script when a
+/// plugin calls a host function (an [`AmxNative`]) that no loaded script
+/// declares, or before any script is loaded.
+///
+/// Set up by the server's own `amx_Init`, so every `amx_*` export (and so
+/// `Allocator`, `call_native!`, the natives' argument parsing) works on it,
+/// and released with `amx_Cleanup` on drop. The test VM does not implement
+/// `amx_Init`: there [`ScratchAmx::new`] fails with `AmxError::Init`, and
+/// tests load the same image through `TestVm::from_bytes` instead. The image
+/// is built to pass the checks of Pawn 3.2's `amx_Init`, but is unverified
+/// against the real one of SA-MP or open.mp.
+///
+/// ```rust,ignore
+/// let scratch = ScratchAmx::new(fn_table, ScratchAmx::DEFAULT_CELLS)?;
+/// let amx = scratch.amx();
+/// let mut name = String::new();
+/// call_native!(amx, get_player_name, playerid, &mut name => ref_string(24), 24)?;
+/// ```
+#[derive(Debug)]
+pub struct ScratchAmx {
+    amx: Box<MaybeUninit<AMX>>,
+    /// The image `amx.base` points into: header, one `HALT`, heap and stack.
+    _memory: Vec<i32>,
+    fn_table: usize,
+}
+
+impl ScratchAmx {
+    /// Heap + stack cells of [`ScratchAmx::new`] callers without a size of
+    /// their own in mind (the `pawncc` default).
+    pub const DEFAULT_CELLS: usize = 4096;
+
+    /// Loads an empty script with `cells` cells of heap + stack.
+    ///
+    /// # Errors
+    /// `AmxError::Memory` if `cells` does not fit a cell-addressed image,
+    /// otherwise the error of `amx_Init`.
+    pub fn new(fn_table: usize, cells: usize) -> AmxResult<ScratchAmx> {
+        let mut memory = scratch_image(cells).ok_or(AmxError::Memory)?;
+        let mut amx = Box::new(MaybeUninit::<AMX>::zeroed());
+        let init = Init::from_table(fn_table);
+        amx_try!(init(amx.as_mut_ptr(), memory.as_mut_ptr().cast()));
+        Ok(ScratchAmx {
+            amx,
+            _memory: memory,
+            fn_table,
+        })
+    }
+
+    /// The script, for `call_native!` and the rest of the [`Amx`] API.
+    #[must_use]
+    pub fn amx(&self) -> Amx {
+        Amx::new(self.amx.as_ptr().cast_mut(), self.fn_table)
+    }
+}
+
+impl Drop for ScratchAmx {
+    fn drop(&mut self) {
+        // Releases what `amx_Init` set up (the JIT and overlay state on
+        // servers that use them) before the image memory goes away.
+        let cleanup = Cleanup::from_table(self.fn_table);
+        cleanup(self.amx.as_mut_ptr());
+    }
+}
+
+/// Where the data segment of the scratch image starts: the header, empty
+/// tables, a name table holding only its length word, and `HALT 0`.
+const SCRATCH_CODE_END: usize = std::mem::size_of::<AMX_HEADER>() + 4 + 8;
+
+/// The `.amx` image of [`ScratchAmx`], sized for `cells` of heap + stack,
+/// as cells so it is aligned the way `amx_Init` wants it. `None` if it
+/// would outgrow the 2 GiB a cell addresses.
+///
+/// The header carries what `amx_Init` of Pawn 3.2 checks: the 32-bit cell
+/// magic, file and AMX version 8 (the `pawncc` 3.2 output both servers
+/// load), name-table stubs (`ANX_FUNCSTUBNT`), `hea == size`, and a stack.
+/// It is only checked against those rules and the test VM's loader — no
+/// server's `amx_Init` runs in `cargo test`.
+pub(crate) fn scratch_image(cells: usize) -> Option<Vec<i32>> {
+    const HALT: i32 = 120;
+    let header_size = std::mem::size_of::<AMX_HEADER>();
+    let cell = |value: usize| i32::try_from(value).ok();
+    // The publics, natives, libraries, pubvars and tags tables are empty,
+    // so they all start where the name table does.
+    let tables = cell(header_size)?;
+    let cod = cell(header_size + 4)?;
+    let dat = cell(SCRATCH_CODE_END)?;
+    let stp = cell(cells.checked_mul(4)?.checked_add(SCRATCH_CODE_END)?)?;
+
+    let header = AMX_HEADER {
+        size: dat,
+        magic: 0xF1E0,
+        file_version: 8,
+        amx_version: 8,
+        flags: 0,
+        defsize: std::mem::size_of::<crate::raw::types::ANX_FUNCSTUBNT>() as i16,
+        cod,
+        dat,
+        hea: dat,
+        stp,
+        cip: -1, // no `main`
+        publics: tables,
+        natives: tables,
+        libraries: tables,
+        pubvars: tables,
+        tags: tables,
+        nametable: tables,
+    };
+
+    let mut memory = vec![0_i32; usize::try_from(stp).ok()? / 4];
+    // SAFETY: the header fits the first `SCRATCH_CODE_END` bytes of `memory`;
+    // the write is unaligned, as `AMX_HEADER` is packed.
+    unsafe { memory.as_mut_ptr().cast::<AMX_HEADER>().write_unaligned(header) };
+    // The name table's longest name (none) stays 0; the code is `HALT 0`.
+    memory[header_size / 4 + 1] = HALT;
+    Some(memory)
+}
+
 #[cfg(test)]
 mod vm_tests {
     use super::Amx;
@@ -5,10 +5,12 @@
 //! - `args[1..]` = the cells with each argument, in signature order
 //!
 //! This module wraps that indirection and converts each cell to the correct
-//! Rust type via [`AmxCell`].
+//! Rust type via [`AmxCell`]. The trailing `...` of a variadic native is
+//! exposed as [`Variadic`].
 
 use crate::amx::Amx;
-use crate::cell::AmxCell;
+use crate::cell::{AmxCell, AmxPrimitive, AmxString, Ref};
+use crate::error::AmxResult;
 
 /// Typed list of arguments for a native function.
 ///
@@ -67,6 +69,22 @@ impl<'a> Args<'a> {
         unsafe { T::from_raw(self.amx, self.params.add(offset + 1).read()).ok() }
     }
 
+    /// Every argument from the cursor to the end of the list, as the `...`
+    /// of a variadic native. Moves the cursor to the end.
+    ///
+    /// `#[native]` calls this for a trailing [`Variadic`] parameter.
+    pub fn rest(&mut self) -> Variadic<'a> {
+        let count = self.count();
+        let start = self.offset.min(count);
+        self.offset = count;
+        Variadic {
+            amx: self.amx,
+            // `start <= count`: at most one past the last argument.
+            params: unsafe { self.params.add(start + 1) },
+            len: count - start,
+        }
+    }
+
     /// Resets the [`next_arg`] cursor back to the start of the list.
     ///
     /// [`next_arg`]: Args::next_arg
@@ -91,6 +109,110 @@ impl<'a> Args<'a> {
     }
 }
 
+/// The `...` arguments of a variadic native.
+///
+/// Pawn passes every variadic argument by reference — the cell holds the
+/// address of the value, even for plain integers. Each [`VarArg`] resolves
+/// that address only when read, as an int, a float, a string or a
+/// writable [`Ref`].
+///
+/// ```rust,ignore
+/// // native Sum(...);
+/// #[native(name = "Sum")]
+/// fn sum(&mut self, _amx: &Amx, values: Variadic) -> AmxResult<i32> {
+///     values.iter().map(|value| value.int()).sum()
+/// }
+/// ```
+pub struct Variadic<'a> {
+    amx: &'a Amx,
+    params: *const i32,
+    len: usize,
+}
+
+impl<'a> Variadic<'a> {
+    /// Number of variadic arguments.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Argument at `index` (zero-indexed, counted from the first variadic
+    /// one). `None` if out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<VarArg<'a>> {
+        if index >= self.len {
+            return None;
+        }
+        Some(VarArg {
+            amx: self.amx,
+            // `index < len`, checked against `Args::count` in `Args::rest`.
+            address: unsafe { self.params.add(index).read() },
+        })
+    }
+
+    /// Iterates the arguments in call order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = VarArg<'a>> + '_ {
+        (0..self.len).map(|index| VarArg {
+            amx: self.amx,
+            // `index < len`, see `get`.
+            address: unsafe { self.params.add(index).read() },
+        })
+    }
+}
+
+/// One variadic argument: the AMX address Pawn passed for it.
+#[derive(Clone, Copy)]
+pub struct VarArg<'a> {
+    amx: &'a Amx,
+    address: i32,
+}
+
+impl<'a> VarArg<'a> {
+    /// The raw AMX address of the argument.
+    #[must_use]
+    pub fn address(&self) -> i32 {
+        self.address
+    }
+
+    /// The argument as a writable reference — assigning through it
+    /// changes the variable in the script.
+    ///
+    /// # Errors
+    /// `AmxError::MemoryAccess` if the address is outside the AMX data.
+    pub fn to_ref<T: AmxPrimitive>(&self) -> AmxResult<Ref<'a, T>> {
+        self.amx.get_ref(self.address)
+    }
+
+    /// The argument as an integer.
+    ///
+    /// # Errors
+    /// See [`to_ref`](Self::to_ref).
+    pub fn int(&self) -> AmxResult<i32> {
+        self.to_ref::<i32>().map(|value| *value)
+    }
+
+    /// The argument as a `Float:`.
+    ///
+    /// # Errors
+    /// See [`to_ref`](Self::to_ref).
+    pub fn float(&self) -> AmxResult<f32> {
+        self.to_ref::<f32>().map(|value| *value)
+    }
+
+    /// The argument as a string (packed or unpacked).
+    ///
+    /// # Errors
+    /// `AmxError::MemoryAccess` if the address is outside the AMX data.
+    pub fn string(&self) -> AmxResult<AmxString<'a>> {
+        AmxString::from_raw(self.amx, self.address)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +251,30 @@ mod tests {
         assert!(args.get::<crate::cell::Ref<i32>>(1).is_none());
     }
 
+    #[test]
+    fn rest_takes_the_remaining_arguments() {
+        let data: [i32; 4] = [3 * 4, 11, 22, 33];
+        let amx = Amx::new(std::ptr::null_mut(), 0);
+        let mut args = Args::new(&amx, data.as_ptr());
+        args.offset = 1;
+
+        let rest = args.rest();
+        assert_eq!(rest.len(), 2);
+        let addresses: Vec<i32> = rest.iter().map(|arg| arg.address()).collect();
+        assert_eq!(addresses, [22, 33]);
+        assert!(rest.get(2).is_none());
+        assert!(args.rest().is_empty());
+    }
+
+    #[test]
+    fn rest_past_the_end_is_empty() {
+        let data: [i32; 2] = [4, 11];
+        let amx = Amx::new(std::ptr::null_mut(), 0);
+        let mut args = Args::new(&amx, data.as_ptr());
+        args.offset = 5;
+        assert!(args.rest().is_empty());
+    }
+
     #[test]
     fn reset_resets_offset() {
         let data: [i32; 1] = [0];
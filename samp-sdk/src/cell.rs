@@ -7,7 +7,9 @@
 //!
 //! - [`Ref<T>`]: typed pointer to a cell (by-reference output of natives).
 //! - [`Buffer`] / [`UnsizedBuffer`]: array of contiguous cells.
+//! - [`Buffer2D`] / [`ArrayView`]: 2-D array (indirection vector + rows).
 //! - [`AmxString`]: native Pawn string (cell vector with `0` terminator).
+//! - [`AmxStruct`] / [`StructRef`]: enum-structured arrays mapped onto structs.
 //! - [`AmxCell`], [`AmxPrimitive`], [`CellConvert`]: conversion traits.
 
 use std::marker::PhantomData;
@@ -16,13 +18,17 @@ use std::ops::{Deref, DerefMut};
 use crate::amx::Amx;
 use crate::error::AmxResult;
 
+pub mod array2d;
 pub mod buffer;
 pub mod repr;
 pub mod string;
+pub mod structure;
 
+pub use array2d::{ArrayView, Buffer2D};
 pub use buffer::{Buffer, UnsizedBuffer};
 pub use repr::{AmxCell, AmxPrimitive, CellConvert};
 pub use string::AmxString;
+pub use structure::{AmxStruct, StructRef};
 
 /// Typed pointer to a live cell in the AMX heap/data.
 ///
@@ -0,0 +1,209 @@
+//! Two-dimensional Pawn arrays (`new names[MAX_PLAYERS][24]`).
+//!
+//! Pawn stores a 2-D array as an *indirection vector* followed by the rows.
+//! The vector has one cell per row, and each cell holds the byte offset from
+//! that cell to the start of its row:
+//!
+//! ```text
+//! address → [ off0 ][ off1 ][ off2 ][ row 0 … ][ row 1 … ][ row 2 … ]
+//!              │       │       └──────────────────────────────┘
+//!              │       └─────────────────────┘
+//!              └───────────┘
+//! ```
+//!
+//! Row 0 starts right after the vector, so `off0 / 4` is the number of rows.
+//! [`Buffer2D`] decodes the vector once, checks every row against the VM data
+//! region and hands rows out as [`Buffer`]s or [`AmxString`]s. [`ArrayView`]
+//! is the native argument form, with the row length as a const parameter.
+
+use std::ops::Deref;
+
+use super::string::Codec;
+use super::{AmxCell, AmxString, Buffer};
+use crate::amx::Amx;
+use crate::error::{AmxError, AmxResult};
+
+/// A 2-D Pawn array with rows of `row_len` cells.
+///
+/// Every row was checked against the VM data region when the `Buffer2D` was
+/// built, so [`row`] and [`string`] only fail on an out-of-range index.
+///
+/// [`row`]: Buffer2D::row
+/// [`string`]: Buffer2D::string
+pub struct Buffer2D<'amx> {
+    amx: &'amx Amx,
+    address: i32,
+    rows: Vec<i32>,
+    row_len: usize,
+}
+
+impl<'amx> Buffer2D<'amx> {
+    /// Decodes the indirection vector at `address` (an AMX address, e.g. the
+    /// raw value of a `names[][]` argument) for rows of `row_len` cells.
+    ///
+    /// # Errors
+    /// - `AmxError::Params` if the vector is malformed (its first offset is
+    ///   not a positive multiple of 4, or a row is misaligned).
+    /// - `AmxError::MemoryAccess` if the vector or any row leaves the VM data
+    ///   region.
+    pub fn new(amx: &'amx Amx, address: i32, row_len: usize) -> AmxResult<Self> {
+        let end = amx.stp().ok_or(AmxError::MemoryAccess)?;
+        let first = *amx.get_ref::<i32>(address)?;
+        if first <= 0 || first % 4 != 0 {
+            return Err(AmxError::Params);
+        }
+        let row_bytes = i32::try_from(row_len)
+            .ok()
+            .and_then(|len| len.checked_mul(4))
+            .ok_or(AmxError::MemoryAccess)?;
+        let count = first / 4;
+        if address
+            .checked_add(first)
+            .is_none_or(|vector_end| vector_end > end)
+        {
+            return Err(AmxError::MemoryAccess);
+        }
+
+        let rows = (0..count)
+            .map(|index| {
+                let slot = address + index * 4;
+                let offset = *amx.get_ref::<i32>(slot)?;
+                let row = slot.checked_add(offset).ok_or(AmxError::MemoryAccess)?;
+                if row % 4 != 0 {
+                    return Err(AmxError::Params);
+                }
+                if row < 0
+                    || row
+                        .checked_add(row_bytes)
+                        .is_none_or(|row_end| row_end > end)
+                {
+                    return Err(AmxError::MemoryAccess);
+                }
+                amx.get_ref::<i32>(row)?;
+                Ok(row)
+            })
+            .collect::<AmxResult<Vec<i32>>>()?;
+
+        Ok(Buffer2D::from_parts(amx, address, rows, row_len))
+    }
+
+    /// An array whose row addresses are already known to be valid, such as
+    /// one the `Allocator` just laid out.
+    pub(crate) fn from_parts(
+        amx: &'amx Amx,
+        address: i32,
+        rows: Vec<i32>,
+        row_len: usize,
+    ) -> Buffer2D<'amx> {
+        Buffer2D {
+            amx,
+            address,
+            rows,
+            row_len,
+        }
+    }
+
+    /// Number of rows (`sizeof names`).
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Cells per row (`sizeof names[]`).
+    #[must_use]
+    pub fn row_len(&self) -> usize {
+        self.row_len
+    }
+
+    /// `true` if the array has no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Row `index` as a `Buffer` of `row_len` cells. `None` if out of bounds.
+    #[must_use]
+    pub fn row(&self, index: usize) -> Option<Buffer<'amx>> {
+        let address = *self.rows.get(index)?;
+        let first = self.amx.get_ref(address).ok()?;
+        Some(Buffer::new(first, self.row_len).with_codec(Codec::for_amx(self.amx)))
+    }
+
+    /// Row `index` read as a string (packed or unpacked), up to its `0`
+    /// terminator or the end of the row. `None` if out of bounds.
+    #[must_use]
+    pub fn string(&self, index: usize) -> Option<AmxString<'amx>> {
+        self.row(index).map(Buffer::into_string)
+    }
+
+    /// Every row, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Buffer<'amx>> + '_ {
+        (0..self.rows()).filter_map(|index| self.row(index))
+    }
+
+    /// Every row read as a string, in order.
+    pub fn strings(&self) -> impl Iterator<Item = AmxString<'amx>> + '_ {
+        (0..self.rows()).filter_map(|index| self.string(index))
+    }
+}
+
+// Like `Buffer`, a `Buffer2D` needs its row length before it can be built;
+// parse `ArrayView<N>` instead, or call `Buffer2D::new`.
+impl<'amx> AmxCell<'amx> for Buffer2D<'amx> {
+    fn as_cell(&self) -> i32 {
+        self.address
+    }
+}
+
+impl std::fmt::Debug for Buffer2D<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Buffer2D")
+            .field("address", &self.address)
+            .field("rows", &self.rows())
+            .field("row_len", &self.row_len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A `names[][N]` native argument: a [`Buffer2D`] whose rows are `N` cells.
+///
+/// The row count is read from the array itself, so the Pawn prototype needs
+/// no `sizeof` parameters:
+///
+/// ```rust,ignore
+/// // native CountAdmins(const names[][MAX_PLAYER_NAME]);
+/// #[native(name = "CountAdmins")]
+/// fn count_admins(&mut self, _amx: &Amx, names: ArrayView<24>) -> usize {
+///     names.strings().filter(|name| name.starts_with("[A]")).count()
+/// }
+/// ```
+///
+/// `N` counts cells, so a packed `names[][24 char]` is `ArrayView<6>`.
+pub struct ArrayView<'amx, const N: usize>(Buffer2D<'amx>);
+
+impl<'amx, const N: usize> AmxCell<'amx> for ArrayView<'amx, N> {
+    fn from_raw(amx: &'amx Amx, cell: i32) -> AmxResult<Self>
+    where
+        Self: 'amx,
+    {
+        Buffer2D::new(amx, cell, N).map(ArrayView)
+    }
+
+    fn as_cell(&self) -> i32 {
+        self.0.as_cell()
+    }
+}
+
+impl<'amx, const N: usize> Deref for ArrayView<'amx, N> {
+    type Target = Buffer2D<'amx>;
+
+    fn deref(&self) -> &Buffer2D<'amx> {
+        &self.0
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for ArrayView<'_, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
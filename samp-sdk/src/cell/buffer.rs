@@ -3,10 +3,10 @@
 
 use std::ops::{Deref, DerefMut};
 
-use super::{AmxCell, Ref};
+use super::{AmxCell, AmxString, Ref};
 use crate::amx::Amx;
 use crate::cell::repr::CellConvert;
-use crate::cell::string;
+use crate::cell::string::{self, Codec};
 use crate::error::AmxResult;
 
 /// AMX cell array with a known size.
@@ -31,18 +31,41 @@ use crate::error::AmxResult;
 pub struct Buffer<'amx> {
     inner: Ref<'amx, i32>,
     len: usize,
+    codec: Codec,
 }
 
 impl<'amx> Buffer<'amx> {
     /// Builds a `Buffer` from the `Ref` to the first cell and its size.
+    ///
+    /// The buffer uses the default encoding; buffers parsed from a native
+    /// argument follow their script's encoding instead.
     #[must_use]
     pub fn new(reference: Ref<'amx, i32>, len: usize) -> Buffer<'amx> {
         Buffer {
             inner: reference,
             len,
+            codec: Codec::default(),
         }
     }
 
+    /// Writes strings with `encoding` instead of the script's or the default
+    /// one.
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn with_encoding(mut self, encoding: &'static encoding_rs::Encoding) -> Buffer<'amx> {
+        self.codec = Codec::with(encoding);
+        self
+    }
+
+    pub(crate) fn with_codec(mut self, codec: Codec) -> Buffer<'amx> {
+        self.codec = codec;
+        self
+    }
+
+    pub(crate) fn codec(&self) -> Codec {
+        self.codec
+    }
+
     /// Number of cells in the buffer.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -104,10 +127,32 @@ impl<'amx> Buffer<'amx> {
     /// Requires `s.len() + 1` cells of space.
     ///
     /// # Errors
-    /// `AmxError::General` if the encoded string is >= the buffer size.
+    /// - `AmxError::General` if the encoded string is >= the buffer size.
+    /// - `AmxError::Unmappable` if a character cannot be encoded and the
+    ///   policy is `Unmappable::Error`; the buffer is left untouched.
     pub fn write_str(&mut self, s: &str) -> AmxResult<()> {
         string::put_in_buffer(self, s)
     }
+
+    /// Writes a Rust string into the buffer packed (4 characters per cell,
+    /// `0` terminator), the layout of a Pawn `buf[N char]` array.
+    ///
+    /// Requires `s.len() / 4 + 1` cells of space.
+    ///
+    /// # Errors
+    /// - `AmxError::General` if the encoded string does not fit.
+    /// - `AmxError::Unmappable` as in [`write_str`](Buffer::write_str).
+    pub fn write_str_packed(&mut self, s: &str) -> AmxResult<()> {
+        string::put_in_buffer_packed(self, s)
+    }
+
+    /// Reads the buffer as a string (packed or unpacked), up to its `0`
+    /// terminator or the end of the buffer.
+    #[must_use]
+    pub fn into_string(self) -> AmxString<'amx> {
+        let len = string::str_len(&self);
+        AmxString::from_buffer_parts(self, len)
+    }
 }
 
 // `Buffer` cannot be parsed directly from a cell — use `UnsizedBuffer`
@@ -155,6 +200,7 @@ pub struct UnsizedBuffer<'amx> {
     ///
     /// [`into_sized_buffer`]: UnsizedBuffer::into_sized_buffer
     max_cells: usize,
+    codec: Codec,
 }
 
 impl<'amx> UnsizedBuffer<'amx> {
@@ -174,7 +220,19 @@ impl<'amx> UnsizedBuffer<'amx> {
             "into_sized_buffer() received len={len} above the {MAX_BUFFER_CELLS} limit"
         );
         let len = len.min(self.max_cells).min(MAX_BUFFER_CELLS);
-        Buffer::new(self.inner, len)
+        Buffer::new(self.inner, len).with_codec(self.codec)
+    }
+
+    /// Writes strings with `encoding` instead of the script's or the default
+    /// one.
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn with_encoding(
+        mut self,
+        encoding: &'static encoding_rs::Encoding,
+    ) -> UnsizedBuffer<'amx> {
+        self.codec = Codec::with(encoding);
+        self
     }
 
     /// Pointer to the first cell.
@@ -197,6 +255,7 @@ impl<'amx> UnsizedBuffer<'amx> {
         UnsizedBuffer {
             inner,
             max_cells: usize::MAX,
+            codec: Codec::default(),
         }
     }
 
@@ -206,12 +265,25 @@ impl<'amx> UnsizedBuffer<'amx> {
     /// recommended way to fill an output string in natives.
     ///
     /// # Errors
-    /// `AmxError::General` if the encoded `s` is >= `max_len` (no room for
-    /// the `0` terminator).
+    /// - `AmxError::General` if the encoded `s` is >= `max_len` (no room for
+    ///   the `0` terminator).
+    /// - `AmxError::Unmappable` as in [`Buffer::write_str`].
     pub fn write_str(self, max_len: usize, s: &str) -> AmxResult<()> {
         let mut buf = self.into_sized_buffer(max_len);
         string::put_in_buffer(&mut buf, s)
     }
+
+    /// Packed form of [`write_str`](UnsizedBuffer::write_str): sizes the
+    /// buffer to `max_len` cells (`sizeof buf` of a `buf[N char]` array) and
+    /// writes `s` four characters per cell.
+    ///
+    /// # Errors
+    /// - `AmxError::General` if the encoded `s` needs more than `max_len` cells.
+    /// - `AmxError::Unmappable` as in [`Buffer::write_str`].
+    pub fn write_str_packed(self, max_len: usize, s: &str) -> AmxResult<()> {
+        let mut buf = self.into_sized_buffer(max_len);
+        string::put_in_buffer_packed(&mut buf, s)
+    }
 }
 
 impl<'amx> AmxCell<'amx> for UnsizedBuffer<'amx> {
@@ -223,7 +295,11 @@ impl<'amx> AmxCell<'amx> for UnsizedBuffer<'amx> {
         let max_cells = amx.stp().map_or(usize::MAX, |stp| {
             usize::try_from((stp - cell).max(0) / 4).unwrap_or(0)
         });
-        Ok(UnsizedBuffer { inner, max_cells })
+        Ok(UnsizedBuffer {
+            inner,
+            max_cells,
+            codec: Codec::for_amx(amx),
+        })
     }
 
     #[inline]
@@ -252,6 +328,7 @@ mod tests {
         UnsizedBuffer {
             inner: make_ref(data),
             max_cells: usize::MAX,
+            codec: Codec::default(),
         }
     }
 
@@ -363,6 +440,7 @@ mod tests {
         let ub = UnsizedBuffer {
             inner: make_ref(&mut data),
             max_cells: 3,
+            codec: Codec::default(),
         };
         let buf = ub.into_sized_buffer(1000);
         assert_eq!(buf.len(), 3);
@@ -565,6 +643,39 @@ mod tests {
         assert!(ub.write_str(3, "abc").is_err());
     }
 
+    // --- write_str_packed ---
+
+    #[test]
+    fn write_str_packed_fills_four_chars_per_cell() {
+        // "hello" -> 0x68656c6c, 0x6f000000; cells past the terminator are
+        // left alone, as with `write_str`.
+        let mut data = vec![-1i32; 3];
+        let mut buf = make_buffer(&mut data);
+        assert!(buf.write_str_packed("hello").is_ok());
+        assert_eq!(data, [0x6865_6c6c, 0x6f00_0000, -1]);
+    }
+
+    #[test]
+    fn write_str_packed_needs_a_terminator_cell() {
+        // "abcd" fills one cell and needs a second for the terminator.
+        let mut data = vec![0i32; 1];
+        let mut buf = make_buffer(&mut data);
+        assert!(buf.write_str_packed("abcd").is_err());
+        assert!(buf.write_str_packed("abc").is_ok());
+        assert_eq!(data, [0x6162_6300]);
+    }
+
+    #[test]
+    fn unsized_write_str_packed_sizes_in_cells() {
+        let mut data = vec![0i32; 2];
+        let ub = make_unsized(&mut data);
+        assert!(ub.write_str_packed(2, "hi").is_ok());
+        assert_eq!(data, [0x6869_0000, 0]);
+
+        let ub = make_unsized(&mut data);
+        assert!(ub.write_str_packed(2, "12345678").is_err());
+    }
+
     // --- Adversarial / property tests: indexed access must stay in bounds for
     //     any index, and sizing must never exceed the declared length. ---
 
@@ -607,6 +718,7 @@ mod tests {
             let ub = UnsizedBuffer {
                 inner: make_ref(&mut data),
                 max_cells: bound,
+                codec: Codec::default(),
             };
             let requested = (lcg(&mut seed) % 1000) as usize;
             let buf = ub.into_sized_buffer(requested);
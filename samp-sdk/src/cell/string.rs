@@ -6,11 +6,19 @@
 //! - **Packed**: 4 characters packed into each i32 cell (bits 31..24,
 //!   23..16, 15..8, 7..0). The first cell signals the mode if its value
 //!   exceeds [`MAX_UNPACKED`]; the SDK detects it automatically in [`to_bytes`].
+//!   A packed string of `len` characters takes `len / 4 + 1` cells
+//!   ([`packed_cells`]).
+//!
+//! Both forms can be written: [`AmxString::new`] / [`AmxString::new_packed`],
+//! `Buffer::write_str` / `Buffer::write_str_packed` and their `UnsizedBuffer`
+//! and `Allocator` counterparts.
 //!
 //! [`to_bytes`]: AmxString::to_bytes
 
+use std::borrow::Cow;
 use std::cell::OnceCell;
 use std::fmt;
+use std::iter::FusedIterator;
 use std::ops::Deref;
 
 use super::{AmxCell, Buffer, UnsizedBuffer};
@@ -22,7 +30,24 @@ use crate::error::AmxResult;
 /// Upper bound for the first cell of an unpacked string.
 ///
 /// Values above this indicate a packed string (4 chars/cell).
-const MAX_UNPACKED: i32 = 0x00FF_FFFF;
+pub(crate) const MAX_UNPACKED: i32 = 0x00FF_FFFF;
+
+/// Cap on how many bytes are read from a single string, so a corrupted
+/// length cannot trigger an unbounded read or allocation (1 MiB).
+const MAX_STRING_LEN: usize = 1024 * 1024;
+
+/// Whether a string whose first cell is `first` is packed. The comparison is
+/// unsigned, as in `amx_StrLen`, so a packed string starting with a byte
+/// `>= 0x80` (a negative cell) is still recognised.
+pub(crate) fn is_packed(first: i32) -> bool {
+    first.cast_unsigned() > MAX_UNPACKED.cast_unsigned()
+}
+
+/// Cells taken by a packed string of `len` characters, terminator included.
+#[must_use]
+pub const fn packed_cells(len: usize) -> usize {
+    len / 4 + 1
+}
 
 /// Native Pawn string — packed or unpacked.
 ///
@@ -75,6 +100,27 @@ impl<'amx> AmxString<'amx> {
         }
     }
 
+    /// Creates a packed `AmxString` from an allocated buffer: `bytes` go four
+    /// per cell, first character in the high byte, followed by a `0`
+    /// terminator.
+    ///
+    /// # Safety
+    /// `buffer` must have at least [`packed_cells`]`(bytes.len())` cells and
+    /// remain alive for `'amx`.
+    #[must_use]
+    pub unsafe fn new_packed(mut buffer: Buffer<'amx>, bytes: &[u8]) -> AmxString<'amx> {
+        pack(
+            bytes,
+            &mut buffer.as_mut_slice()[..packed_cells(bytes.len())],
+        );
+
+        AmxString {
+            len: bytes.len(),
+            inner: buffer,
+            decoded: OnceCell::new(),
+        }
+    }
+
     /// Constructor for tests/benchmarks — assumes `inner` is already populated.
     /// Not part of the stable API.
     #[doc(hidden)]
@@ -93,48 +139,50 @@ impl<'amx> AmxString<'amx> {
     /// from the value of the first cell. Caps the read at 1 MiB to avoid
     /// uncontrolled allocation if `len` is corrupted.
     pub fn to_bytes(&self) -> Vec<u8> {
-        const MAX_STRING_LEN: usize = 1024 * 1024;
-        // An empty backing buffer has no first cell to probe for the
-        // packed/unpacked marker — return early instead of indexing `[0]`
-        // (which would panic). Reachable only via a corrupted length.
-        if self.inner.is_empty() {
-            return Vec::new();
-        }
-        let len = self.len.min(MAX_STRING_LEN);
-        let mut vec = Vec::with_capacity(len);
-
-        // packed string
-        if self.inner[0] > MAX_UNPACKED {
-            let cells = self.inner.as_slice();
-            let max_cells = cells.len();
-            let mut cell_idx = 0usize;
-            let mut mark = 3usize;
-            for _ in 0..len {
-                if cell_idx >= max_cells {
-                    break;
-                }
-                // Byte extraction from a packed i32 cell — truncation is intentional.
-                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-                let ch = (cells[cell_idx] >> (mark * 8)) as u8;
-                if ch == b'\0' {
-                    break;
-                }
-                vec.push(ch);
-                mark = (mark + 3) % 4;
-                if mark == 3 {
-                    cell_idx += 1;
-                }
-            }
-        } else {
-            for item in self.inner.iter().take(len) {
-                // An unpacked cell holds a single byte; truncation is intentional.
-                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-                let byte = *item as u8;
-                vec.push(byte);
-            }
-        }
+        cells_to_bytes(self.inner.as_slice(), self.len.min(MAX_STRING_LEN))
+    }
 
-        vec
+    /// Iterates over the raw bytes of the string straight from the cells,
+    /// without allocating or decoding. Yields the same bytes as
+    /// [`to_bytes`](AmxString::to_bytes), in the script's encoding.
+    pub fn bytes(&self) -> Bytes<'_> {
+        Bytes::new(self.inner.as_slice(), self.len.min(MAX_STRING_LEN))
+    }
+
+    /// `true` if the string starts with `prefix`.
+    ///
+    /// Compares the raw cells against `prefix` encoded like
+    /// [`allot_string`](crate::amx::Allocator::allot_string) would, so the
+    /// string is neither copied nor decoded. `str::starts_with`, reached
+    /// through `Deref`, decodes first but takes any pattern.
+    pub fn starts_with_bytes(&self, prefix: &str) -> bool {
+        let Some(prefix) = self.codec().encode_exact(prefix) else {
+            return false;
+        };
+        let mut bytes = self.bytes();
+        prefix
+            .iter()
+            .all(|&expected| bytes.next() == Some(expected))
+    }
+
+    /// `true` if the string equals `other`, ignoring ASCII case.
+    ///
+    /// Compares the raw cells like
+    /// [`starts_with_bytes`](AmxString::starts_with_bytes), without copying
+    /// or decoding; bytes outside ASCII must match exactly.
+    pub fn eq_ignore_ascii_case_bytes(&self, other: &str) -> bool {
+        let Some(other) = self.codec().encode_exact(other) else {
+            return false;
+        };
+        if other.len() != self.len {
+            return false;
+        }
+        let mut bytes = self.bytes();
+        other.iter().all(|expected| {
+            bytes
+                .next()
+                .is_some_and(|byte| byte.eq_ignore_ascii_case(expected))
+        }) && bytes.next().is_none()
     }
 
     /// String length in characters (excluding the `0` terminator).
@@ -152,6 +200,30 @@ impl<'amx> AmxString<'amx> {
         self.inner.len()
     }
 
+    /// Decodes this string with `encoding` instead of the script's or the
+    /// default one. Also used by `starts_with_bytes` and
+    /// `eq_ignore_ascii_case_bytes`.
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn with_encoding(self, encoding: &'static encoding_rs::Encoding) -> AmxString<'amx> {
+        AmxString {
+            inner: self.inner.with_encoding(encoding),
+            len: self.len,
+            decoded: OnceCell::new(),
+        }
+    }
+
+    /// The encoding this string decodes with.
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn encoding(&self) -> &'static encoding_rs::Encoding {
+        self.codec().encoding()
+    }
+
+    pub(crate) fn codec(&self) -> Codec {
+        self.inner.codec()
+    }
+
     /// Explicit form of the `Deref` to `&str`.
     ///
     /// Useful when type inference does not trigger auto-deref (e.g. a generic
@@ -161,14 +233,188 @@ impl<'amx> AmxString<'amx> {
     }
 }
 
-/// Decodes the raw bytes using the configured encoding (UTF-8 by default;
-/// Windows-1251 etc. via the `encoding` feature).
-fn decode_bytes(bytes: &[u8]) -> String {
+/// Borrowing iterator over the raw bytes of an [`AmxString`], returned by
+/// [`AmxString::bytes`].
+///
+/// Reads packed and unpacked strings alike; a packed string ends at its
+/// first `0` byte.
+#[derive(Debug, Clone)]
+pub struct Bytes<'a>(BytesInner<'a>);
+
+#[derive(Debug, Clone)]
+enum BytesInner<'a> {
+    /// One byte per cell.
+    Unpacked(std::slice::Iter<'a, i32>),
+    /// Four bytes per cell, high byte first; `pos` and `len` count bytes.
+    Packed {
+        cells: &'a [i32],
+        pos: usize,
+        len: usize,
+    },
+}
+
+impl<'a> Bytes<'a> {
+    /// At most `len` bytes of `cells`, never past the end of the slice.
+    fn new(cells: &'a [i32], len: usize) -> Bytes<'a> {
+        match cells.first() {
+            Some(&first) if is_packed(first) => Bytes(BytesInner::Packed {
+                cells,
+                pos: 0,
+                len: len.min(cells.len() * 4),
+            }),
+            _ => Bytes(BytesInner::Unpacked(cells[..len.min(cells.len())].iter())),
+        }
+    }
+}
+
+impl Iterator for Bytes<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        match &mut self.0 {
+            // An unpacked cell holds a single byte; truncation is intentional.
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            BytesInner::Unpacked(cells) => cells.next().map(|&cell| cell as u8),
+            BytesInner::Packed { cells, pos, len } => {
+                if *pos >= *len {
+                    return None;
+                }
+                let shift = (3 - *pos % 4) * 8;
+                // Byte extraction from a packed i32 cell — truncation is intentional.
+                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                let byte = (cells[*pos / 4] >> shift) as u8;
+                if byte == 0 {
+                    *pos = *len;
+                    return None;
+                }
+                *pos += 1;
+                Some(byte)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            BytesInner::Unpacked(cells) => cells.size_hint(),
+            // A packed string may end early at a `0` byte.
+            BytesInner::Packed { pos, len, .. } => (0, Some(len - pos)),
+        }
+    }
+}
+
+impl FusedIterator for Bytes<'_> {}
+
+/// Reads up to `len` bytes of a Pawn string out of `cells`, packed or
+/// unpacked depending on the first cell. Never reads past `cells`.
+pub(crate) fn cells_to_bytes(cells: &[i32], len: usize) -> Vec<u8> {
+    let mut vec = Vec::with_capacity(len.min(cells.len() * 4));
+    vec.extend(Bytes::new(cells, len));
+    vec
+}
+
+/// Length in characters of the string stored in `cells`: up to the first `0`,
+/// or the whole slice when there is no terminator. Packed strings count four
+/// characters per cell.
+pub(crate) fn str_len(cells: &[i32]) -> usize {
+    match cells.first() {
+        Some(&first) if is_packed(first) => cells_to_bytes(cells, cells.len() * 4).len(),
+        _ => cells
+            .iter()
+            .position(|&cell| cell == 0)
+            .unwrap_or(cells.len()),
+    }
+}
+
+/// The encoding a string or buffer converts with: an explicit choice (per
+/// script or per argument), or the default encoding at the time of use.
+/// Zero-sized without the `encoding` feature, where everything is UTF-8.
+///
+/// `Codec::default()` follows the default encoding. [`AmxStruct`] gets the
+/// codec of the array's script and passes it to the string field helpers.
+///
+/// [`AmxStruct`]: crate::cell::AmxStruct
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Codec {
+    #[cfg(feature = "encoding")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl Codec {
+    /// The encoding of strings coming from (or going to) `amx`.
+    #[cfg_attr(
+        not(feature = "encoding"),
+        // Without encodings there is nothing to look up per script.
+        allow(unused_variables)
+    )]
+    #[must_use]
+    pub fn for_amx(amx: &Amx) -> Codec {
+        Codec {
+            #[cfg(feature = "encoding")]
+            encoding: encoding::amx_override(amx),
+        }
+    }
+
     #[cfg(feature = "encoding")]
-    return encoding::get().decode(bytes).0.into_owned();
+    pub(crate) fn with(encoding: &'static encoding_rs::Encoding) -> Codec {
+        Codec {
+            encoding: Some(encoding),
+        }
+    }
+
+    #[cfg(feature = "encoding")]
+    pub(crate) fn encoding(self) -> &'static encoding_rs::Encoding {
+        self.encoding.unwrap_or_else(encoding::get)
+    }
+
+    /// Encodes a Rust string into the bytes the script expects, handling
+    /// unmappable characters by `encoding::unmappable_policy`.
+    ///
+    /// # Errors
+    /// `AmxError::Unmappable` if the policy is `Unmappable::Error` and
+    /// `string` has a character the encoding cannot represent.
+    pub(crate) fn encode(self, string: &str) -> AmxResult<Cow<'_, [u8]>> {
+        #[cfg(feature = "encoding")]
+        return encoding::encode(self.encoding(), string, encoding::unmappable_policy())
+            .map_err(|_| crate::error::AmxError::Unmappable);
+
+        #[cfg(not(feature = "encoding"))]
+        return Ok(Cow::from(string.as_bytes()));
+    }
+
+    /// [`encode`](Codec::encode) for writes that cannot fail: under
+    /// `Unmappable::Error` the character is replaced with `?` instead.
+    pub(crate) fn encode_lossy(self, string: &str) -> Cow<'_, [u8]> {
+        #[cfg(feature = "encoding")]
+        return encoding::encode(
+            self.encoding(),
+            string,
+            encoding::unmappable_policy().infallible(),
+        )
+        .unwrap_or_default();
 
-    #[cfg(not(feature = "encoding"))]
-    return String::from_utf8_lossy(bytes).into_owned();
+        #[cfg(not(feature = "encoding"))]
+        return Cow::from(string.as_bytes());
+    }
+
+    /// The exact encoding of `string`, or `None` if a character cannot be
+    /// represented. Comparisons use it so a `?` in the script never matches
+    /// a character that would have been replaced by one.
+    pub(crate) fn encode_exact(self, string: &str) -> Option<Cow<'_, [u8]>> {
+        #[cfg(feature = "encoding")]
+        return encoding::encode(self.encoding(), string, encoding::Unmappable::Error).ok();
+
+        #[cfg(not(feature = "encoding"))]
+        return Some(Cow::from(string.as_bytes()));
+    }
+
+    /// Decodes the script's bytes into a Rust string.
+    pub(crate) fn decode(self, bytes: &[u8]) -> String {
+        #[cfg(feature = "encoding")]
+        return self.encoding().decode(bytes).0.into_owned();
+
+        #[cfg(not(feature = "encoding"))]
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
 }
 
 impl<'amx> AmxCell<'amx> for AmxString<'amx> {
@@ -196,7 +442,8 @@ impl Deref for AmxString<'_> {
     /// Decodes on the first call and caches in [`OnceCell`] — subsequent
     /// accesses return the same `&str` without allocation.
     fn deref(&self) -> &str {
-        self.decoded.get_or_init(|| decode_bytes(&self.to_bytes()))
+        self.decoded
+            .get_or_init(|| self.codec().decode(&self.to_bytes()))
     }
 }
 
@@ -235,14 +482,11 @@ impl PartialEq<String> for AmxString<'_> {
 /// [`UnsizedBuffer::write_str`]: crate::cell::buffer::UnsizedBuffer::write_str
 ///
 /// # Errors
-/// `AmxError::General` if `string` (after encoding) is >= the buffer size.
+/// - `AmxError::General` if `string` (after encoding) is >= the buffer size.
+/// - `AmxError::Unmappable` if a character cannot be encoded and the policy
+///   is `Unmappable::Error`.
 pub(crate) fn put_in_buffer(buffer: &mut Buffer, string: &str) -> AmxResult<()> {
-    #[cfg(feature = "encoding")]
-    let bytes = encoding::get().encode(string).0;
-
-    #[cfg(not(feature = "encoding"))]
-    let bytes = std::borrow::Cow::from(string.as_bytes());
-
+    let bytes = buffer.codec().encode(string)?;
     let bytes = bytes.as_ref();
 
     if bytes.len() >= buffer.len() {
@@ -259,6 +503,41 @@ pub(crate) fn put_in_buffer(buffer: &mut Buffer, string: &str) -> AmxResult<()>
     Ok(())
 }
 
+/// Packed counterpart of [`put_in_buffer`] (4 bytes per cell, `0`
+/// terminator), shared by [`Buffer::write_str_packed`] and
+/// [`UnsizedBuffer::write_str_packed`].
+///
+/// [`Buffer::write_str_packed`]: crate::cell::buffer::Buffer::write_str_packed
+/// [`UnsizedBuffer::write_str_packed`]: crate::cell::buffer::UnsizedBuffer::write_str_packed
+///
+/// # Errors
+/// - `AmxError::General` if `string` (after encoding) needs more than
+///   `buffer.len()` cells.
+/// - `AmxError::Unmappable` as in [`put_in_buffer`].
+pub(crate) fn put_in_buffer_packed(buffer: &mut Buffer, string: &str) -> AmxResult<()> {
+    let bytes = buffer.codec().encode(string)?;
+    let cells = packed_cells(bytes.len());
+
+    if cells > buffer.len() {
+        return Err(crate::error::AmxError::General);
+    }
+
+    pack(&bytes, &mut buffer.as_mut_slice()[..cells]);
+
+    Ok(())
+}
+
+/// Packs `bytes` into `cells`, big-endian within each cell, zeroing the
+/// rest. `cells` must hold at least `packed_cells(bytes.len())` cells.
+fn pack(bytes: &[u8], cells: &mut [i32]) {
+    cells.fill(0);
+    for (cell, chunk) in cells.iter_mut().zip(bytes.chunks(4)) {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        *cell = i32::from_be_bytes(word);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -302,6 +581,8 @@ mod tests {
         // &str methods without .to_string()
         assert!(s.contains("world"));
         assert!(s.starts_with("hello"));
+        assert!(s.starts_with('h'));
+        assert!(s.eq_ignore_ascii_case("HELLO WORLD"));
         assert!(s.ends_with("world"));
         assert_eq!(s.to_uppercase(), "HELLO WORLD");
         assert_eq!(s.split_once(' ').unwrap(), ("hello", "world"));
@@ -406,6 +687,84 @@ mod tests {
         assert_eq!(s.to_bytes(), b"AB");
     }
 
+    #[test]
+    fn new_packed_round_trips() {
+        let mut data = vec![-1i32; 3];
+        let buf = make_buffer(&mut data);
+        let s = unsafe { AmxString::new_packed(buf, b"hello") };
+        assert_eq!(s.len(), 5);
+        assert_eq!(&*s, "hello");
+        assert_eq!(data, [0x6865_6c6c, 0x6f00_0000, -1]);
+    }
+
+    #[test]
+    fn packed_detection_is_unsigned() {
+        // A packed string starting with a byte >= 0x80 has a negative first
+        // cell; it is still packed.
+        let mut data = vec![0i32; 2];
+        let buf = make_buffer(&mut data);
+        let s = unsafe { AmxString::new_packed(buf, &[0xC0, 0x41]) };
+        assert_eq!(s.to_bytes(), [0xC0, 0x41]);
+        assert_eq!(str_len(&data), 2);
+    }
+
+    #[test]
+    fn packed_cells_include_the_terminator() {
+        assert_eq!(packed_cells(0), 1);
+        assert_eq!(packed_cells(3), 1);
+        assert_eq!(packed_cells(4), 2);
+        assert_eq!(packed_cells(8), 3);
+    }
+
+    // --- Zero-copy reads ---
+
+    #[test]
+    fn bytes_matches_to_bytes() {
+        let mut unpacked = vec![0x68, 0x69, 0];
+        let s = AmxString::from_buffer_parts(make_buffer(&mut unpacked), 2);
+        assert_eq!(s.bytes().collect::<Vec<_>>(), s.to_bytes());
+        assert_eq!(s.bytes().size_hint(), (2, Some(2)));
+
+        // "AB\0D" packed stops at the 0 byte, like `to_bytes`.
+        let mut packed = vec![0x4142_0044, 0];
+        let s = AmxString::from_buffer_parts(make_buffer(&mut packed), 4);
+        assert_eq!(s.bytes().collect::<Vec<_>>(), b"AB");
+    }
+
+    #[test]
+    fn bytes_never_reads_past_the_buffer() {
+        let mut data = vec![0x41, 0x42];
+        let s = AmxString::from_buffer_parts(make_buffer(&mut data), 100);
+        assert_eq!(s.bytes().count(), 2);
+    }
+
+    #[test]
+    fn starts_with_bytes_compares_raw_cells() {
+        let mut data = vec![0i32; 16];
+        let s = unsafe { AmxString::new(make_buffer(&mut data), b"/help me") };
+        assert!(s.starts_with_bytes("/help"));
+        assert!(s.starts_with_bytes(""));
+        assert!(!s.starts_with_bytes("/helpme"));
+        assert!(!s.starts_with_bytes("/help me too"));
+        // Nothing was decoded.
+        assert!(s.decoded.get().is_none());
+
+        let mut packed = vec![0i32; 3];
+        let s = unsafe { AmxString::new_packed(make_buffer(&mut packed), b"/kick 3") };
+        assert!(s.starts_with_bytes("/kick"));
+    }
+
+    #[test]
+    fn eq_ignore_ascii_case_bytes_needs_the_whole_string() {
+        let mut data = vec![0i32; 8];
+        let s = unsafe { AmxString::new(make_buffer(&mut data), b"Admin") };
+        assert!(s.eq_ignore_ascii_case_bytes("ADMIN"));
+        assert!(s.eq_ignore_ascii_case_bytes("admin"));
+        assert!(!s.eq_ignore_ascii_case_bytes("adm"));
+        assert!(!s.eq_ignore_ascii_case_bytes("admins"));
+        assert!(s.decoded.get().is_none());
+    }
+
     // --- as_str ---
 
     #[test]
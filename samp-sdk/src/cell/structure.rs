@@ -0,0 +1,265 @@
+//! Enum-structured Pawn arrays (`new Data[E_PLAYER_DATA]`) mapped onto Rust
+//! structs.
+//!
+//! Pawn lays the fields of such an array out back to back, one cell per
+//! scalar and `N` cells per `name[N]` field, in declaration order:
+//!
+//! ```pawn
+//! enum E_PLAYER_DATA {
+//!     E_LEVEL,
+//!     Float:E_HEALTH,
+//!     E_NAME[MAX_PLAYER_NAME]
+//! }
+//! ```
+//!
+//! [`AmxStruct`] describes that layout for a Rust type (usually through
+//! `#[derive(AmxStruct)]` in `samp`), and [`StructRef`] is the native
+//! argument that reads it out of the script and writes it back.
+
+use std::ops::{Deref, DerefMut};
+
+use super::string::{Codec, cells_to_bytes};
+use super::{AmxCell, Buffer, UnsizedBuffer};
+use crate::amx::Amx;
+use crate::error::{AmxError, AmxResult};
+
+/// A Rust type with the same cell layout as an enum-structured Pawn array.
+///
+/// `read` and `write` always get a slice of exactly [`CELLS`] cells, and the
+/// [`Codec`] of the script the array belongs to for its string fields.
+///
+/// [`CELLS`]: AmxStruct::CELLS
+pub trait AmxStruct: Sized {
+    /// Size of the Pawn array in cells (`sizeof Data`).
+    const CELLS: usize;
+
+    /// Builds the value from the array's cells.
+    fn read(cells: &[i32], codec: Codec) -> Self;
+
+    /// Stores the value into the array's cells.
+    fn write(&self, cells: &mut [i32], codec: Codec);
+}
+
+/// Reads a fixed-size string field (`E_NAME[24]`), packed or unpacked, up to
+/// the first `0` or the end of the field.
+#[must_use]
+pub fn read_string(cells: &[i32], codec: Codec) -> String {
+    let mut bytes = cells_to_bytes(cells, cells.len() * 4);
+    // Unpacked strings are read cell by cell; cut at the terminator.
+    if let Some(end) = bytes.iter().position(|&byte| byte == 0) {
+        bytes.truncate(end);
+    }
+    codec.decode(&bytes)
+}
+
+/// Writes `value` into a fixed-size string field (unpacked, `0` terminated).
+/// Text that does not fit is cut off, as Pawn's `strcopy` would, and the rest
+/// of the field is zeroed.
+pub fn write_string(cells: &mut [i32], value: &str, codec: Codec) {
+    let Some(max) = cells.len().checked_sub(1) else {
+        return;
+    };
+    let bytes = codec.encode_lossy(value);
+    let bytes = &bytes[..bytes.len().min(max)];
+    let (text, rest) = cells.split_at_mut(bytes.len());
+    text.iter_mut()
+        .zip(bytes)
+        .for_each(|(cell, &byte)| *cell = i32::from(byte));
+    rest.fill(0);
+}
+
+/// An enum-structured array passed to a native, read into a `T`.
+///
+/// Derefs to `T`. Changes made through `DerefMut` are written back to the
+/// script's array when the `StructRef` is dropped, which for a native argument
+/// is when the native returns. `#[native]` parses a `&mut T` parameter through
+/// this type, so signatures can name the struct directly:
+///
+/// ```rust,ignore
+/// #[native(name = "Player_LevelUp")]
+/// fn level_up(&mut self, _amx: &Amx, data: &mut PlayerData) -> i32 {
+///     data.level += 1;
+///     data.level
+/// }
+/// ```
+pub struct StructRef<'amx, T: AmxStruct> {
+    buffer: Buffer<'amx>,
+    value: T,
+    dirty: bool,
+}
+
+impl<'amx, T: AmxStruct> StructRef<'amx, T> {
+    /// Reads a `T` out of `buffer`, decoding string fields with the
+    /// buffer's encoding (the script's, for a native argument).
+    ///
+    /// # Errors
+    /// `AmxError::MemoryAccess` if `buffer` is shorter than `T::CELLS`.
+    pub fn new(buffer: Buffer<'amx>) -> AmxResult<Self> {
+        if buffer.len() < T::CELLS {
+            return Err(AmxError::MemoryAccess);
+        }
+        let value = T::read(&buffer[..T::CELLS], buffer.codec());
+        Ok(StructRef {
+            buffer,
+            value,
+            dirty: false,
+        })
+    }
+
+    /// Writes the current value to the array now instead of on drop.
+    pub fn flush(&mut self) {
+        let codec = self.buffer.codec();
+        self.value
+            .write(&mut self.buffer.as_mut_slice()[..T::CELLS], codec);
+        self.dirty = false;
+    }
+}
+
+impl<'amx, T: AmxStruct> AmxCell<'amx> for StructRef<'amx, T> {
+    fn from_raw(amx: &'amx Amx, cell: i32) -> AmxResult<Self>
+    where
+        Self: 'amx,
+    {
+        let buffer = UnsizedBuffer::from_raw(amx, cell)?.into_sized_buffer(T::CELLS);
+        StructRef::new(buffer)
+    }
+
+    fn as_cell(&self) -> i32 {
+        self.buffer.as_cell()
+    }
+}
+
+impl<T: AmxStruct> Deref for StructRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: AmxStruct> DerefMut for StructRef<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.dirty = true;
+        &mut self.value
+    }
+}
+
+impl<T: AmxStruct> Drop for StructRef<'_, T> {
+    fn drop(&mut self) {
+        if self.dirty {
+            self.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{CellConvert, Ref};
+
+    /// `enum E_DATA { E_LEVEL, Float:E_HEALTH, E_NAME[6] }`, written by hand
+    /// the way the derive does it.
+    #[derive(Debug, PartialEq)]
+    struct Data {
+        level: i32,
+        health: f32,
+        name: String,
+    }
+
+    impl AmxStruct for Data {
+        const CELLS: usize = 8;
+
+        fn read(cells: &[i32], codec: Codec) -> Self {
+            Data {
+                level: i32::from_cell(cells[0]),
+                health: f32::from_cell(cells[1]),
+                name: read_string(&cells[2..8], codec),
+            }
+        }
+
+        fn write(&self, cells: &mut [i32], codec: Codec) {
+            cells[0] = self.level.into_cell();
+            cells[1] = self.health.into_cell();
+            write_string(&mut cells[2..8], &self.name, codec);
+        }
+    }
+
+    fn buffer(data: &mut [i32]) -> Buffer<'_> {
+        let len = data.len();
+        Buffer::new(unsafe { Ref::new(0, data.as_mut_ptr()) }, len)
+    }
+
+    fn cells(level: i32, health: f32, name: &[u8]) -> Vec<i32> {
+        let mut data = vec![level, health.into_cell(), 0, 0, 0, 0, 0, 0];
+        for (cell, &byte) in data[2..].iter_mut().zip(name) {
+            *cell = i32::from(byte);
+        }
+        data
+    }
+
+    #[test]
+    fn reads_fields_at_their_offsets() {
+        let mut data = cells(3, 75.5, b"Alice");
+        let data_ref = StructRef::<Data>::new(buffer(&mut data)).unwrap();
+        assert_eq!(
+            *data_ref,
+            Data {
+                level: 3,
+                health: 75.5,
+                name: "Alice".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn changes_are_written_back_on_drop() {
+        let mut data = cells(3, 75.5, b"Alice");
+        {
+            let mut data_ref = StructRef::<Data>::new(buffer(&mut data)).unwrap();
+            data_ref.level += 1;
+            data_ref.name = "Bob".to_owned();
+        }
+        assert_eq!(data, cells(4, 75.5, b"Bob"));
+    }
+
+    #[test]
+    fn untouched_array_is_not_rewritten() {
+        // Garbage past the terminator survives when nothing was changed.
+        let mut data = cells(1, 0.0, b"Al\0xy");
+        drop(StructRef::<Data>::new(buffer(&mut data)).unwrap());
+        assert_eq!(data, cells(1, 0.0, b"Al\0xy"));
+    }
+
+    #[test]
+    fn short_array_is_rejected() {
+        let mut data = vec![0; 7];
+        assert!(matches!(
+            StructRef::<Data>::new(buffer(&mut data)),
+            Err(AmxError::MemoryAccess)
+        ));
+    }
+
+    #[test]
+    fn string_fields_read_packed_and_stop_at_the_field_end() {
+        // "Alice" packed: 0x416c6963, 0x65000000.
+        let codec = Codec::default();
+        assert_eq!(read_string(&[0x416c_6963, 0x6500_0000], codec), "Alice");
+        // No terminator inside the field: the field end bounds the read.
+        assert_eq!(read_string(&[0x41, 0x42, 0x43], codec), "ABC");
+        assert_eq!(read_string(&[], codec), "");
+    }
+
+    #[test]
+    fn string_fields_are_truncated_and_zero_filled() {
+        let codec = Codec::default();
+        let mut field = [-1; 4];
+        write_string(&mut field, "Alice", codec);
+        assert_eq!(field, [0x41, 0x6c, 0x69, 0]);
+
+        write_string(&mut field, "A", codec);
+        assert_eq!(field, [0x41, 0, 0, 0]);
+
+        let mut empty: [i32; 0] = [];
+        write_string(&mut empty, "A", codec);
+    }
+}
@@ -1,15 +1,39 @@
-//! Global encoding for Rust <-> AMX conversion (only with the `encoding` feature).
+//! Encoding for Rust <-> AMX conversion (only with the `encoding` feature).
 //!
 //! The original SA-MP operates on 8-bit encodings (Western Windows-1252 by
 //! default, Windows-1251 for Cyrillic on Russian servers). This module lets the
 //! plugin configure the encoding once in `on_load` — after that, `AmxString`
 //! decodes and [`Buffer::write_str`] encodes using it automatically.
 //!
+//! The choice is made at three levels, the most specific winning:
+//! 1. per argument — [`EncodedString<E>`] or `with_encoding` on an
+//!    `AmxString`, `Buffer` or `UnsizedBuffer`;
+//! 2. per script — [`set_amx_encoding`], for a server that mixes a Cyrillic
+//!    gamemode with Western filterscripts;
+//! 3. the global default — [`set_default_encoding`].
+//!
+//! Characters the chosen code page cannot represent are handled according
+//! to [`set_unmappable_policy`]: replaced with `?` (the default), rejected
+//! with `AmxError::Unmappable`, or approximated in ASCII.
+//!
 //! [`Buffer::write_str`]: crate::cell::Buffer::write_str
 
-use encoding_rs::Encoding;
-pub use encoding_rs::{WINDOWS_1251, WINDOWS_1252};
-use std::sync::atomic::{AtomicPtr, Ordering};
+mod translit;
+
+use encoding_rs::{EncoderResult, Encoding};
+pub use encoding_rs::{
+    UTF_8, WINDOWS_1250, WINDOWS_1251, WINDOWS_1252, WINDOWS_1253, WINDOWS_1254, WINDOWS_1256,
+};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+use std::sync::{PoisonError, RwLock};
+
+use crate::amx::Amx;
+use crate::cell::{AmxCell, AmxString};
+use crate::error::AmxResult;
 
 static DEFAULT_ENCODING: AtomicPtr<Encoding> =
     AtomicPtr::new(std::ptr::from_ref::<Encoding>(WINDOWS_1252).cast_mut());
@@ -29,6 +53,277 @@ pub(crate) fn get() -> &'static Encoding {
     unsafe { &*DEFAULT_ENCODING.load(Ordering::Acquire) }
 }
 
+/// What writing a string does with a character the target code page
+/// cannot represent (`€` in Windows-1251, `Ж` in Windows-1252, …).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Unmappable {
+    /// Write `?` in its place, as the SA-MP client does. The default.
+    #[default]
+    Replace,
+    /// Fail the write with `AmxError::Unmappable`, leaving the destination
+    /// untouched.
+    Error,
+    /// Write an ASCII approximation (`é` → `e`, `Ж` → `Zh`, `“` → `"`), or
+    /// `?` when none is known.
+    Transliterate,
+}
+
+impl Unmappable {
+    /// The policy for writes that have no way to report an error.
+    pub(crate) fn infallible(self) -> Unmappable {
+        match self {
+            Unmappable::Error => Unmappable::Replace,
+            policy => policy,
+        }
+    }
+}
+
+static UNMAPPABLE: AtomicU8 = AtomicU8::new(Unmappable::Replace as u8);
+
+/// Sets how [`Unmappable`] characters are written, for every script.
+///
+/// Applies to `write_str`, `write_str_packed` and the `Allocator::allot_string*`
+/// family. Comparisons (`AmxString::starts_with_bytes`,
+/// `eq_ignore_ascii_case_bytes`)
+/// never match an unmappable character, whatever the policy.
+pub fn set_unmappable_policy(policy: Unmappable) {
+    UNMAPPABLE.store(policy as u8, Ordering::Release);
+}
+
+/// The policy set by [`set_unmappable_policy`].
+#[must_use]
+pub fn unmappable_policy() -> Unmappable {
+    match UNMAPPABLE.load(Ordering::Acquire) {
+        1 => Unmappable::Error,
+        2 => Unmappable::Transliterate,
+        _ => Unmappable::Replace,
+    }
+}
+
+/// Encodes `string` with `encoding`, handling unmappable characters as
+/// `policy` says. `Err` carries the first unmappable character when
+/// `policy` is [`Unmappable::Error`].
+///
+/// UTF-8 (and any text the code page covers entirely) is borrowed as is.
+pub(crate) fn encode<'a>(
+    encoding: &'static Encoding,
+    string: &'a str,
+    policy: Unmappable,
+) -> Result<Cow<'a, [u8]>, char> {
+    let (bytes, _, unmappable) = encoding.encode(string);
+    if !unmappable {
+        return Ok(bytes);
+    }
+
+    // `encode` wrote HTML character references for the unmappable
+    // characters; redo the conversion and apply the policy instead.
+    let mut encoder = encoding.new_encoder();
+    let mut out = Vec::with_capacity(string.len());
+    let mut rest = string;
+    loop {
+        let needed = encoder
+            .max_buffer_length_from_utf8_without_replacement(rest.len())
+            .unwrap_or(rest.len());
+        out.reserve(needed);
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(rest, &mut out, true);
+        rest = &rest[read..];
+        match result {
+            EncoderResult::InputEmpty => return Ok(Cow::Owned(out)),
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(c) => match policy {
+                Unmappable::Replace => out.push(b'?'),
+                Unmappable::Error => return Err(c),
+                Unmappable::Transliterate => translit::push(c, &mut out),
+            },
+        }
+    }
+}
+
+/// Per-script overrides, keyed by the `AMX` pointer. A server runs a handful
+/// of scripts, so a vector beats a map here.
+static AMX_ENCODINGS: RwLock<Vec<(usize, &'static Encoding)>> = RwLock::new(Vec::new());
+
+fn amx_key(amx: &Amx) -> Option<usize> {
+    amx.amx().map(|ptr| ptr.as_ptr() as usize)
+}
+
+/// Sets the encoding of one script, overriding the default for the strings
+/// its natives receive and the strings written back to it.
+///
+/// The override lasts until [`reset_amx_encoding`] or until the script is
+/// unloaded (the `samp` runtime clears it). Strings parsed before the call
+/// keep the encoding they were parsed with.
+pub fn set_amx_encoding(amx: &Amx, encoding: &'static Encoding) {
+    let Some(key) = amx_key(amx) else {
+        return;
+    };
+    let mut table = AMX_ENCODINGS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    match table.iter_mut().find(|(k, _)| *k == key) {
+        Some(entry) => entry.1 = encoding,
+        None => table.push((key, encoding)),
+    }
+}
+
+/// Removes the override of [`set_amx_encoding`]; the script follows the
+/// default encoding again.
+pub fn reset_amx_encoding(amx: &Amx) {
+    let Some(key) = amx_key(amx) else {
+        return;
+    };
+    AMX_ENCODINGS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|(k, _)| *k != key);
+}
+
+/// Encoding in effect for `amx`: its override, or the default.
+#[must_use]
+pub fn amx_encoding(amx: &Amx) -> &'static Encoding {
+    amx_override(amx).unwrap_or_else(get)
+}
+
+/// The override of `amx`, if [`set_amx_encoding`] set one.
+pub(crate) fn amx_override(amx: &Amx) -> Option<&'static Encoding> {
+    let key = amx_key(amx)?;
+    AMX_ENCODINGS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|&(_, encoding)| encoding)
+}
+
+/// A code page known at compile time, for [`EncodedString`].
+pub trait Charset {
+    /// The `encoding_rs` encoding of the code page.
+    fn encoding() -> &'static Encoding;
+}
+
+/// Windows-1250 (Central European: Polish, Czech, Hungarian, …).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1250 {}
+
+/// Windows-1251 (Cyrillic).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1251 {}
+
+/// Windows-1252 (Western Latin).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1252 {}
+
+/// Windows-1253 (Greek).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1253 {}
+
+/// Windows-1254 (Turkish).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1254 {}
+
+/// Windows-1256 (Arabic).
+#[derive(Debug, Clone, Copy)]
+pub enum Cp1256 {}
+
+/// UTF-8 passthrough, for open.mp gamemodes compiled with UTF-8 string
+/// literals: written bytes are the Rust string's own, and no character is
+/// unmappable.
+#[derive(Debug, Clone, Copy)]
+pub enum Utf8 {}
+
+impl Charset for Cp1250 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1250
+    }
+}
+
+impl Charset for Cp1251 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1251
+    }
+}
+
+impl Charset for Cp1252 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1252
+    }
+}
+
+impl Charset for Cp1253 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1253
+    }
+}
+
+impl Charset for Cp1254 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1254
+    }
+}
+
+impl Charset for Cp1256 {
+    fn encoding() -> &'static Encoding {
+        WINDOWS_1256
+    }
+}
+
+impl Charset for Utf8 {
+    fn encoding() -> &'static Encoding {
+        UTF_8
+    }
+}
+
+/// A string argument decoded with a fixed code page `E`, whatever the
+/// script's or the default encoding is:
+///
+/// ```rust,ignore
+/// use samp::encoding::{Cp1251, EncodedString};
+///
+/// #[native(name = "LogRussian")]
+/// fn log_russian(&mut self, _amx: &Amx, text: EncodedString<Cp1251>) -> bool {
+///     log::info!("{}", &**text);
+///     true
+/// }
+/// ```
+///
+/// Derefs to [`AmxString`].
+pub struct EncodedString<'amx, E: Charset> {
+    string: AmxString<'amx>,
+    charset: PhantomData<E>,
+}
+
+impl<'amx, E: Charset> AmxCell<'amx> for EncodedString<'amx, E> {
+    fn from_raw(amx: &'amx Amx, cell: i32) -> AmxResult<Self>
+    where
+        Self: 'amx,
+    {
+        let string = AmxString::from_raw(amx, cell)?.with_encoding(E::encoding());
+        Ok(EncodedString {
+            string,
+            charset: PhantomData,
+        })
+    }
+
+    fn as_cell(&self) -> i32 {
+        self.string.as_cell()
+    }
+}
+
+impl<'amx, E: Charset> Deref for EncodedString<'amx, E> {
+    type Target = AmxString<'amx>;
+
+    fn deref(&self) -> &AmxString<'amx> {
+        &self.string
+    }
+}
+
+impl<E: Charset> fmt::Display for EncodedString<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.string, f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,4 +345,37 @@ mod tests {
         let enc = get();
         assert_eq!(enc.name(), WINDOWS_1252.name());
     }
+
+    #[test]
+    fn single_byte_code_pages() {
+        let encode = |encoding, s| encode(encoding, s, Unmappable::Error).unwrap();
+        assert_eq!(*encode(WINDOWS_1250, "ł"), [0xB3]);
+        assert_eq!(*encode(WINDOWS_1251, "Ж"), [0xC6]);
+        assert_eq!(*encode(WINDOWS_1253, "Ω"), [0xD9]);
+        assert_eq!(*encode(WINDOWS_1254, "ş"), [0xFE]);
+        assert_eq!(*encode(WINDOWS_1256, "ع"), [0xDA]);
+    }
+
+    #[test]
+    fn utf8_is_passed_through() {
+        let text = "Жёлтый 日本";
+        let bytes = encode(UTF_8, text, Unmappable::Error).unwrap();
+        assert!(matches!(bytes, Cow::Borrowed(b) if b == text.as_bytes()));
+    }
+
+    #[test]
+    fn unmappable_characters_follow_the_policy() {
+        let encode = |policy| encode(WINDOWS_1252, "Жé", policy);
+        assert_eq!(*encode(Unmappable::Replace).unwrap(), [b'?', 0xE9]);
+        assert_eq!(
+            *encode(Unmappable::Transliterate).unwrap(),
+            [b'Z', b'h', 0xE9]
+        );
+        assert_eq!(encode(Unmappable::Error), Err('Ж'));
+        // Text the code page covers entirely is borrowed, not copied.
+        assert!(matches!(
+            super::encode(WINDOWS_1252, "plain", Unmappable::Error),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
 }
@@ -0,0 +1,131 @@
+//! ASCII approximations for [`Unmappable::Transliterate`].
+//!
+//! Covers what a player is likely to type on a server whose code page lacks
+//! it: Latin letters with diacritics, Cyrillic (a plain GOST-style
+//! romanisation) and typographic punctuation. Anything else becomes `?`.
+//!
+//! [`Unmappable::Transliterate`]: super::Unmappable::Transliterate
+
+/// Appends the ASCII approximation of `c` to `out`.
+pub(super) fn push(c: char, out: &mut Vec<u8>) {
+    // `İ` lowercases to two chars; the table only needs its base letter.
+    if c == 'İ' {
+        out.push(b'I');
+        return;
+    }
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    let ascii = lowercase(lower);
+    if lower == c {
+        out.extend_from_slice(ascii.as_bytes());
+        return;
+    }
+    // Upper case: capitalise the first letter (`Ж` → `Zh`).
+    let mut bytes = ascii.bytes();
+    if let Some(first) = bytes.next() {
+        out.push(first.to_ascii_uppercase());
+        out.extend(bytes);
+    }
+}
+
+fn lowercase(c: char) -> &'static str {
+    match c {
+        // Latin with diacritics.
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ķ' => "k",
+        'ĺ' | 'ľ' | 'ł' | 'ļ' => "l",
+        'ñ' | 'ń' | 'ň' | 'ņ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'ŕ' | 'ř' => "r",
+        'ś' | 'š' | 'ş' | 'ș' => "s",
+        'ť' | 'ţ' | 'ț' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'þ' => "th",
+        'ð' => "d",
+        // Cyrillic.
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'э' | 'є' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' | 'ў' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'ю' => "yu",
+        'я' => "ya",
+        // Punctuation and symbols.
+        '\u{a0}' | '\u{2002}'..='\u{200a}' => " ",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '«' | '»' | '″' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '•' | '·' => "*",
+        '€' => "EUR",
+        '™' => "TM",
+        '©' => "(c)",
+        '®' => "(R)",
+        _ => "?",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translit(s: &str) -> String {
+        let mut out = Vec::new();
+        // ASCII is mappable in every code page and never reaches `push`.
+        for c in s.chars() {
+            if c.is_ascii() {
+                out.push(c as u8);
+            } else {
+                push(c, &mut out);
+            }
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn letters_keep_their_case() {
+        assert_eq!(translit("Ščęśliwy"), "Scesliwy");
+        assert_eq!(translit("Жёлтый"), "Zhyoltyy");
+        assert_eq!(translit("ŞİŞLİ"), "SISLI");
+    }
+
+    #[test]
+    fn punctuation_and_unknowns() {
+        assert_eq!(translit("“Hi” — …"), "\"Hi\" - ...");
+        assert_eq!(translit("日本"), "??");
+    }
+}
@@ -43,6 +43,9 @@ pub enum AmxError {
     General = 27,
     Overlay = 28,
     Unknown,
+    /// Not an `AMX_ERR_*` code: a string had a character its code page
+    /// cannot represent, under `encoding::Unmappable::Error`.
+    Unmappable,
 }
 
 impl Display for AmxError {
@@ -81,6 +84,7 @@ impl Display for AmxError {
             General => write!(f, "General error (unknown or unspecific error)"),
             Overlay => write!(f, "Overlays are unsupported (JIT) or uninitialized"),
             Unknown => write!(f, "Unknown error"),
+            Unmappable => write!(f, "Character not representable in the string encoding"),
         }
     }
 }
@@ -184,6 +188,7 @@ mod tests {
             AmxError::Divide,
             AmxError::NotFound,
             AmxError::Unknown,
+            AmxError::Unmappable,
         ];
 
         for err in errors {
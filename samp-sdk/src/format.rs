@@ -0,0 +1,373 @@
+//! Pawn's `format` in Rust.
+//!
+//! Renders a Pawn format string against the `...` arguments of a native,
+//! following the rules of the server's `format()` native and producing the
+//! bytes it would write. The usual use is a native that takes a format
+//! string and forwards the result somewhere:
+//!
+//! ```rust,ignore
+//! // native SendClientMessageEx(playerid, color, const fmt[], ...);
+//! #[native(name = "SendClientMessageEx")]
+//! fn send_ex(&mut self, amx: &Amx, playerid: i32, color: i32, fmt: &AmxString, args: Variadic) -> AmxResult<bool> {
+//!     let message = samp::format::format(fmt, &args)?;
+//!     send_client_message(amx, playerid, color, &message)
+//! }
+//! ```
+//!
+//! # Specifiers
+//!
+//! `%[flags][width][.precision]specifier`:
+//!
+//! | Specifier   | Output                                                          |
+//! | ----------- | --------------------------------------------------------------- |
+//! | `%d` / `%i` | Signed decimal integer.                                         |
+//! | `%s`        | String. The precision caps the number of characters.           |
+//! | `%q`        | Like `%s`, with every `'` doubled (SQLite escaping).            |
+//! | `%f`        | `Float:` with 6 decimals, or as many as the precision says.     |
+//! | `%x`        | Upper-case hexadecimal of the cell as unsigned (`-1` → `FFFFFFFF`). |
+//! | `%b`        | Binary of the cell as unsigned.                                 |
+//! | `%c`        | The cell as a single character.                                 |
+//! | `%%`        | A literal `%` (consumes no argument).                           |
+//!
+//! Flags: `-` pads on the right instead of the left; `0` pads numbers
+//! (`%d %i %x %b %f`) with zeros instead of spaces. Width and precision
+//! may be `*`, read from the next argument; a negative `*` width pads on
+//! the right, a negative `*` precision is ignored. Width and precision
+//! count bytes, like the server.
+//!
+//! An unknown specifier is copied to the output as written, and a `%` at
+//! the very end is dropped.
+//!
+//! # Arguments
+//!
+//! Pawn passes variadic arguments by reference, so each one is read at its
+//! address — an integer or float from the cell, a string from the array.
+//! A specifier without a matching argument is an error
+//! ([`AmxError::Params`]) rather than a silently shorter message.
+
+use crate::args::{VarArg, Variadic};
+use crate::cell::AmxString;
+use crate::error::{AmxError, AmxResult};
+
+/// Renders `format` against `args` and decodes the result with the
+/// encoding of `format` (see `AmxString`).
+///
+/// # Errors
+/// [`AmxError::Params`] when a specifier has no argument left, or the
+/// error of reading an argument (`AmxError::MemoryAccess` for a bad address).
+pub fn format(format: &AmxString<'_>, args: &Variadic<'_>) -> AmxResult<String> {
+    format_bytes(format, args).map(|bytes| format.codec().decode(&bytes))
+}
+
+/// Like [`format`], but returns the raw bytes the server would write into
+/// the destination array, before any decoding.
+///
+/// # Errors
+/// See [`format`].
+pub fn format_bytes(format: &AmxString<'_>, args: &Variadic<'_>) -> AmxResult<Vec<u8>> {
+    let mut args = args.iter();
+    render(&format.to_bytes(), &mut args)
+}
+
+/// Where [`render`] takes its arguments from: the variadic arguments of a
+/// native, or plain values in the unit tests.
+trait FormatArgs {
+    /// The next argument read as a cell (`%d %x %f *`…).
+    fn next_cell(&mut self) -> AmxResult<i32>;
+
+    /// The next argument read as a string (`%s %q`).
+    fn next_string(&mut self) -> AmxResult<Vec<u8>>;
+}
+
+impl<'a, I: Iterator<Item = VarArg<'a>>> FormatArgs for I {
+    fn next_cell(&mut self) -> AmxResult<i32> {
+        self.next().ok_or(AmxError::Params)?.int()
+    }
+
+    fn next_string(&mut self) -> AmxResult<Vec<u8>> {
+        Ok(self.next().ok_or(AmxError::Params)?.string()?.to_bytes())
+    }
+}
+
+/// One parsed `%` conversion.
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    /// Appends `body`, padded to the width.
+    fn pad(&self, out: &mut Vec<u8>, body: &[u8], numeric: bool) {
+        let fill = self.width.saturating_sub(body.len());
+        if self.left {
+            out.extend_from_slice(body);
+            out.resize(out.len() + fill, b' ');
+        } else if self.zero && numeric {
+            // Zeros go between the sign and the digits: `-0042`.
+            let (sign, digits) = match body.split_first() {
+                Some((b'-', digits)) => (&body[..1], digits),
+                _ => (&body[..0], body),
+            };
+            out.extend_from_slice(sign);
+            out.resize(out.len() + fill, b'0');
+            out.extend_from_slice(digits);
+        } else {
+            out.resize(out.len() + fill, b' ');
+            out.extend_from_slice(body);
+        }
+    }
+}
+
+fn render(format: &[u8], args: &mut dyn FormatArgs) -> AmxResult<Vec<u8>> {
+    let mut out = Vec::with_capacity(format.len());
+    let mut bytes = format.iter().copied().peekable();
+
+    while let Some(byte) = bytes.next() {
+        if byte != b'%' {
+            out.push(byte);
+            continue;
+        }
+        let mut spec = Spec::default();
+
+        while let Some(&flag) = bytes.peek() {
+            match flag {
+                b'-' => spec.left = true,
+                b'0' => spec.zero = true,
+                _ => break,
+            }
+            bytes.next();
+        }
+
+        if bytes.peek() == Some(&b'*') {
+            bytes.next();
+            let width = args.next_cell()?;
+            spec.left |= width < 0;
+            spec.width = width.unsigned_abs() as usize;
+        } else {
+            spec.width = read_number(&mut bytes);
+        }
+
+        if bytes.peek() == Some(&b'.') {
+            bytes.next();
+            if bytes.peek() == Some(&b'*') {
+                bytes.next();
+                spec.precision = usize::try_from(args.next_cell()?).ok();
+            } else {
+                spec.precision = Some(read_number(&mut bytes));
+            }
+        }
+
+        let Some(specifier) = bytes.next() else {
+            break;
+        };
+        match specifier {
+            b'd' | b'i' => {
+                let value = args.next_cell()?;
+                spec.pad(&mut out, value.to_string().as_bytes(), true);
+            }
+            b'x' => {
+                let value = args.next_cell()?.cast_unsigned();
+                spec.pad(&mut out, format!("{value:X}").as_bytes(), true);
+            }
+            b'b' => {
+                let value = args.next_cell()?.cast_unsigned();
+                spec.pad(&mut out, format!("{value:b}").as_bytes(), true);
+            }
+            b'f' => {
+                let value = f32::from_bits(args.next_cell()?.cast_unsigned());
+                let body = float(f64::from(value), spec.precision.unwrap_or(6));
+                let numeric = value.is_finite();
+                spec.pad(&mut out, body.as_bytes(), numeric);
+            }
+            b'c' => {
+                // A Pawn character is one byte of the cell.
+                #[allow(clippy::cast_possible_truncation)] // truncation is the point
+                let value = args.next_cell()? as u8;
+                spec.pad(&mut out, &[value], false);
+            }
+            b's' | b'q' => {
+                let mut string = args.next_string()?;
+                if let Some(max) = spec.precision {
+                    string.truncate(max);
+                }
+                if specifier == b'q' {
+                    string = escape_quotes(&string);
+                }
+                spec.pad(&mut out, &string, false);
+            }
+            b'%' => out.push(b'%'),
+            _ => {
+                // Unknown: keep the text as written.
+                out.extend_from_slice(&format_source(&spec, specifier));
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Parses a run of decimal digits; `0` when there are none.
+fn read_number(bytes: &mut std::iter::Peekable<impl Iterator<Item = u8>>) -> usize {
+    let mut number = 0usize;
+    while let Some(digit) = bytes.peek().filter(|b| b.is_ascii_digit()) {
+        number = number
+            .saturating_mul(10)
+            .saturating_add(usize::from(digit - b'0'));
+        bytes.next();
+    }
+    number
+}
+
+/// `%f`: fixed notation, or `inf`/`-inf`/`nan` like C's `printf`.
+fn float(value: f64, precision: usize) -> String {
+    if value.is_nan() {
+        "nan".to_owned()
+    } else if value.is_infinite() {
+        if value < 0.0 { "-inf" } else { "inf" }.to_owned()
+    } else {
+        format!("{value:.precision$}")
+    }
+}
+
+/// `%q`: doubles every single quote.
+fn escape_quotes(string: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(string.len());
+    for &byte in string {
+        escaped.push(byte);
+        if byte == b'\'' {
+            escaped.push(b'\'');
+        }
+    }
+    escaped
+}
+
+/// Rebuilds the text of an unknown conversion, e.g. `%-5y`.
+fn format_source(spec: &Spec, specifier: u8) -> Vec<u8> {
+    let mut text = vec![b'%'];
+    if spec.left {
+        text.push(b'-');
+    }
+    if spec.zero {
+        text.push(b'0');
+    }
+    if spec.width > 0 {
+        text.extend_from_slice(spec.width.to_string().as_bytes());
+    }
+    if let Some(precision) = spec.precision {
+        text.push(b'.');
+        text.extend_from_slice(precision.to_string().as_bytes());
+    }
+    text.push(specifier);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Arg {
+        Int(i32),
+        Float(f32),
+        Str(&'static str),
+    }
+
+    struct Fake(std::vec::IntoIter<Arg>);
+
+    impl FormatArgs for Fake {
+        fn next_cell(&mut self) -> AmxResult<i32> {
+            match self.0.next().ok_or(AmxError::Params)? {
+                Arg::Int(value) => Ok(value),
+                Arg::Float(value) => Ok(value.to_bits().cast_signed()),
+                // A string's cell is its address; any non-zero value will do.
+                Arg::Str(_) => Ok(0x100),
+            }
+        }
+
+        fn next_string(&mut self) -> AmxResult<Vec<u8>> {
+            match self.0.next().ok_or(AmxError::Params)? {
+                Arg::Str(value) => Ok(value.as_bytes().to_vec()),
+                // Pawn reads a string at whatever address it is given.
+                _ => Ok(Vec::new()),
+            }
+        }
+    }
+
+    fn fmt(format: &str, args: Vec<Arg>) -> String {
+        let bytes = render(format.as_bytes(), &mut Fake(args.into_iter())).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(fmt("%d|%i", vec![Arg::Int(42), Arg::Int(-7)]), "42|-7");
+        assert_eq!(fmt("[%5d]", vec![Arg::Int(42)]), "[   42]");
+        assert_eq!(fmt("[%-5d]", vec![Arg::Int(42)]), "[42   ]");
+        assert_eq!(fmt("[%05d]", vec![Arg::Int(-42)]), "[-0042]");
+        assert_eq!(fmt("[%-05d]", vec![Arg::Int(42)]), "[42   ]");
+        assert_eq!(fmt("%d", vec![Arg::Int(i32::MIN)]), "-2147483648");
+    }
+
+    #[test]
+    fn hex_and_binary_are_unsigned() {
+        assert_eq!(fmt("%x", vec![Arg::Int(255)]), "FF");
+        assert_eq!(fmt("%x", vec![Arg::Int(-1)]), "FFFFFFFF");
+        assert_eq!(fmt("%08x", vec![Arg::Int(0xBEEF)]), "0000BEEF");
+        assert_eq!(fmt("%b", vec![Arg::Int(5)]), "101");
+        assert_eq!(fmt("%b", vec![Arg::Int(-1)]), "1".repeat(32));
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(fmt("%f", vec![Arg::Float(1.5)]), "1.500000");
+        assert_eq!(fmt("%.2f", vec![Arg::Float(1.23456)]), "1.23");
+        assert_eq!(fmt("%.0f", vec![Arg::Float(2.5)]), "2");
+        assert_eq!(fmt("[%8.3f]", vec![Arg::Float(-1.25)]), "[  -1.250]");
+        assert_eq!(fmt("[%08.3f]", vec![Arg::Float(-1.25)]), "[-001.250]");
+        assert_eq!(fmt("%f", vec![Arg::Float(f32::INFINITY)]), "inf");
+        assert_eq!(fmt("[%05f]", vec![Arg::Float(f32::NAN)]), "[  nan]");
+    }
+
+    #[test]
+    fn star_reads_width_and_precision_from_arguments() {
+        assert_eq!(fmt("%.*f", vec![Arg::Int(2), Arg::Float(1.23456)]), "1.23");
+        assert_eq!(fmt("[%*d]", vec![Arg::Int(4), Arg::Int(7)]), "[   7]");
+        assert_eq!(fmt("[%*d]", vec![Arg::Int(-4), Arg::Int(7)]), "[7   ]");
+        assert_eq!(fmt("%.*f", vec![Arg::Int(-1), Arg::Float(0.5)]), "0.500000");
+    }
+
+    #[test]
+    fn strings_and_characters() {
+        assert_eq!(fmt("Hi %s!", vec![Arg::Str("Bob")]), "Hi Bob!");
+        assert_eq!(fmt("%.3s", vec![Arg::Str("abcdef")]), "abc");
+        assert_eq!(fmt("[%6s]", vec![Arg::Str("ab")]), "[    ab]");
+        assert_eq!(fmt("[%-6s]", vec![Arg::Str("ab")]), "[ab    ]");
+        assert_eq!(fmt("[%06s]", vec![Arg::Str("ab")]), "[    ab]");
+        assert_eq!(fmt("%c%c", vec![Arg::Int(65), Arg::Int(0x142)]), "AB");
+        assert_eq!(fmt("[%3c]", vec![Arg::Int(b'x'.into())]), "[  x]");
+    }
+
+    #[test]
+    fn q_escapes_single_quotes() {
+        assert_eq!(
+            fmt("name = '%q'", vec![Arg::Str("O'Brien")]),
+            "name = 'O''Brien'"
+        );
+        assert_eq!(fmt("%.2q", vec![Arg::Str("''''")]), "''''");
+    }
+
+    #[test]
+    fn literal_and_unknown_specifiers() {
+        assert_eq!(fmt("100%%", vec![]), "100%");
+        assert_eq!(fmt("%-5y|%u", vec![]), "%-5y|%u");
+        assert_eq!(fmt("trailing %", vec![]), "trailing ");
+    }
+
+    #[test]
+    fn missing_arguments_are_an_error() {
+        let result = render(b"%d %d", &mut Fake(vec![Arg::Int(1)].into_iter()));
+        assert!(matches!(result, Err(AmxError::Params)));
+    }
+}
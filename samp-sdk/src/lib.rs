@@ -4,7 +4,8 @@
 //!
 //! - **AMX**: pointers, cell types, function table and error codes of the
 //!   Pawn VM used by SA-MP (modules [`raw`], [`amx`], [`cell`], [`args`],
-//!   [`error`], [`exports`], [`consts`]).
+//!   [`error`], [`exports`], [`consts`]), plus a Pawn-compatible
+//!   [`format`].
 //! - **Open Multiplayer**: vtables, binary layout of `IComponent` and typed wrappers
 //!   of the native server interfaces (module [`omp`], active while the
 //!   `samp-only` feature is not enabled).
@@ -24,6 +25,7 @@ pub mod debug;
 pub mod encoding;
 pub mod error;
 pub mod exports;
+pub mod format;
 #[doc(hidden)]
 pub mod macros;
 // The open.mp component ABI on Windows uses the `thiscall` calling convention,
@@ -37,5 +39,8 @@ pub mod macros;
 ))]
 pub mod omp;
 pub mod raw;
+// Always built for the crate's own tests; downstream enables `test-vm`.
+#[cfg(any(test, feature = "test-vm"))]
+pub mod test_vm;
 #[cfg(test)]
 mod tests;
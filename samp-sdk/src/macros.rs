@@ -2,9 +2,10 @@
 ///
 /// Resolves the function index via `amx_FindPublic` and pushes the arguments
 /// before invoking `amx_Exec`. Types implementing `AmxCell` are passed directly.
-/// Rust strings and slices use alternative syntax (`expr => string` / `=> array`):
-/// the macro allocates memory on the AMX heap, copies the content, and releases
-/// everything when the `Allocator` goes out of scope.
+/// Rust strings and slices use alternative syntax (`expr => string` or
+/// `=> packed_string`, `=> array`, and `=> array2d` / `=> strings` for 2-D
+/// arrays): the macro allocates memory on the AMX heap, copies the content,
+/// and releases everything when the `Allocator` goes out of scope.
 ///
 /// # Examples
 ///
@@ -48,6 +49,17 @@
 ///     Ok(true)
 /// }
 /// ```
+///
+/// With 2-D arrays (`rows[][]`) and string arrays (`names[][]`):
+/// ```rust,no_run
+/// use samp_sdk::exec_public;
+/// # use samp_sdk::amx::Amx;
+/// # use samp_sdk::error::AmxResult;
+/// // forward OnPlayerList(const names[][], count, const positions[][]);
+/// fn notify(amx: &Amx, names: &[String], positions: &[[i32; 3]]) -> AmxResult<i32> {
+///     exec_public!(amx, "OnPlayerList", names => strings, names.len(), positions => array2d)
+/// }
+/// ```
 #[macro_export]
 macro_rules! exec_public {
     ($amx:expr, $pubname:expr) => {
@@ -74,6 +86,16 @@ macro_rules! exec_public {
         exec_public!(@ $amx, $al, $arg => string);
     };
 
+    (@ $amx:expr, $al:ident, $arg:expr => packed_string) => {
+        let string = $al.allot_packed_string($arg)?;
+        $amx.push(string)?;
+    };
+
+    (@ $amx:expr, $al:ident, $arg:expr => packed_string, $($tail:tt)+) => {
+        exec_public!(@ $amx, $al, $($tail)+);
+        exec_public!(@ $amx, $al, $arg => packed_string);
+    };
+
     (@ $amx:expr, $al:ident, $arg:expr => array) => {
         let array = $al.allot_array($arg)?;
         $amx.push(array)?;
@@ -84,6 +106,26 @@ macro_rules! exec_public {
         exec_public!(@ $amx, $al, $arg => array);
     };
 
+    (@ $amx:expr, $al:ident, $arg:expr => array2d) => {
+        let array = $al.allot_array_2d($arg)?;
+        $amx.push(array)?;
+    };
+
+    (@ $amx:expr, $al:ident, $arg:expr => array2d, $($tail:tt)+) => {
+        exec_public!(@ $amx, $al, $($tail)+);
+        exec_public!(@ $amx, $al, $arg => array2d);
+    };
+
+    (@ $amx:expr, $al:ident, $arg:expr => strings) => {
+        let strings = $al.allot_string_array($arg)?;
+        $amx.push(strings)?;
+    };
+
+    (@ $amx:expr, $al:ident, $arg:expr => strings, $($tail:tt)+) => {
+        exec_public!(@ $amx, $al, $($tail)+);
+        exec_public!(@ $amx, $al, $arg => strings);
+    };
+
     ($amx:expr, $pubname:expr, $($args:tt)+) => {
         {
             let allocator = $amx.allocator();
@@ -95,3 +137,145 @@ macro_rules! exec_public {
         }
     };
 }
+
+/// Calls a native registered by another plugin (or the server) with typed
+/// arguments — the marshalling counterpart of
+/// [`Amx::call_native`](crate::amx::Amx::call_native).
+///
+/// Types implementing `AmxCell` (`i32`, `f32`, `bool`, `AmxString`, …) are
+/// passed as their cell, so floats need no bit-casting. The heap arms of
+/// [`exec_public!`] (`=> string`, `=> packed_string`, `=> array`,
+/// `=> array2d`, `=> strings`) work the same way, and three more pass a
+/// place by reference and read it back once the native returns:
+/// - `&mut value => ref` — one cell (`&Float:x`); `value` is any
+///   `CellConvert + Copy` type and is passed in as well;
+/// - `&mut values => ref_array` — an array (`values[]`) of `CellConvert +
+///   Copy` elements, passed in and read back;
+/// - `&mut string => ref_string(len)` — an output string of `len` cells
+///   (`dest[], len`), decoded into the `String`.
+///
+/// Everything allocated is released when the call returns. The result is
+/// `AmxResult<i32>`, or `AmxResult<T>` with `"Name" => T` for any
+/// `CellConvert` type `T`.
+///
+/// The native is named by a string, looked up on every call, by a
+/// [`NativeHandle`](crate::amx::NativeHandle) from
+/// [`Amx::resolve_native`](crate::amx::Amx::resolve_native) for natives
+/// called often, or given as a host function
+/// ([`AmxNative`](crate::raw::functions::AmxNative)), which runs in `amx`
+/// whether or not the script declares it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use samp_sdk::call_native;
+/// # use samp_sdk::amx::Amx;
+/// # use samp_sdk::error::AmxResult;
+/// fn spawn_crate(amx: &Amx) -> AmxResult<i32> {
+///     // CreateDynamicObject(modelid, Float:x, Float:y, Float:z, Float:rx, Float:ry, Float:rz)
+///     call_native!(amx, "CreateDynamicObject", 1224, 100.0_f32, 200.0_f32, 10.0_f32, 0.0_f32, 0.0_f32, 0.0_f32)
+/// }
+///
+/// fn describe(amx: &Amx, playerid: i32) -> AmxResult<String> {
+///     let mut name = String::new();
+///     call_native!(amx, "GetPlayerName", playerid, &mut name => ref_string(24), 24)?;
+///
+///     let (mut x, mut y, mut z) = (0.0_f32, 0.0_f32, 0.0_f32);
+///     let found = call_native!(amx, "GetPlayerPos" => bool, playerid, &mut x => ref, &mut y => ref, &mut z => ref)?;
+///     Ok(if found { format!("{name} at {x}, {y}, {z}") } else { name })
+/// }
+/// ```
+#[macro_export]
+macro_rules! call_native {
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident;) => {
+        $crate::amx::NativeTarget::invoke(&$name, $amx, &$params)?
+    };
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => string $(, $($tail:tt)*)?) => {{
+        let string = $al.allot_string($arg)?;
+        $params.push($crate::cell::AmxCell::as_cell(&string));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => packed_string $(, $($tail:tt)*)?) => {{
+        let string = $al.allot_packed_string($arg)?;
+        $params.push($crate::cell::AmxCell::as_cell(&string));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => array $(, $($tail:tt)*)?) => {{
+        let array = $al.allot_array($arg)?;
+        $params.push($crate::cell::AmxCell::as_cell(&array));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => array2d $(, $($tail:tt)*)?) => {{
+        let array = $al.allot_array_2d($arg)?;
+        $params.push($crate::cell::AmxCell::as_cell(&array));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => strings $(, $($tail:tt)*)?) => {{
+        let strings = $al.allot_string_array($arg)?;
+        $params.push($crate::cell::AmxCell::as_cell(&strings));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => ref $(, $($tail:tt)*)?) => {{
+        let place = $arg;
+        let cell = $al.allot($crate::cell::CellConvert::into_cell(*place))?;
+        $params.push($crate::cell::AmxCell::as_cell(&cell));
+        let retval = $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?);
+        *place = $crate::cell::CellConvert::from_cell(*cell);
+        retval
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => ref_array $(, $($tail:tt)*)?) => {{
+        let place = $arg;
+        let mut buffer = $al.allot_buffer(place.len())?;
+        for (cell, value) in buffer.as_mut_slice().iter_mut().zip(place.iter()) {
+            *cell = $crate::cell::CellConvert::into_cell(*value);
+        }
+        $params.push($crate::cell::AmxCell::as_cell(&buffer));
+        let retval = $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?);
+        for (value, cell) in place.iter_mut().zip(buffer.as_slice()) {
+            *value = $crate::cell::CellConvert::from_cell(*cell);
+        }
+        retval
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr => ref_string($len:expr) $(, $($tail:tt)*)?) => {{
+        let place: &mut String = $arg;
+        let mut buffer = $al.allot_buffer($len)?;
+        buffer.as_mut_slice().fill(0);
+        $params.push($crate::cell::AmxCell::as_cell(&buffer));
+        let retval = $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?);
+        *place = buffer.into_string().to_string();
+        retval
+    }};
+
+    (@ $amx:expr, $al:ident, $name:expr, $params:ident; $arg:expr $(, $($tail:tt)*)?) => {{
+        $params.push($crate::cell::AmxCell::as_cell(&$arg));
+        $crate::call_native!(@ $amx, $al, $name, $params; $($($tail)*)?)
+    }};
+
+    ($amx:expr, $name:expr => $ret:ty $(, $($args:tt)*)?) => {
+        $crate::call_native!($amx, $name $(, $($args)*)?)
+            .map(<$ret as $crate::cell::CellConvert>::from_cell)
+    };
+
+    ($amx:expr, $name:expr $(, $($args:tt)*)?) => {{
+        // Evaluated once, whatever the expression does.
+        let amx = &$amx;
+        // Unused when no argument goes through the heap.
+        #[allow(unused_variables)]
+        let allocator = amx.allocator();
+        let mut params: Vec<i32> = Vec::new();
+        // Called in place so `?` in the arms ends the call, not the caller.
+        #[allow(clippy::redundant_closure_call)]
+        let retval = (|| -> $crate::error::AmxResult<i32> {
+            Ok($crate::call_native!(@ amx, allocator, $name, params; $($($args)*)?))
+        })();
+        retval
+    }};
+}
@@ -7,7 +7,8 @@
 //! component's lifecycle.
 //!
 //! Offsets and slots were confirmed via disasm of `Console.dll` / `Console.so`
-//! and `omp-server.exe`, not by guessing from the C++ headers.
+//! and `omp-server.exe`, not by guessing from the C++ headers. The exception
+//! is [`players`], whose slots follow the headers and are marked as such.
 //!
 //! Supports the two i686 ABIs used by the server:
 //! - **Itanium** (Linux GCC) — calling convention `extern "C"`
@@ -18,6 +19,7 @@ pub mod component;
 pub mod component_api;
 pub mod core;
 pub mod events;
+pub mod players;
 pub mod server;
 pub mod timers;
 pub mod types;
@@ -30,10 +32,16 @@ pub use component::{
 pub use component_api::{OmpComponentHandle, component_name, component_version};
 pub use core::{LogLevel, core_log_ln, core_log_ln_u8, core_print_ln, core_print_ln_u8};
 pub use events::{PawnEventHandler, PawnEventHandlerVTable};
+pub use players::{
+    IEventDispatcherPlayerConnect, IPlayer, IPlayerPool, PlayerConnectEventHandler,
+    PlayerConnectEventHandlerVTable, add_player_connect_handler, core_players,
+    player_connect_dispatcher, player_id, remove_player_connect_handler,
+};
 pub use server::{
     AmxFunctionTable, IEventDispatcherPawn, IPawnScript, PAWN_COMPONENT_UID, PawnComponent,
     ServerComponentList, ServerPawnComponent, add_pawn_event_handler, get_amx_from_script,
-    get_amx_functions, get_pawn_event_dispatcher, query_component, remove_pawn_event_handler,
+    get_amx_functions, get_main_script, get_pawn_event_dispatcher, query_component,
+    remove_pawn_event_handler,
 };
 pub use timers::{
     ITimer, ITimersComponent, TIMERS_COMPONENT_UID, TimerHandlerVTable, TimerTimeOutHandler,
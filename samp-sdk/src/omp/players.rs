@@ -0,0 +1,278 @@
+//! Bindings for the Open Multiplayer `IPlayerPool` connection events.
+//!
+//! `ICore::getPlayers()` returns the server's `IPlayerPool`, whose
+//! `IEventDispatcher<PlayerConnectEventHandler>` reports every player that
+//! connects or leaves, whether or not a Pawn script declares
+//! `OnPlayerConnect`/`OnPlayerDisconnect`. The `samp` crate registers a
+//! handler on it to deliver those callbacks to scripts that lack them.
+//!
+//! **Not confirmed via disasm.** Unlike the rest of [`crate::omp`], the slots
+//! below follow the declaration order of the open.mp SDK headers (`core.hpp`,
+//! `player.hpp`, `entity.hpp`), with destructor slots placed as for
+//! `IComponentList`. Dump them against the server before relying on them.
+//!
+//! ## `ICore` primary vtable
+//!
+//! - **[0..3]** `IExtensible`
+//! - **[4]** destructor (1 slot MSVC / 2 slots Itanium)
+//! - `getVersion()`, `getNetworkBitStreamVersion()`
+//! - **[8 Itanium / 7 MSVC]** `getPlayers()` -> `IPlayerPool&`
+//!
+//! ## `IPlayerPool` primary vtable
+//!
+//! - **[0..3]** `IExtensible`
+//! - **[4]** destructor (1 slot MSVC / 2 slots Itanium)
+//! - `entries()`, `players()`, `bots()`, `isNameTaken()`,
+//!   `getPlayerSpawnDispatcher()`
+//! - **[11 Itanium / 10 MSVC]** `getPlayerConnectDispatcher()`
+//!
+//! ## `IPlayer`
+//!
+//! `IPlayer : public IExtensible, public IEntity`. The `IEntity` subobject
+//! follows the `IExtensible` vtable pointer; slot **[0]** of its vtable is
+//! `IIDProvider::getID()`.
+//!
+//! ## `PlayerConnectEventHandler` vtable (interface provided by the plugin)
+//!
+//! No virtual destructor in the header -> 4 slots:
+//! - **[0]** `onIncomingConnection(IPlayer&, StringView ipAddress, unsigned short port)`
+//! - **[1]** `onPlayerConnect(IPlayer&)`
+//! - **[2]** `onPlayerDisconnect(IPlayer&, PeerDisconnectReason)`
+//! - **[3]** `onPlayerClientInit(IPlayer&)`
+
+use super::component::ICore;
+use super::types::StringView;
+use std::os::raw::c_int;
+
+/// Slot of `getPlayers()` in the `ICore` vtable.
+#[cfg(not(target_env = "msvc"))]
+const SLOT_CORE_GET_PLAYERS: usize = 8;
+#[cfg(target_env = "msvc")]
+const SLOT_CORE_GET_PLAYERS: usize = 7;
+
+/// Slot of `getPlayerConnectDispatcher()` in the `IPlayerPool` vtable.
+#[cfg(not(target_env = "msvc"))]
+const SLOT_POOL_CONNECT_DISPATCHER: usize = 11;
+#[cfg(target_env = "msvc")]
+const SLOT_POOL_CONNECT_DISPATCHER: usize = 10;
+
+/// Offset of the `IEntity` subobject inside `IPlayer`.
+const IENTITY_OFFSET: isize = std::mem::size_of::<usize>() as isize;
+
+/// Slot of `getID()` in the `IEntity` vtable.
+const SLOT_ENTITY_GET_ID: usize = 0;
+
+/// Slots of `addEventHandler` / `removeEventHandler` in `IEventDispatcher<T>`.
+const SLOT_ADD_HANDLER: usize = 0;
+const SLOT_REMOVE_HANDLER: usize = 1;
+
+/// Opaque pointer to the server's `IPlayerPool`.
+#[repr(C)]
+pub struct IPlayerPool {
+    _opaque: [u8; 0],
+}
+
+/// Opaque pointer to a server `IPlayer`.
+#[repr(C)]
+pub struct IPlayer {
+    _opaque: [u8; 0],
+}
+
+/// Opaque pointer to the server's `IEventDispatcher<PlayerConnectEventHandler>`.
+#[repr(C)]
+pub struct IEventDispatcherPlayerConnect {
+    _opaque: [u8; 0],
+}
+
+/// `PlayerConnectEventHandler` vtable — Itanium ABI.
+#[cfg(not(target_env = "msvc"))]
+#[repr(C)]
+pub struct PlayerConnectEventHandlerVTable {
+    pub on_incoming_connection:
+        unsafe extern "C" fn(*mut PlayerConnectEventHandler, *mut IPlayer, StringView, u16),
+    pub on_player_connect: unsafe extern "C" fn(*mut PlayerConnectEventHandler, *mut IPlayer),
+    pub on_player_disconnect:
+        unsafe extern "C" fn(*mut PlayerConnectEventHandler, *mut IPlayer, c_int),
+    pub on_player_client_init: unsafe extern "C" fn(*mut PlayerConnectEventHandler, *mut IPlayer),
+}
+
+/// `PlayerConnectEventHandler` vtable — MSVC ABI (`this` in ECX).
+#[cfg(target_env = "msvc")]
+#[repr(C)]
+pub struct PlayerConnectEventHandlerVTable {
+    pub on_incoming_connection:
+        unsafe extern "thiscall" fn(*mut PlayerConnectEventHandler, *mut IPlayer, StringView, u16),
+    pub on_player_connect:
+        unsafe extern "thiscall" fn(*mut PlayerConnectEventHandler, *mut IPlayer),
+    pub on_player_disconnect:
+        unsafe extern "thiscall" fn(*mut PlayerConnectEventHandler, *mut IPlayer, c_int),
+    pub on_player_client_init:
+        unsafe extern "thiscall" fn(*mut PlayerConnectEventHandler, *mut IPlayer),
+}
+
+/// Rust object compatible with Open Multiplayer's `PlayerConnectEventHandler*`.
+#[repr(C)]
+pub struct PlayerConnectEventHandler {
+    vtable: *const PlayerConnectEventHandlerVTable,
+}
+
+// SAFETY: handler is only accessed on the server's main thread.
+unsafe impl Send for PlayerConnectEventHandler {}
+unsafe impl Sync for PlayerConnectEventHandler {}
+
+impl PlayerConnectEventHandler {
+    /// Creates a new handler with the supplied vtable.
+    #[must_use]
+    pub fn new(vtable: *const PlayerConnectEventHandlerVTable) -> Self {
+        Self { vtable }
+    }
+}
+
+#[cfg(not(target_env = "msvc"))]
+type GetPlayersFn = unsafe extern "C" fn(*mut ICore) -> *mut IPlayerPool;
+#[cfg(target_env = "msvc")]
+type GetPlayersFn = unsafe extern "thiscall" fn(*mut ICore) -> *mut IPlayerPool;
+
+#[cfg(not(target_env = "msvc"))]
+type GetConnectDispatcherFn =
+    unsafe extern "C" fn(*mut IPlayerPool) -> *mut IEventDispatcherPlayerConnect;
+#[cfg(target_env = "msvc")]
+type GetConnectDispatcherFn =
+    unsafe extern "thiscall" fn(*mut IPlayerPool) -> *mut IEventDispatcherPlayerConnect;
+
+#[cfg(not(target_env = "msvc"))]
+type AddHandlerFn = unsafe extern "C" fn(
+    *mut IEventDispatcherPlayerConnect,
+    *mut PlayerConnectEventHandler,
+    i8,
+) -> bool;
+#[cfg(target_env = "msvc")]
+type AddHandlerFn = unsafe extern "thiscall" fn(
+    *mut IEventDispatcherPlayerConnect,
+    *mut PlayerConnectEventHandler,
+    i8,
+) -> bool;
+
+#[cfg(not(target_env = "msvc"))]
+type RemoveHandlerFn = unsafe extern "C" fn(
+    *mut IEventDispatcherPlayerConnect,
+    *mut PlayerConnectEventHandler,
+) -> bool;
+#[cfg(target_env = "msvc")]
+type RemoveHandlerFn = unsafe extern "thiscall" fn(
+    *mut IEventDispatcherPlayerConnect,
+    *mut PlayerConnectEventHandler,
+) -> bool;
+
+#[cfg(not(target_env = "msvc"))]
+type GetIdFn = unsafe extern "C" fn(*mut u8) -> c_int;
+#[cfg(target_env = "msvc")]
+type GetIdFn = unsafe extern "thiscall" fn(*mut u8) -> c_int;
+
+/// `ICore::getPlayers()` — the server's player pool. Null when `core` is null.
+///
+/// # Safety
+/// `core` must be null or point to a valid `ICore` received in `on_load`.
+pub unsafe fn core_players(core: *mut ICore) -> *mut IPlayerPool {
+    let Some((_, slot)) = (unsafe {
+        super::vtable::secondary_call_target(core.cast::<u8>(), 0, SLOT_CORE_GET_PLAYERS)
+    }) else {
+        return std::ptr::null_mut();
+    };
+    let get_players: GetPlayersFn = unsafe { std::mem::transmute(slot) };
+    unsafe { get_players(core) }
+}
+
+/// `IPlayerPool::getPlayerConnectDispatcher()`. Null when `pool` is null.
+///
+/// # Safety
+/// `pool` must be null or a valid pointer returned by [`core_players`].
+pub unsafe fn player_connect_dispatcher(
+    pool: *mut IPlayerPool,
+) -> *mut IEventDispatcherPlayerConnect {
+    let Some((_, slot)) = (unsafe {
+        super::vtable::secondary_call_target(pool.cast::<u8>(), 0, SLOT_POOL_CONNECT_DISPATCHER)
+    }) else {
+        return std::ptr::null_mut();
+    };
+    let get_dispatcher: GetConnectDispatcherFn = unsafe { std::mem::transmute(slot) };
+    unsafe { get_dispatcher(pool) }
+}
+
+/// Registers a connection handler in the dispatcher. Returns `false` when
+/// the server refused it (or the dispatcher is null).
+///
+/// # Safety
+/// `dispatcher` must be null or valid. `handler` must outlive its registration.
+pub unsafe fn add_player_connect_handler(
+    dispatcher: *mut IEventDispatcherPlayerConnect,
+    handler: *mut PlayerConnectEventHandler,
+) -> bool {
+    let Some((_, slot)) = (unsafe {
+        super::vtable::secondary_call_target(dispatcher.cast::<u8>(), 0, SLOT_ADD_HANDLER)
+    }) else {
+        return false;
+    };
+    let add: AddHandlerFn = unsafe { std::mem::transmute(slot) };
+    unsafe { add(dispatcher, handler, 0) }
+}
+
+/// Removes a connection handler from the dispatcher.
+///
+/// # Safety
+/// `dispatcher` must be null or valid.
+pub unsafe fn remove_player_connect_handler(
+    dispatcher: *mut IEventDispatcherPlayerConnect,
+    handler: *mut PlayerConnectEventHandler,
+) {
+    let Some((_, slot)) = (unsafe {
+        super::vtable::secondary_call_target(dispatcher.cast::<u8>(), 0, SLOT_REMOVE_HANDLER)
+    }) else {
+        return;
+    };
+    let remove: RemoveHandlerFn = unsafe { std::mem::transmute(slot) };
+    unsafe { remove(dispatcher, handler) };
+}
+
+/// `IPlayer::getID()` — the player's id, as Pawn sees it. `None` when
+/// `player` is null or its `IEntity` vtable is unreadable.
+///
+/// # Safety
+/// `player` must be null or a live `IPlayer` handed to a
+/// `PlayerConnectEventHandler`.
+pub unsafe fn player_id(player: *mut IPlayer) -> Option<i32> {
+    let (this, slot) = unsafe {
+        super::vtable::secondary_call_target(
+            player.cast::<u8>(),
+            IENTITY_OFFSET,
+            SLOT_ENTITY_GET_ID,
+        )?
+    };
+    let get_id: GetIdFn = unsafe { std::mem::transmute(slot) };
+    Some(unsafe { get_id(this) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connect_handler_vtable_has_four_slots() {
+        assert_eq!(
+            std::mem::size_of::<PlayerConnectEventHandlerVTable>(),
+            4 * std::mem::size_of::<*const ()>()
+        );
+    }
+
+    #[test]
+    fn null_objects_are_refused() {
+        unsafe {
+            assert!(core_players(std::ptr::null_mut()).is_null());
+            assert!(player_connect_dispatcher(std::ptr::null_mut()).is_null());
+            let handler = std::ptr::dangling_mut::<PlayerConnectEventHandler>();
+            assert!(!add_player_connect_handler(std::ptr::null_mut(), handler));
+            remove_player_connect_handler(std::ptr::null_mut(), handler);
+            assert_eq!(player_id(std::ptr::null_mut()), None);
+        }
+    }
+}
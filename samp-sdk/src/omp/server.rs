@@ -168,8 +168,10 @@ pub struct ServerPawnComponent {
 // Useful slots (in both):
 //   [18-19 Itanium / 16-17 MSVC] getEventDispatcher, getAmxFunctions
 //
-// Trailing opaques (4 slots, identical in both ABIs):
+// Trailing slots (4 slots, identical in both ABIs):
 //   getScript(const), getScript(mut), mainScript, sideScripts
+// Only mainScript is called. Its slot follows the header and has not been
+// confirmed by a runtime dump.
 #[cfg(not(target_env = "msvc"))]
 type GetEventDispatcherFn =
     unsafe extern "C" fn(*mut ServerPawnComponent) -> *mut IEventDispatcherPawn;
@@ -184,6 +186,11 @@ type GetAmxFunctionsFn =
 type GetAmxFunctionsFn =
     unsafe extern "thiscall" fn(*const ServerPawnComponent) -> *const AmxFunctionTable;
 
+#[cfg(not(target_env = "msvc"))]
+type MainScriptFn = unsafe extern "C" fn(*mut ServerPawnComponent) -> *mut IPawnScript;
+#[cfg(target_env = "msvc")]
+type MainScriptFn = unsafe extern "thiscall" fn(*mut ServerPawnComponent) -> *mut IPawnScript;
+
 #[cfg(not(target_env = "msvc"))]
 const PAWN_COMPONENT_PREFIX_SLOTS: usize = 18;
 #[cfg(target_env = "msvc")]
@@ -194,10 +201,10 @@ struct ServerPawnComponentVTable {
     _prefix: [*const (); PAWN_COMPONENT_PREFIX_SLOTS],
     get_event_dispatcher: GetEventDispatcherFn,
     get_amx_functions: GetAmxFunctionsFn,
-    // Trailing opaques common to both ABIs.
+    // Trailing slots common to both ABIs.
     _get_script_const: *const (),
     _get_script_mut: *const (),
-    _main_script: *const (),
+    main_script: MainScriptFn,
     _side_scripts: *const (),
 }
 
@@ -316,6 +323,16 @@ pub unsafe fn get_pawn_event_dispatcher(pawn: *mut ServerComponent) -> *mut IEve
     unsafe { ((*(*pawn).vtable).get_event_dispatcher)(pawn) }
 }
 
+/// Gets the gamemode's `IPawnScript` from the `IPawnComponent` — null while
+/// no gamemode is loaded.
+///
+/// # Safety
+/// `pawn` must be a valid pointer to an Open Multiplayer server `IPawnComponent`.
+pub unsafe fn get_main_script(pawn: *mut ServerComponent) -> *mut IPawnScript {
+    let pawn = pawn.cast::<ServerPawnComponent>();
+    unsafe { ((*(*pawn).vtable).main_script)(pawn) }
+}
+
 /// Gets the pointer to the AMX function table from the `IPawnComponent`.
 ///
 /// # Safety
@@ -336,8 +353,9 @@ use std::ptr::NonNull;
 /// Typed wrapper for the Open Multiplayer server's `IPawnComponent`.
 ///
 /// Obtained via `samp::plugin::omp_query::<PawnComponent>()`. Exposes the
-/// Pawn-specific methods (event dispatcher, AMX functions) in addition to the
-/// generic `IComponent` ones (`name()`, `version()` via `component_api`).
+/// Pawn-specific methods (event dispatcher, AMX functions, main script) in
+/// addition to the generic `IComponent` ones (`name()`, `version()` via
+/// `component_api`).
 #[derive(Debug, Clone, Copy)]
 pub struct PawnComponent {
     ptr: NonNull<ServerComponent>,
@@ -384,6 +402,13 @@ impl PawnComponent {
     pub fn amx_functions(&self) -> usize {
         unsafe { get_amx_functions(self.ptr.as_ptr()) }
     }
+
+    /// Returns the gamemode's `IPawnScript` (`mainScript()`), or null while
+    /// no gamemode is loaded.
+    #[must_use]
+    pub fn main_script(&self) -> *mut IPawnScript {
+        unsafe { get_main_script(self.ptr.as_ptr()) }
+    }
 }
 
 #[cfg(test)]
@@ -5,8 +5,8 @@
 //! in [`crate::amx::Amx`] instead of storing every pointer — most calls
 //! never happen in a typical plugin.
 //!
-//! `Logprintf` at the end of the file comes from another table (`ppData` of `Load()`),
-//! not from `amx_Exports`.
+//! `Logprintf` and `CallPublic` at the end of the file come from another table
+//! (`ppData` of `Load()`), not from `amx_Exports`.
 
 use super::types::{AMX, AMX_NATIVE_INFO};
 use std::ffi::{c_char, c_void};
@@ -24,7 +24,7 @@ pub type AmxDebug = extern "C" fn(*mut AMX) -> i32;
 
 pub type Align16 = extern "C" fn(*mut u16) -> *mut u16;
 pub type Align32 = extern "C" fn(*mut u32) -> *mut u32;
-pub type Allot = extern "C" fn(*mut AMX, i32, *mut i32, *mut i32) -> i32;
+pub type Allot = extern "C" fn(*mut AMX, i32, *mut i32, *mut *mut i32) -> i32;
 pub type Callback = extern "C" fn(*mut AMX, i32, *mut i32, *mut i32) -> i32;
 pub type Cleanup = extern "C" fn(*mut AMX) -> i32;
 pub type Clone = extern "C" fn(*mut AMX, *mut AMX, *mut c_void) -> i32;
@@ -68,3 +68,8 @@ pub type UTF8Len = extern "C" fn(*const i32, *mut i32) -> i32;
 pub type UTF8Put = extern "C" fn(*mut c_char, *mut *mut c_char, i32, i32) -> i32;
 
 pub type Logprintf = extern "C" fn(*const c_char);
+
+/// `CallPublicFs`/`CallPublicGm` of `ppData`: runs the argument-less public
+/// `name` in every filterscript / in the gamemode and returns its result (`0`
+/// when no script defines it).
+pub type CallPublic = extern "C" fn(name: *const c_char) -> i32;
@@ -0,0 +1,978 @@
+//! The interpreter's `amx_Exports` table and the `ppData` table of `Load()`.
+//!
+//! Every function mirrors its `amx.c` counterpart (same arguments, same
+//! `AMX_ERR_*` codes) and is type-checked against the aliases in
+//! [`crate::raw::functions`] when the table is built.
+
+use super::{FILTERSCRIPTS, GAMEMODE, LOG, VmState, code, interp};
+use crate::consts::AmxFlags;
+use crate::error::AmxError;
+use crate::exports::{Exec, Export, Exports, FindPublic};
+use crate::raw::functions::{self, AmxCallback, AmxDebug, AmxNative};
+use crate::raw::types::{AMX, AMX_NATIVE_INFO};
+
+use std::ffi::{CStr, c_char, c_long, c_void};
+use std::sync::OnceLock;
+use std::sync::atomic::Ordering;
+
+/// Minimum gap kept between heap and stack (`STKMARGIN` in `amx.c`).
+pub(super) const STKMARGIN: i32 = 16 * 4;
+
+/// Longest name of the inline-name format (`sEXPMAX` in `amx.c`).
+const INLINE_NAME_MAX: usize = 19;
+
+const NONE: i32 = 0;
+
+/// `*mut AMX` → `*mut VmState`; `None` for a null pointer.
+fn state(amx: *mut AMX) -> Option<*mut VmState> {
+    (!amx.is_null()).then(|| amx.cast::<VmState>())
+}
+
+unsafe fn base(vm: *mut VmState) -> *mut u8 {
+    unsafe { amx_get!(vm, base) }
+}
+
+/// Start of the data segment: `amx->data`, or `base + dat` when unset.
+pub(super) unsafe fn data(vm: *mut VmState) -> *mut u8 {
+    unsafe {
+        let data = amx_get!(vm, data);
+        if data.is_null() {
+            base(vm).add((*vm).layout.dat as usize)
+        } else {
+            data
+        }
+    }
+}
+
+/// Total bytes of the image (header through stack).
+unsafe fn image_len(vm: *mut VmState) -> usize {
+    unsafe { (*vm).memory.len() * 4 }
+}
+
+unsafe fn read_u32(vm: *mut VmState, offset: usize) -> u32 {
+    unsafe { base(vm).add(offset).cast::<u32>().read_unaligned() }
+}
+
+/// Byte offset of entry `index` of the table starting at `table`.
+unsafe fn entry(vm: *mut VmState, table: i32, index: i32) -> usize {
+    unsafe { (table + index * (*vm).layout.defsize) as usize }
+}
+
+/// Name of a table entry — from the name table or inline, per `defsize`.
+unsafe fn entry_name(vm: *mut VmState, table: i32, index: i32) -> Vec<u8> {
+    unsafe {
+        let offset = entry(vm, table, index);
+        let (start, max) = if (*vm).layout.defsize == super::image::DEFSIZE_NAMETABLE {
+            (read_u32(vm, offset + 4) as usize, usize::MAX)
+        } else {
+            (offset + 4, INLINE_NAME_MAX + 1)
+        };
+        let end = image_len(vm);
+        let mut name = Vec::new();
+        let mut at = start;
+        while at < end && name.len() < max {
+            let byte = base(vm).add(at).read();
+            if byte == 0 {
+                break;
+            }
+            name.push(byte);
+            at += 1;
+        }
+        name
+    }
+}
+
+/// Linear lookup of `name` in a table of `count` entries.
+unsafe fn find(vm: *mut VmState, table: i32, count: i32, name: *const c_char) -> Option<i32> {
+    if name.is_null() {
+        return None;
+    }
+    let wanted = unsafe { CStr::from_ptr(name) }.to_bytes();
+    (0..count).find(|&i| unsafe { entry_name(vm, table, i) } == wanted)
+}
+
+/// Writes `name` + NUL to a caller buffer (sized `sNAMEMAX + 1` by contract).
+unsafe fn copy_name(dest: *mut c_char, name: &[u8]) {
+    if dest.is_null() {
+        return;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(name.as_ptr().cast::<c_char>(), dest, name.len());
+        dest.add(name.len()).write(0);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// amx_Exports
+// ---------------------------------------------------------------------------
+
+// The table targets little-endian hosts only (x86), where the align helpers
+// are the identity — same as `amx.c` on those machines.
+extern "C" fn align16(v: *mut u16) -> *mut u16 {
+    v
+}
+
+extern "C" fn align32(v: *mut u32) -> *mut u32 {
+    v
+}
+
+extern "C" fn align64(v: *mut u64) -> *mut u64 {
+    v
+}
+
+extern "C" fn allot(
+    amx: *mut AMX,
+    cells: i32,
+    amx_addr: *mut i32,
+    phys_addr: *mut *mut i32,
+) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::Memory);
+    };
+    unsafe {
+        let hea = amx_get!(vm, hea);
+        let stk = amx_get!(vm, stk);
+        let Some(bytes) = cells.checked_mul(4).filter(|b| *b >= 0) else {
+            return code(AmxError::Memory);
+        };
+        if i64::from(stk) - i64::from(hea) - i64::from(bytes) < i64::from(STKMARGIN) {
+            return code(AmxError::Memory);
+        }
+        if !amx_addr.is_null() {
+            *amx_addr = hea;
+        }
+        if !phys_addr.is_null() {
+            *phys_addr = data(vm).add(hea as usize).cast::<i32>();
+        }
+        amx_set!(vm, hea, hea + bytes);
+    }
+    NONE
+}
+
+/// Default native dispatcher (`amx_Callback`), installed as `AMX::callback`.
+pub(super) extern "C" fn callback(
+    amx: *mut AMX,
+    index: i32,
+    result: *mut i32,
+    params: *mut i32,
+) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::Callback);
+    };
+    let natives = unsafe { &(*vm).natives };
+    let native = usize::try_from(index)
+        .ok()
+        .and_then(|i| natives.get(i).copied().flatten());
+    let Some(native) = native else {
+        return code(AmxError::NotFound);
+    };
+    unsafe {
+        amx_set!(vm, error, 0);
+        let value = native(amx, params);
+        if !result.is_null() {
+            *result = value;
+        }
+        amx_get!(vm, error)
+    }
+}
+
+/// Placeholder for `AMX::debug` — the C field is nullable, the Rust one is not.
+pub(super) extern "C" fn no_debug(_amx: *mut AMX) -> i32 {
+    NONE
+}
+
+extern "C" fn cleanup(_amx: *mut AMX) -> i32 {
+    // Memory belongs to the `TestVm`, released on drop.
+    NONE
+}
+
+extern "C" fn clone(_amx: *mut AMX, _source: *mut AMX, _data: *mut c_void) -> i32 {
+    code(AmxError::General)
+}
+
+extern "C" fn exec(amx: *mut AMX, retval: *mut i32, index: i32) -> i32 {
+    match state(amx) {
+        Some(vm) => unsafe { interp::exec(vm, retval, index) },
+        None => code(AmxError::InvalidState),
+    }
+}
+
+extern "C" fn find_native(amx: *mut AMX, name: *const c_char, index: *mut i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::NotFound);
+    };
+    let layout = unsafe { (*vm).layout };
+    let found = unsafe { find(vm, layout.natives, layout.num_natives(), name) };
+    write_index(index, found)
+}
+
+extern "C" fn find_public(amx: *mut AMX, name: *const c_char, index: *mut i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::NotFound);
+    };
+    let layout = unsafe { (*vm).layout };
+    let found = unsafe { find(vm, layout.publics, layout.num_publics(), name) };
+    write_index(index, found)
+}
+
+/// `amx.c` stores `INT_MAX` in the index when the lookup fails.
+fn write_index(index: *mut i32, found: Option<i32>) -> i32 {
+    if !index.is_null() {
+        unsafe { *index = found.unwrap_or(i32::MAX) };
+    }
+    if found.is_some() {
+        NONE
+    } else {
+        code(AmxError::NotFound)
+    }
+}
+
+extern "C" fn find_pubvar(amx: *mut AMX, name: *const c_char, amx_addr: *mut i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::NotFound);
+    };
+    let layout = unsafe { (*vm).layout };
+    let Some(index) = (unsafe { find(vm, layout.pubvars, layout.num_pubvars(), name) }) else {
+        return code(AmxError::NotFound);
+    };
+    if !amx_addr.is_null() {
+        unsafe { *amx_addr = read_u32(vm, entry(vm, layout.pubvars, index)).cast_signed() };
+    }
+    NONE
+}
+
+extern "C" fn find_tag_id(amx: *mut AMX, tag_id: i32, tagname: *mut c_char) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::NotFound);
+    };
+    let layout = unsafe { (*vm).layout };
+    for index in 0..layout.num_tags() {
+        unsafe {
+            if read_u32(vm, entry(vm, layout.tags, index)).cast_signed() == tag_id {
+                copy_name(tagname, &entry_name(vm, layout.tags, index));
+                return NONE;
+            }
+        }
+    }
+    code(AmxError::NotFound)
+}
+
+extern "C" fn flags(amx: *mut AMX, flags: *mut u16) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::InvalidState);
+    };
+    if !flags.is_null() {
+        // The public flags fit the low 16 bits (`AMX::flags` is `int` in C).
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        unsafe {
+            *flags = amx_get!(vm, flags) as u16;
+        }
+    }
+    NONE
+}
+
+extern "C" fn get_addr(amx: *mut AMX, amx_addr: i32, phys_addr: *mut *mut i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::MemoryAccess);
+    };
+    unsafe {
+        let hea = amx_get!(vm, hea);
+        let stk = amx_get!(vm, stk);
+        let stp = amx_get!(vm, stp);
+        if (amx_addr >= hea && amx_addr < stk) || amx_addr < 0 || amx_addr >= stp {
+            return code(AmxError::MemoryAccess);
+        }
+        if !phys_addr.is_null() {
+            *phys_addr = data(vm).add(amx_addr as usize).cast::<i32>();
+        }
+    }
+    NONE
+}
+
+extern "C" fn get_native(amx: *mut AMX, index: i32, funcname: *mut c_char) -> i32 {
+    get_entry_name(amx, index, funcname, |l| (l.natives, l.num_natives()))
+}
+
+extern "C" fn get_public(amx: *mut AMX, index: i32, funcname: *mut c_char) -> i32 {
+    get_entry_name(amx, index, funcname, |l| (l.publics, l.num_publics()))
+}
+
+fn get_entry_name(
+    amx: *mut AMX,
+    index: i32,
+    name: *mut c_char,
+    table: impl Fn(&super::Layout) -> (i32, i32),
+) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::Index);
+    };
+    let (offset, count) = table(unsafe { &(*vm).layout });
+    if index < 0 || index >= count {
+        return code(AmxError::Index);
+    }
+    unsafe { copy_name(name, &entry_name(vm, offset, index)) };
+    NONE
+}
+
+extern "C" fn get_pubvar(
+    amx: *mut AMX,
+    index: i32,
+    varname: *mut c_char,
+    amx_addr: *mut i32,
+) -> i32 {
+    let rc = get_entry_name(amx, index, varname, |l| (l.pubvars, l.num_pubvars()));
+    if rc == NONE && !amx_addr.is_null() {
+        let vm = amx.cast::<VmState>();
+        unsafe {
+            let layout = (*vm).layout;
+            *amx_addr = read_u32(vm, entry(vm, layout.pubvars, index)).cast_signed();
+        }
+    }
+    rc
+}
+
+extern "C" fn get_tag(amx: *mut AMX, index: i32, tagname: *mut c_char, tag_id: *mut i32) -> i32 {
+    let rc = get_entry_name(amx, index, tagname, |l| (l.tags, l.num_tags()));
+    if rc == NONE && !tag_id.is_null() {
+        let vm = amx.cast::<VmState>();
+        unsafe {
+            let layout = (*vm).layout;
+            *tag_id = read_u32(vm, entry(vm, layout.tags, index)).cast_signed();
+        }
+    }
+    rc
+}
+
+/// Bytes of the string at `source`, packed or unpacked, without the NUL.
+/// Unpacked cells contribute their low byte.
+pub(super) unsafe fn cell_string_bytes(source: *const i32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    unsafe {
+        if (*source).cast_unsigned() > crate::cell::string::MAX_UNPACKED.cast_unsigned() {
+            let mut cell = source;
+            'cells: loop {
+                for byte in (*cell).to_be_bytes() {
+                    if byte == 0 {
+                        break 'cells;
+                    }
+                    bytes.push(byte);
+                }
+                cell = cell.add(1);
+            }
+        } else {
+            let mut cell = source;
+            while *cell != 0 {
+                bytes.push((*cell).to_le_bytes()[0]);
+                cell = cell.add(1);
+            }
+        }
+    }
+    bytes
+}
+
+extern "C" fn get_string(dest: *mut u8, source: *const i32, _use_wchar: i32, size: usize) -> i32 {
+    if dest.is_null() || source.is_null() || size == 0 {
+        return code(AmxError::Params);
+    }
+    let bytes = unsafe { cell_string_bytes(source) };
+    let len = bytes.len().min(size - 1);
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), dest, len);
+        dest.add(len).write(0);
+    }
+    NONE
+}
+
+/// `long` is 64-bit on x86_64 Linux but 32-bit on the i686/Windows servers.
+#[allow(clippy::useless_conversion)] // widening on 32-bit `long` targets only
+fn same_tag(stored: c_long, tag: i64) -> bool {
+    i64::from(stored) == tag
+}
+
+extern "C" fn get_user_data(amx: *mut AMX, tag: i64, ptr: *mut *mut c_void) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::UserData);
+    };
+    let tags = unsafe { amx_get!(vm, usertags) };
+    let data = unsafe { amx_get!(vm, userdata) };
+    match tags.iter().position(|t| same_tag(*t, tag)) {
+        Some(slot) => {
+            if !ptr.is_null() {
+                unsafe { *ptr = data[slot] };
+            }
+            NONE
+        }
+        None => code(AmxError::UserData),
+    }
+}
+
+extern "C" fn init(_amx: *mut AMX, _program: *mut c_void) -> i32 {
+    code(AmxError::Init)
+}
+
+extern "C" fn init_jit(_amx: *mut AMX, _reloc: *mut c_void, _code: *mut c_void) -> i32 {
+    code(AmxError::InitJit)
+}
+
+extern "C" fn mem_info(
+    amx: *mut AMX,
+    codesize: *mut i32,
+    datasize: *mut i32,
+    stackheap: *mut i32,
+) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::InvalidState);
+    };
+    let layout = unsafe { (*vm).layout };
+    for (out, value) in [
+        (codesize, layout.dat - layout.cod),
+        (datasize, layout.hea - layout.dat),
+        (stackheap, layout.stp - layout.hea),
+    ] {
+        if !out.is_null() {
+            unsafe { *out = value };
+        }
+    }
+    NONE
+}
+
+extern "C" fn name_length(amx: *mut AMX, length: *mut i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::InvalidState);
+    };
+    let value = unsafe {
+        let layout = (*vm).layout;
+        if layout.defsize == super::image::DEFSIZE_NAMETABLE {
+            i32::from(
+                base(vm)
+                    .add(layout.nametable as usize)
+                    .cast::<u16>()
+                    .read_unaligned(),
+            )
+        } else {
+            INLINE_NAME_MAX as i32
+        }
+    };
+    if !length.is_null() {
+        unsafe { *length = value };
+    }
+    NONE
+}
+
+extern "C" fn native_info(name: *const c_char, func: AmxNative) -> *mut AMX_NATIVE_INFO {
+    // `amx.c` returns a pointer to a static; a leaked box keeps every returned
+    // entry valid instead, which is harmless in tests.
+    Box::into_raw(Box::new(AMX_NATIVE_INFO { name, func }))
+}
+
+fn count(amx: *mut AMX, number: *mut i32, f: impl Fn(&super::Layout) -> i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::InvalidState);
+    };
+    if !number.is_null() {
+        unsafe { *number = f(&(*vm).layout) };
+    }
+    NONE
+}
+
+extern "C" fn num_natives(amx: *mut AMX, number: *mut i32) -> i32 {
+    count(amx, number, super::Layout::num_natives)
+}
+
+extern "C" fn num_publics(amx: *mut AMX, number: *mut i32) -> i32 {
+    count(amx, number, super::Layout::num_publics)
+}
+
+extern "C" fn num_pubvars(amx: *mut AMX, number: *mut i32) -> i32 {
+    count(amx, number, super::Layout::num_pubvars)
+}
+
+extern "C" fn num_tags(amx: *mut AMX, number: *mut i32) -> i32 {
+    count(amx, number, super::Layout::num_tags)
+}
+
+extern "C" fn push(amx: *mut AMX, value: i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::StackError);
+    };
+    unsafe {
+        let stk = amx_get!(vm, stk).wrapping_sub(4);
+        if i64::from(stk) < i64::from(amx_get!(vm, hea)) + i64::from(STKMARGIN) {
+            return code(AmxError::StackError);
+        }
+        data(vm).add(stk as usize).cast::<i32>().write(value);
+        amx_set!(vm, stk, stk);
+        amx_set!(vm, paramcount, amx_get!(vm, paramcount) + 1);
+    }
+    NONE
+}
+
+extern "C" fn push_array(
+    amx: *mut AMX,
+    amx_addr: *mut i32,
+    phys_addr: *mut *mut i32,
+    array: *const i32,
+    numcells: i32,
+) -> i32 {
+    let mut addr = 0;
+    let mut phys: *mut i32 = std::ptr::null_mut();
+    let mut rc = allot(amx, numcells, &raw mut addr, &raw mut phys);
+    if rc == NONE {
+        if !array.is_null() {
+            unsafe { std::ptr::copy_nonoverlapping(array, phys, numcells as usize) };
+        }
+        rc = push(amx, addr);
+    }
+    write_push_result(amx_addr, phys_addr, addr, phys);
+    rc
+}
+
+extern "C" fn push_string(
+    amx: *mut AMX,
+    amx_addr: *mut i32,
+    phys_addr: *mut *mut i32,
+    string: *const c_char,
+    pack: i32,
+    use_wchar: i32,
+) -> i32 {
+    if string.is_null() {
+        return code(AmxError::Params);
+    }
+    let len = unsafe { CStr::from_ptr(string) }.to_bytes().len();
+    let cells = if pack != 0 { len / 4 + 1 } else { len + 1 };
+    let Ok(cells) = i32::try_from(cells) else {
+        return code(AmxError::Memory);
+    };
+    let mut addr = 0;
+    let mut phys: *mut i32 = std::ptr::null_mut();
+    let mut rc = allot(amx, cells, &raw mut addr, &raw mut phys);
+    if rc == NONE {
+        set_string(phys, string, pack, use_wchar, cells as usize);
+        rc = push(amx, addr);
+    }
+    write_push_result(amx_addr, phys_addr, addr, phys);
+    rc
+}
+
+fn write_push_result(amx_addr: *mut i32, phys_addr: *mut *mut i32, addr: i32, phys: *mut i32) {
+    unsafe {
+        if !amx_addr.is_null() {
+            *amx_addr = addr;
+        }
+        if !phys_addr.is_null() {
+            *phys_addr = phys;
+        }
+    }
+}
+
+extern "C" fn raise_error(amx: *mut AMX, error: i32) -> i32 {
+    if let Some(vm) = state(amx) {
+        unsafe { amx_set!(vm, error, error) };
+    }
+    NONE
+}
+
+extern "C" fn register(amx: *mut AMX, list: *const AMX_NATIVE_INFO, number: i32) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::NotFound);
+    };
+    let layout = unsafe { (*vm).layout };
+    let mut rc = NONE;
+    for index in 0..layout.num_natives() {
+        let slot = index as usize;
+        if unsafe { (&(*vm).natives)[slot].is_some() } {
+            continue;
+        }
+        let name = unsafe { entry_name(vm, layout.natives, index) };
+        match unsafe { find_in_list(list, number, &name) } {
+            Some(func) => unsafe {
+                (&mut (*vm).natives)[slot] = Some(func);
+                // The `.amx` slot is 32 bits wide: only a 32-bit host pointer
+                // fits (see the module docs). 64-bit hosts use the side table alone.
+                #[cfg(target_pointer_width = "32")]
+                base(vm)
+                    .add(entry(vm, layout.natives, index))
+                    .cast::<u32>()
+                    .write_unaligned(func as usize as u32);
+            },
+            None => rc = code(AmxError::NotFound),
+        }
+    }
+    if rc == NONE {
+        unsafe {
+            amx_set!(
+                vm,
+                flags,
+                amx_get!(vm, flags) | i32::from(AmxFlags::NTVREG.bits())
+            )
+        };
+    }
+    rc
+}
+
+/// `findfunction` of `amx.c`: scans `number` entries, or up to a NULL name
+/// when `number` is -1.
+unsafe fn find_in_list(
+    list: *const AMX_NATIVE_INFO,
+    number: i32,
+    name: &[u8],
+) -> Option<AmxNative> {
+    if list.is_null() {
+        return None;
+    }
+    let mut i = 0;
+    loop {
+        if number != -1 && i >= number {
+            return None;
+        }
+        let info = unsafe { list.add(i as usize).read_unaligned() };
+        if info.name.is_null() {
+            return None;
+        }
+        if unsafe { CStr::from_ptr(info.name) }.to_bytes() == name {
+            return Some(info.func);
+        }
+        i += 1;
+    }
+}
+
+extern "C" fn release(amx: *mut AMX, amx_addr: i32) -> i32 {
+    if let Some(vm) = state(amx) {
+        unsafe {
+            if amx_get!(vm, hea) > amx_addr && amx_addr >= amx_get!(vm, hlw) {
+                amx_set!(vm, hea, amx_addr);
+            }
+        }
+    }
+    NONE
+}
+
+extern "C" fn set_callback(amx: *mut AMX, callback: AmxCallback) -> i32 {
+    if let Some(vm) = state(amx) {
+        unsafe { amx_set!(vm, callback, callback) };
+    }
+    NONE
+}
+
+extern "C" fn set_debug_hook(amx: *mut AMX, debug: AmxDebug) -> i32 {
+    if let Some(vm) = state(amx) {
+        unsafe { amx_set!(vm, debug, debug) };
+    }
+    NONE
+}
+
+extern "C" fn set_string(
+    dest: *mut i32,
+    source: *const c_char,
+    pack: i32,
+    _use_wchar: i32,
+    size: usize,
+) -> i32 {
+    if dest.is_null() || source.is_null() || size == 0 {
+        return code(AmxError::Params);
+    }
+    let bytes = unsafe { CStr::from_ptr(source) }.to_bytes();
+    unsafe {
+        if pack != 0 {
+            let len = bytes.len().min(size * 4 - 1);
+            for cell in 0..=len / 4 {
+                let mut packed = [0u8; 4];
+                for (i, slot) in packed.iter_mut().enumerate() {
+                    if let Some(&byte) = bytes.get(cell * 4 + i).filter(|_| cell * 4 + i < len) {
+                        *slot = byte;
+                    }
+                }
+                dest.add(cell).write(i32::from_be_bytes(packed));
+            }
+        } else {
+            let len = bytes.len().min(size - 1);
+            for (i, &byte) in bytes[..len].iter().enumerate() {
+                dest.add(i).write(i32::from(byte));
+            }
+            dest.add(len).write(0);
+        }
+    }
+    NONE
+}
+
+extern "C" fn set_user_data(amx: *mut AMX, tag: i64, ptr: *mut c_void) -> i32 {
+    let Some(vm) = state(amx) else {
+        return code(AmxError::UserData);
+    };
+    let mut tags = unsafe { amx_get!(vm, usertags) };
+    let mut data = unsafe { amx_get!(vm, userdata) };
+    let slot = tags
+        .iter()
+        .position(|t| same_tag(*t, tag))
+        .or_else(|| tags.iter().position(|t| *t == 0));
+    let Some(slot) = slot else {
+        return code(AmxError::UserData);
+    };
+    // `long` is 32-bit on the 32-bit servers; tags are four-character codes.
+    #[allow(clippy::cast_possible_truncation, clippy::useless_conversion)]
+    {
+        tags[slot] = tag as c_long;
+    }
+    data[slot] = ptr;
+    unsafe {
+        amx_set!(vm, usertags, tags);
+        amx_set!(vm, userdata, data);
+    }
+    NONE
+}
+
+extern "C" fn str_len(cstring: *const i32, length: *mut i32) -> i32 {
+    if cstring.is_null() || length.is_null() {
+        return code(AmxError::Params);
+    }
+    let len = unsafe { cell_string_bytes(cstring) }.len();
+    unsafe { *length = i32::try_from(len).unwrap_or(i32::MAX) };
+    NONE
+}
+
+extern "C" fn utf8_check(string: *const c_char, length: *mut i32) -> i32 {
+    if string.is_null() {
+        return code(AmxError::Params);
+    }
+    match std::str::from_utf8(unsafe { CStr::from_ptr(string) }.to_bytes()) {
+        Ok(s) => {
+            if !length.is_null() {
+                unsafe { *length = i32::try_from(s.chars().count()).unwrap_or(i32::MAX) };
+            }
+            NONE
+        }
+        Err(_) => code(AmxError::Params),
+    }
+}
+
+extern "C" fn utf8_get(string: *const c_char, endptr: *mut *const c_char, value: *mut i32) -> i32 {
+    if string.is_null() {
+        return code(AmxError::Params);
+    }
+    let bytes = unsafe { CStr::from_ptr(string) }.to_bytes();
+    let width = match bytes.first() {
+        None => 0,
+        Some(b) if *b < 0x80 => 1,
+        Some(b) if *b >> 5 == 0b110 => 2,
+        Some(b) if *b >> 4 == 0b1110 => 3,
+        Some(b) if *b >> 3 == 0b11110 => 4,
+        Some(_) => return code(AmxError::Params),
+    };
+    let decoded = match bytes.get(..width).map(std::str::from_utf8) {
+        Some(Ok(s)) => s.chars().next().map_or(0, |c| c as i32),
+        _ => return code(AmxError::Params),
+    };
+    unsafe {
+        if !value.is_null() {
+            *value = decoded;
+        }
+        if !endptr.is_null() {
+            *endptr = string.add(width);
+        }
+    }
+    NONE
+}
+
+extern "C" fn utf8_len(cstr: *const i32, length: *mut i32) -> i32 {
+    if cstr.is_null() || length.is_null() {
+        return code(AmxError::Params);
+    }
+    let len: usize = unsafe {
+        if (*cstr).cast_unsigned() > crate::cell::string::MAX_UNPACKED.cast_unsigned() {
+            cell_string_bytes(cstr).len()
+        } else {
+            let mut total = 0;
+            let mut cell = cstr;
+            while *cell != 0 {
+                total += match (*cell).cast_unsigned() {
+                    0..0x80 => 1,
+                    0x80..0x800 => 2,
+                    0x800..0x1_0000 => 3,
+                    _ => 4,
+                };
+                cell = cell.add(1);
+            }
+            total
+        }
+    };
+    unsafe { *length = i32::try_from(len).unwrap_or(i32::MAX) };
+    NONE
+}
+
+extern "C" fn utf8_put(
+    string: *mut c_char,
+    endptr: *mut *mut c_char,
+    maxchars: i32,
+    value: i32,
+) -> i32 {
+    if string.is_null() {
+        return code(AmxError::Params);
+    }
+    let Some(c) = char::from_u32(value.cast_unsigned()) else {
+        return code(AmxError::Domain);
+    };
+    let mut buf = [0u8; 4];
+    let encoded = c.encode_utf8(&mut buf).as_bytes();
+    if usize::try_from(maxchars).map_or(true, |max| encoded.len() > max) {
+        return code(AmxError::Params);
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(encoded.as_ptr().cast::<c_char>(), string, encoded.len());
+        if !endptr.is_null() {
+            *endptr = string.add(encoded.len());
+        }
+    }
+    NONE
+}
+
+// ---------------------------------------------------------------------------
+// Tables
+// ---------------------------------------------------------------------------
+
+/// Number of entries in `amx_Exports` (`Exports::UTF8Put` is the last).
+const EXPORTS_LEN: usize = Exports::UTF8Put as usize + 1;
+
+/// Address of the interpreter's `amx_Exports` table, built on first use.
+pub(super) fn table() -> usize {
+    static TABLE: OnceLock<[usize; EXPORTS_LEN]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        let mut t = [0usize; EXPORTS_LEN];
+        // Each cast through the `raw::functions` alias type-checks the signature.
+        t[Exports::Align16 as usize] = align16 as functions::Align16 as usize;
+        t[Exports::Align32 as usize] = align32 as functions::Align32 as usize;
+        t[Exports::Align64 as usize] = align64 as extern "C" fn(*mut u64) -> *mut u64 as usize;
+        t[Exports::Allot as usize] = allot as functions::Allot as usize;
+        t[Exports::Callback as usize] = callback as functions::Callback as usize;
+        t[Exports::Cleanup as usize] = cleanup as functions::Cleanup as usize;
+        t[Exports::Clone as usize] = clone as functions::Clone as usize;
+        t[Exports::Exec as usize] = exec as functions::Exec as usize;
+        t[Exports::FindNative as usize] = find_native as functions::FindNative as usize;
+        t[Exports::FindPublic as usize] = find_public as functions::FindPublic as usize;
+        t[Exports::FindPubVar as usize] = find_pubvar as functions::FindPubVar as usize;
+        t[Exports::FindTagId as usize] = find_tag_id as functions::FindTagId as usize;
+        t[Exports::Flags as usize] = flags as functions::Flags as usize;
+        t[Exports::GetAddr as usize] = get_addr as functions::GetAddr as usize;
+        t[Exports::GetNative as usize] = get_native as functions::GetNative as usize;
+        t[Exports::GetPublic as usize] = get_public as functions::GetPublic as usize;
+        t[Exports::GetPubVar as usize] = get_pubvar as functions::GetPubVar as usize;
+        t[Exports::GetString as usize] = get_string as functions::GetString as usize;
+        t[Exports::GetTag as usize] = get_tag as functions::GetTag as usize;
+        t[Exports::GetUserData as usize] = get_user_data as functions::GetUserData as usize;
+        t[Exports::Init as usize] = init as functions::Init as usize;
+        t[Exports::InitJIT as usize] = init_jit as functions::InitJIT as usize;
+        t[Exports::MemInfo as usize] = mem_info as functions::MemInfo as usize;
+        t[Exports::NameLength as usize] = name_length as functions::NameLength as usize;
+        t[Exports::NativeInfo as usize] = native_info as functions::NativeInfo as usize;
+        t[Exports::NumNatives as usize] = num_natives as functions::NumNatives as usize;
+        t[Exports::NumPublics as usize] = num_publics as functions::NumPublics as usize;
+        t[Exports::NumPubVars as usize] = num_pubvars as functions::NumPubVars as usize;
+        t[Exports::NumTags as usize] = num_tags as functions::NumTags as usize;
+        t[Exports::Push as usize] = push as functions::Push as usize;
+        t[Exports::PushArray as usize] = push_array as functions::PushArray as usize;
+        t[Exports::PushString as usize] = push_string as functions::PushString as usize;
+        t[Exports::RaiseError as usize] = raise_error as functions::RaiseError as usize;
+        t[Exports::Register as usize] = register as functions::Register as usize;
+        t[Exports::Release as usize] = release as functions::Release as usize;
+        t[Exports::SetCallback as usize] = set_callback as functions::SetCallback as usize;
+        t[Exports::SetDebugHook as usize] = set_debug_hook as functions::SetDebugHook as usize;
+        t[Exports::SetString as usize] = set_string as functions::SetString as usize;
+        t[Exports::SetUserData as usize] = set_user_data as functions::SetUserData as usize;
+        t[Exports::StrLen as usize] = str_len as functions::StrLen as usize;
+        t[Exports::UTF8Check as usize] = utf8_check as functions::UTF8Check as usize;
+        t[Exports::UTF8Get as usize] = utf8_get as functions::UTF8Get as usize;
+        t[Exports::UTF8Len as usize] = utf8_len as functions::UTF8Len as usize;
+        t[Exports::UTF8Put as usize] = utf8_put as functions::UTF8Put as usize;
+        // The `.amx` natives slots cannot hold a 64-bit host pointer: `Amx`
+        // reads the side table `register` fills instead.
+        #[cfg(target_pointer_width = "64")]
+        crate::amx::set_bound_native_reader(t[Exports::Register as usize], super::bound_native);
+        t
+    });
+    table.as_ptr() as usize
+}
+
+/// `logprintf` of the `ppData` table: records the line for `take_log`.
+extern "C" fn logprintf(message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+    let line = unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned();
+    LOG.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(line);
+}
+
+/// `CallPublicFs` of the `ppData` table: runs the argument-less public `name`
+/// in every script added with `add_filterscript` and returns what the last
+/// one defining it returned.
+extern "C" fn call_public_fs(name: *const c_char) -> i32 {
+    let filterscripts = FILTERSCRIPTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    let mut retval = 0;
+    for amx in filterscripts {
+        if let Some(value) = call_public(amx as *mut AMX, name) {
+            retval = value;
+        }
+    }
+    retval
+}
+
+/// `CallPublicGm` of the `ppData` table: runs the argument-less public `name`
+/// in the script set by `set_gamemode`.
+extern "C" fn call_public_gm(name: *const c_char) -> i32 {
+    call_public(GAMEMODE.load(Ordering::Acquire), name).unwrap_or(0)
+}
+
+/// Runs the argument-less public `name` in `amx`, `None` when it has none.
+/// Looked up and run through the `amx_Exports` entries, as the server does,
+/// so detours on them see it.
+fn call_public(amx: *mut AMX, name: *const c_char) -> Option<i32> {
+    if amx.is_null() {
+        return None;
+    }
+    let mut index = 0;
+    if FindPublic::from_table(table())(amx, name, &raw mut index) != NONE {
+        return None;
+    }
+    let mut retval = 0;
+    Exec::from_table(table())(amx, &raw mut retval, index);
+    Some(retval)
+}
+
+/// Address of the table `IPawnComponent::getAmxFunctions()` returns on open.mp:
+/// `amx_Exports` followed by the open.mp-only entries, which stay null.
+#[cfg(all(
+    not(feature = "samp-only"),
+    not(all(windows, target_env = "msvc", target_pointer_width = "64"))
+))]
+pub(super) fn omp_table() -> usize {
+    use crate::omp::server::NUM_AMX_FUNCS;
+    static TABLE: OnceLock<[usize; NUM_AMX_FUNCS]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        let mut t = [0usize; NUM_AMX_FUNCS];
+        // SAFETY: `table()` points at a live `[usize; EXPORTS_LEN]` static.
+        let exports = unsafe { std::slice::from_raw_parts(table() as *const usize, EXPORTS_LEN) };
+        t[..EXPORTS_LEN].copy_from_slice(exports);
+        t
+    });
+    table.as_ptr() as usize
+}
+
+/// Address of the `ppData` table passed to `Load()`.
+pub(super) fn server_data() -> *const usize {
+    use crate::consts::ServerData;
+    static DATA: OnceLock<[usize; ServerData::CallPublicGm as usize + 1]> = OnceLock::new();
+    DATA.get_or_init(|| {
+        let mut data = [0usize; ServerData::CallPublicGm as usize + 1];
+        data[ServerData::Logprintf as usize] = logprintf as functions::Logprintf as usize;
+        data[ServerData::AmxExports as usize] = table();
+        data[ServerData::CallPublicFs as usize] = call_public_fs as functions::CallPublic as usize;
+        data[ServerData::CallPublicGm as usize] = call_public_gm as functions::CallPublic as usize;
+        data
+    })
+    .as_ptr()
+}
@@ -0,0 +1,543 @@
+//! `.amx` image handling: header parsing, compact-encoding expansion and the
+//! [`ImageBuilder`] used to hand-assemble scripts in tests.
+
+use super::opcodes as op;
+use crate::consts::AmxFlags;
+use crate::error::{AmxError, AmxResult};
+use crate::raw::types::{AMX_FUNCSTUB, AMX_HEADER};
+
+/// `AMX_MAGIC` for 32-bit cells.
+const MAGIC: u16 = 0xF1E0;
+/// Newest file format understood (`CUR_FILE_VERSION` in `amx.c`).
+const CUR_FILE_VERSION: u8 = 8;
+/// Oldest file format understood (`MIN_FILE_VERSION` in `amx.c`).
+const MIN_FILE_VERSION: u8 = 6;
+/// Size of a table entry when names live in the name table (`ANX_FUNCSTUBNT`).
+pub(super) const DEFSIZE_NAMETABLE: i32 = 8;
+/// Size of a table entry with the name inline (`AMX_FUNCSTUB`).
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub(super) const DEFSIZE_INLINE: i32 = std::mem::size_of::<AMX_FUNCSTUB>() as i32;
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+const HEADER_SIZE: i32 = std::mem::size_of::<AMX_HEADER>() as i32;
+
+/// Header fields the VM needs, decoded once at load time. All offsets are
+/// relative to the start of the image (`AMX::base`).
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Layout {
+    pub flags: u16,
+    pub defsize: i32,
+    pub cod: i32,
+    pub dat: i32,
+    pub hea: i32,
+    pub stp: i32,
+    pub cip: i32,
+    pub publics: i32,
+    pub natives: i32,
+    pub libraries: i32,
+    pub pubvars: i32,
+    pub tags: i32,
+    pub nametable: i32,
+}
+
+impl Layout {
+    pub fn num_publics(&self) -> i32 {
+        (self.natives - self.publics) / self.defsize
+    }
+
+    pub fn num_natives(&self) -> i32 {
+        (self.libraries - self.natives) / self.defsize
+    }
+
+    pub fn num_pubvars(&self) -> i32 {
+        (self.tags - self.pubvars) / self.defsize
+    }
+
+    pub fn num_tags(&self) -> i32 {
+        (self.nametable - self.tags) / self.defsize
+    }
+
+    /// Bytes of the code segment (`[cod, dat)`).
+    pub fn code_size(&self) -> i32 {
+        self.dat - self.cod
+    }
+
+    /// Bytes addressable from the data segment: data + heap + stack.
+    pub fn data_size(&self) -> i32 {
+        self.stp - self.dat
+    }
+}
+
+fn read_i32(bytes: &[u8], off: usize) -> i32 {
+    i32::from_le_bytes([bytes[off], bytes[off + 1], bytes[off + 2], bytes[off + 3]])
+}
+
+fn read_u16(bytes: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([bytes[off], bytes[off + 1]])
+}
+
+/// Validates the header of a raw `.amx` file, mirroring the checks of `amx_Init`.
+pub(super) fn parse_header(bytes: &[u8]) -> AmxResult<Layout> {
+    if bytes.len() < HEADER_SIZE as usize {
+        return Err(AmxError::Format);
+    }
+    if read_u16(bytes, 4) != MAGIC {
+        return Err(AmxError::Format);
+    }
+    if bytes[6] < MIN_FILE_VERSION || bytes[7] > CUR_FILE_VERSION {
+        return Err(AmxError::Version);
+    }
+
+    let layout = Layout {
+        flags: read_u16(bytes, 8),
+        defsize: i32::from(read_u16(bytes, 10).cast_signed()),
+        cod: read_i32(bytes, 12),
+        dat: read_i32(bytes, 16),
+        hea: read_i32(bytes, 20),
+        stp: read_i32(bytes, 24),
+        cip: read_i32(bytes, 28),
+        publics: read_i32(bytes, 32),
+        natives: read_i32(bytes, 36),
+        libraries: read_i32(bytes, 40),
+        pubvars: read_i32(bytes, 44),
+        tags: read_i32(bytes, 48),
+        nametable: read_i32(bytes, 52),
+    };
+
+    if layout.defsize != DEFSIZE_NAMETABLE && layout.defsize != DEFSIZE_INLINE {
+        return Err(AmxError::Format);
+    }
+    let segments_ordered = HEADER_SIZE <= layout.publics
+        && layout.publics <= layout.natives
+        && layout.natives <= layout.libraries
+        && layout.libraries <= layout.pubvars
+        && layout.pubvars <= layout.tags
+        && layout.tags <= layout.nametable
+        && layout.nametable <= layout.cod
+        && layout.cod <= layout.dat
+        && layout.dat <= layout.hea
+        && layout.hea < layout.stp;
+    // The VM hands out `*mut i32` into the image — every segment must be cell-aligned.
+    let aligned = [layout.cod, layout.dat, layout.hea, layout.stp]
+        .iter()
+        .all(|off| off % 4 == 0);
+    if !segments_ordered || !aligned {
+        return Err(AmxError::Format);
+    }
+    Ok(layout)
+}
+
+/// Loads `bytes` into a zeroed memory block of `stp` bytes, expanding the
+/// compact encoding when the header advertises it. Returns the block as cells
+/// so every address the VM hands out is cell-aligned.
+pub(super) fn load_image(bytes: &[u8], layout: &mut Layout) -> AmxResult<Vec<i32>> {
+    let size = usize::try_from(read_i32(bytes, 0)).map_err(|_| AmxError::Format)?;
+    let cod = layout.cod as usize;
+    let hea = layout.hea as usize;
+    if size > bytes.len() || size < cod {
+        return Err(AmxError::Format);
+    }
+
+    let mut image = vec![0u8; layout.stp as usize];
+    if layout.flags & AmxFlags::COMPACT.bits() != 0 {
+        let expanded = expand(&bytes[cod..size]).ok_or(AmxError::Format)?;
+        if expanded.len() != hea - cod {
+            return Err(AmxError::Format);
+        }
+        image[..cod].copy_from_slice(&bytes[..cod]);
+        image[cod..hea].copy_from_slice(&expanded);
+        // Same as `amx_Init`: once expanded, the image is no longer compact.
+        layout.flags &= !AmxFlags::COMPACT.bits();
+        image[8..10].copy_from_slice(&layout.flags.to_le_bytes());
+        image[0..4].copy_from_slice(&layout.hea.to_le_bytes());
+    } else {
+        let end = size.min(hea);
+        image[..end].copy_from_slice(&bytes[..end]);
+    }
+
+    Ok(image
+        .chunks_exact(4)
+        .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect())
+}
+
+/// Decodes the compact (variable-length) encoding of the code + data
+/// segments. Each cell is stored big-end first in 7-bit groups; bit 7 marks a
+/// continuation and bit 6 of the first byte carries the sign.
+///
+/// `amx.c` expands in place from the end backwards; decoding forward into a
+/// fresh buffer yields the same cells.
+pub(super) fn expand(compact: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(compact.len() * 2);
+    let mut bytes = compact.iter().copied();
+    while let Some(first) = bytes.next() {
+        let mut value = u32::from(first & 0x7f);
+        let mut bits = 7;
+        let mut byte = first;
+        while byte & 0x80 != 0 {
+            byte = bytes.next()?;
+            value = (value << 7) | u32::from(byte & 0x7f);
+            bits += 7;
+        }
+        if bits > 35 {
+            return None;
+        }
+        if first & 0x40 != 0 && bits < 32 {
+            value |= u32::MAX << bits;
+        }
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    Some(out)
+}
+
+/// Hand-assembles a valid (non-compact, file version 8) `.amx` image.
+///
+/// Code starts with `HALT 0` at offset 0 — the return address `amx_Exec`
+/// pushes — exactly like `pawncc` output, so a public's final `RETN` ends the
+/// run. Addresses passed to `CALL`/`JUMP`/`SWITCH` are code offsets (see
+/// [`here`](Self::here)); data addresses returned by [`data`](Self::data) and
+/// friends are what `PUSH_C`/`LOAD_PRI`/… expect.
+///
+/// ```rust
+/// use samp_sdk::test_vm::{ImageBuilder, TestVm, opcodes as op};
+///
+/// let mut image = ImageBuilder::new();
+/// image.public("Add").code(&[
+///     op::PROC,
+///     op::LOAD_S_PRI, 12, // first argument
+///     op::LOAD_S_ALT, 16, // second argument
+///     op::ADD,
+///     op::RETN,
+/// ]);
+/// let vm = TestVm::from_bytes(&image.build()).unwrap();
+/// assert_eq!(vm.call("Add", &[2, 3]).unwrap(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImageBuilder {
+    code: Vec<i32>,
+    data: Vec<i32>,
+    publics: Vec<(String, i32)>,
+    natives: Vec<String>,
+    pubvars: Vec<(String, i32)>,
+    main: Option<i32>,
+    stack_cells: usize,
+}
+
+impl Default for ImageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageBuilder {
+    /// Empty script with 4096 cells of heap + stack (the `pawncc` default).
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            code: vec![op::HALT, 0],
+            data: Vec::new(),
+            publics: Vec::new(),
+            natives: Vec::new(),
+            pubvars: Vec::new(),
+            main: None,
+            stack_cells: 4096,
+        }
+    }
+
+    /// Code offset of the next emitted cell — the target to use in
+    /// `CALL`/`JUMP`/`JZER`/… when branching to it.
+    #[must_use]
+    pub fn here(&self) -> i32 {
+        cells_to_bytes(self.code.len())
+    }
+
+    /// Appends raw code cells (opcodes and their operands).
+    pub fn code(&mut self, cells: &[i32]) -> &mut Self {
+        self.code.extend_from_slice(cells);
+        self
+    }
+
+    /// Overwrites the code cell at byte `offset` — for back-patching forward jumps.
+    ///
+    /// # Panics
+    /// If `offset` is not a cell-aligned offset of already emitted code.
+    pub fn patch(&mut self, offset: i32, value: i32) -> &mut Self {
+        let index = usize::try_from(offset / 4).expect("negative code offset");
+        assert!(offset % 4 == 0, "unaligned code offset {offset}");
+        self.code[index] = value;
+        self
+    }
+
+    /// Declares a public whose entry point is the next emitted cell.
+    pub fn public(&mut self, name: &str) -> &mut Self {
+        let here = self.here();
+        self.publics.push((name.to_owned(), here));
+        self
+    }
+
+    /// Marks the next emitted cell as the `main()` entry point.
+    pub fn main(&mut self) -> &mut Self {
+        self.main = Some(self.here());
+        self
+    }
+
+    /// Declares a native and returns its index — the `SYSREQ_C` operand.
+    /// Declaring the same name twice returns the same index.
+    pub fn native(&mut self, name: &str) -> i32 {
+        let index = match self.natives.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.natives.push(name.to_owned());
+                self.natives.len() - 1
+            }
+        };
+        i32::try_from(index).expect("too many natives")
+    }
+
+    /// Appends cells to the data segment and returns their address.
+    pub fn data(&mut self, cells: &[i32]) -> i32 {
+        let addr = cells_to_bytes(self.data.len());
+        self.data.extend_from_slice(cells);
+        addr
+    }
+
+    /// Appends `count` zeroed cells (a global array) and returns their address.
+    pub fn zeroed(&mut self, count: usize) -> i32 {
+        let addr = cells_to_bytes(self.data.len());
+        self.data.resize(self.data.len() + count, 0);
+        addr
+    }
+
+    /// Appends an unpacked, NUL-terminated string (one byte per cell) and
+    /// returns its address.
+    pub fn string(&mut self, s: &str) -> i32 {
+        let mut cells: Vec<i32> = s.bytes().map(i32::from).collect();
+        cells.push(0);
+        self.data(&cells)
+    }
+
+    /// Declares a public variable initialized to `value` and returns its address.
+    pub fn pubvar(&mut self, name: &str, value: i32) -> i32 {
+        let addr = self.data(&[value]);
+        self.pubvars.push((name.to_owned(), addr));
+        addr
+    }
+
+    /// Sets the combined heap + stack size in cells.
+    pub fn stack_size(&mut self, cells: usize) -> &mut Self {
+        self.stack_cells = cells;
+        self
+    }
+
+    /// Serializes the image. Publics are sorted by name, as `pawncc` does.
+    ///
+    /// # Panics
+    /// If the image would exceed the 2 GiB addressable by a cell.
+    #[must_use]
+    pub fn build(&self) -> Vec<u8> {
+        let mut publics = self.publics.clone();
+        publics.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut names: Vec<u8> = Vec::new();
+        let max_name = publics
+            .iter()
+            .map(|(n, _)| n)
+            .chain(&self.natives)
+            .chain(self.pubvars.iter().map(|(n, _)| n))
+            .map(String::len)
+            .max()
+            .unwrap_or(0);
+
+        let defsize = DEFSIZE_NAMETABLE as usize;
+        let publics_off = HEADER_SIZE as usize;
+        let natives_off = publics_off + publics.len() * defsize;
+        let libraries_off = natives_off + self.natives.len() * defsize;
+        let pubvars_off = libraries_off;
+        let tags_off = pubvars_off + self.pubvars.len() * defsize;
+        let nametable_off = tags_off;
+
+        // Name table: u16 max name length, then the NUL-terminated names.
+        names.extend_from_slice(&u16::try_from(max_name).unwrap_or(u16::MAX).to_le_bytes());
+        let mut name_offsets = Vec::new();
+        for name in publics
+            .iter()
+            .map(|(n, _)| n)
+            .chain(&self.natives)
+            .chain(self.pubvars.iter().map(|(n, _)| n))
+        {
+            name_offsets.push(nametable_off + names.len());
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+        }
+
+        let cod = (nametable_off + names.len()).next_multiple_of(4);
+        let dat = cod + self.code.len() * 4;
+        let hea = dat + self.data.len() * 4;
+        let stp = hea + self.stack_cells * 4;
+        let to_i32 = |v: usize| i32::try_from(v).expect("image too large");
+
+        let mut out = Vec::with_capacity(hea);
+        out.extend_from_slice(&to_i32(hea).to_le_bytes()); // size
+        out.extend_from_slice(&MAGIC.to_le_bytes());
+        out.push(CUR_FILE_VERSION); // file_version
+        out.push(CUR_FILE_VERSION); // amx_version
+        out.extend_from_slice(&0i16.to_le_bytes()); // flags
+        out.extend_from_slice(&i16::try_from(defsize).unwrap_or(8).to_le_bytes());
+        for value in [
+            cod,
+            dat,
+            hea,
+            stp,
+            usize::MAX, // cip placeholder
+            publics_off,
+            natives_off,
+            libraries_off,
+            pubvars_off,
+            tags_off,
+            nametable_off,
+        ] {
+            let value = if value == usize::MAX {
+                self.main.unwrap_or(-1)
+            } else {
+                to_i32(value)
+            };
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut names_iter = name_offsets.into_iter();
+        let mut stub = |out: &mut Vec<u8>, address: i32| {
+            out.extend_from_slice(&address.to_le_bytes());
+            let name = names_iter.next().unwrap_or_default();
+            out.extend_from_slice(&to_i32(name).to_le_bytes());
+        };
+        for (_, address) in &publics {
+            stub(&mut out, *address);
+        }
+        for _ in &self.natives {
+            stub(&mut out, 0);
+        }
+        for (_, address) in &self.pubvars {
+            stub(&mut out, *address);
+        }
+
+        out.extend_from_slice(&names);
+        out.resize(cod, 0);
+        for cell in self.code.iter().chain(&self.data) {
+            out.extend_from_slice(&cell.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn cells_to_bytes(cells: usize) -> i32 {
+    i32::try_from(cells * 4).expect("image too large")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inverse of [`expand`], as `pawncc` writes it.
+    fn compress(cells: &[i32]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &cell in cells {
+            let mut groups = vec![(cell & 0x7f) as u8];
+            let mut value = cell >> 7;
+            // Keep emitting groups until the remaining value is pure sign
+            // extension and bit 6 of the top group agrees with the sign.
+            while !((value == 0 && groups[0] & 0x40 == 0) || (value == -1 && groups[0] & 0x40 != 0))
+            {
+                groups.insert(0, (value & 0x7f) as u8);
+                value >>= 7;
+            }
+            let last = groups.len() - 1;
+            for (i, group) in groups.iter().enumerate() {
+                out.push(if i == last { *group } else { group | 0x80 });
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn expand_round_trips_edge_values() {
+        let cells = [
+            0,
+            1,
+            -1,
+            63,
+            64,
+            -64,
+            -65,
+            0x7fff_ffff,
+            i32::MIN,
+            123_456,
+            -98_765,
+        ];
+        let expanded = expand(&compress(&cells)).unwrap();
+        let decoded: Vec<i32> = expanded
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        assert_eq!(decoded, cells);
+    }
+
+    #[test]
+    fn expand_rejects_truncated_sequence() {
+        assert!(expand(&[0x81]).is_none());
+    }
+
+    #[test]
+    fn built_header_parses() {
+        let mut builder = ImageBuilder::new();
+        builder.public("B").code(&[op::RETN]);
+        builder.public("A").code(&[op::RETN]);
+        builder.native("N");
+        builder.pubvar("V", 7);
+        let bytes = builder.build();
+        let layout = parse_header(&bytes).unwrap();
+        assert_eq!(layout.num_publics(), 2);
+        assert_eq!(layout.num_natives(), 1);
+        assert_eq!(layout.num_pubvars(), 1);
+        assert_eq!(layout.num_tags(), 0);
+        assert_eq!(layout.cip, -1);
+        assert_eq!(layout.code_size(), 4 * 4);
+    }
+
+    #[test]
+    fn compact_image_loads_like_plain() {
+        let mut builder = ImageBuilder::new();
+        builder.public("F").code(&[op::CONST_PRI, -5, op::RETN]);
+        builder.data(&[1, -2, 300]);
+        let plain = builder.build();
+
+        let mut plain_layout = parse_header(&plain).unwrap();
+        let cod = plain_layout.cod as usize;
+        let cells: Vec<i32> = plain[cod..]
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        let mut compact = plain[..cod].to_vec();
+        compact.extend(compress(&cells));
+        let size = i32::try_from(compact.len()).unwrap();
+        compact[0..4].copy_from_slice(&size.to_le_bytes());
+        compact[8..10].copy_from_slice(&AmxFlags::COMPACT.bits().to_le_bytes());
+
+        let mut compact_layout = parse_header(&compact).unwrap();
+        let from_compact = load_image(&compact, &mut compact_layout).unwrap();
+        let from_plain = load_image(&plain, &mut plain_layout).unwrap();
+        assert_eq!(from_compact[cod / 4..], from_plain[cod / 4..]);
+        assert_eq!(compact_layout.flags & AmxFlags::COMPACT.bits(), 0);
+    }
+
+    #[test]
+    fn rejects_bad_magic_and_version() {
+        let mut bytes = ImageBuilder::new().build();
+        bytes[4] = 0;
+        assert!(matches!(parse_header(&bytes), Err(AmxError::Format)));
+
+        let mut bytes = ImageBuilder::new().build();
+        bytes[7] = 9;
+        assert!(matches!(parse_header(&bytes), Err(AmxError::Version)));
+
+        assert!(matches!(parse_header(&[0; 8]), Err(AmxError::Format)));
+    }
+}
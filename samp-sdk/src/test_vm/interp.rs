@@ -0,0 +1,724 @@
+//! The bytecode interpreter behind `amx_Exec`.
+//!
+//! A straight port of the `switch` loop of `amx.c` (checked build, little
+//! endian, 32-bit cells). Registers live in a local [`Machine`] and are synced
+//! back to `AMX` before every native call and on exit, exactly where `amx.c`
+//! does it — natives see the same `amx->stk`/`amx->hea` they would on a server.
+//!
+//! Every memory access is bounds-checked against the image, so a buggy script
+//! ends with `AmxError::MemoryAccess` instead of touching host memory.
+
+use super::exports::{STKMARGIN, data};
+use super::opcodes as op;
+use super::{VmState, code};
+use crate::error::AmxError;
+use crate::raw::types::AMX;
+
+use std::cmp::Ordering;
+
+/// `amx_Exec` for the interpreter. Returns an `AMX_ERR_*` code.
+///
+/// # Safety
+/// `vm` must point to a live `VmState`.
+pub(super) unsafe fn exec(vm: *mut VmState, retval: *mut i32, index: i32) -> i32 {
+    let layout = unsafe { (*vm).layout };
+    let entry = match index {
+        -1 if layout.cip < 0 => return code(AmxError::Index),
+        -1 => layout.cip,
+        // `AMX_EXEC_CONT` resumes a sleeping script — `sleep` is not supported.
+        -2 => return code(AmxError::InvalidState),
+        i if i < 0 || i >= layout.num_publics() => return code(AmxError::Index),
+        i => unsafe {
+            let offset = (layout.publics + i * layout.defsize) as usize;
+            amx_get!(vm, base)
+                .add(offset)
+                .cast::<i32>()
+                .read_unaligned()
+        },
+    };
+
+    let mut machine = unsafe { Machine::new(vm, entry) };
+    let reset_hea = machine.hea;
+    let reset_stk = machine.stk + unsafe { amx_get!(vm, paramcount) } * 4;
+
+    let result = unsafe { machine.start() };
+
+    unsafe {
+        amx_set!(vm, stk, reset_stk);
+        amx_set!(vm, hea, reset_hea);
+        amx_set!(vm, frm, machine.frm);
+        amx_set!(vm, pri, machine.pri);
+        amx_set!(vm, alt, machine.alt);
+        amx_set!(vm, cip, machine.cip);
+    }
+
+    match result {
+        Ok(halt) => {
+            if !retval.is_null() {
+                unsafe { *retval = machine.pri };
+            }
+            halt
+        }
+        Err(err) => code(err),
+    }
+}
+
+type Step<T = ()> = Result<T, AmxError>;
+
+/// Registers plus the bounds every access is checked against. Addresses are
+/// data-relative (`amx->data`), `cip` is code-relative — as in `amx.c`.
+struct Machine {
+    vm: *mut VmState,
+    code: *const u8,
+    data: *mut u8,
+    code_size: i32,
+    data_size: i32,
+    cod: i32,
+    dat: i32,
+    hlw: i32,
+    stp: i32,
+    pri: i32,
+    alt: i32,
+    frm: i32,
+    stk: i32,
+    hea: i32,
+    cip: i32,
+}
+
+impl Machine {
+    unsafe fn new(vm: *mut VmState, cip: i32) -> Machine {
+        unsafe {
+            let layout = (*vm).layout;
+            Machine {
+                vm,
+                code: amx_get!(vm, base).add(layout.cod as usize),
+                data: data(vm),
+                code_size: layout.code_size(),
+                data_size: layout.data_size(),
+                cod: layout.cod,
+                dat: layout.dat,
+                hlw: amx_get!(vm, hlw),
+                stp: amx_get!(vm, stp),
+                pri: 0,
+                alt: 0,
+                frm: 0,
+                stk: amx_get!(vm, stk),
+                hea: amx_get!(vm, hea),
+                cip,
+            }
+        }
+    }
+
+    fn amx(&self) -> *mut AMX {
+        self.vm.cast::<AMX>()
+    }
+
+    /// Pushes the argument size and the zero return address, then runs until
+    /// `HALT`. Returns the `HALT` operand.
+    unsafe fn start(&mut self) -> Step<i32> {
+        unsafe {
+            let paramcount = amx_get!(self.vm, paramcount);
+            self.push(paramcount * 4)?;
+            amx_set!(self.vm, paramcount, 0);
+            self.push(0)?;
+            self.check_margin()?;
+            self.run()
+        }
+    }
+
+    // -- memory ---------------------------------------------------------------
+
+    /// Host pointer for `width` bytes at data address `addr`.
+    fn ptr(&self, addr: i32, width: i32) -> Step<*mut u8> {
+        if addr < 0 || addr > self.data_size - width {
+            return Err(AmxError::MemoryAccess);
+        }
+        Ok(unsafe { self.data.add(addr as usize) })
+    }
+
+    fn read(&self, addr: i32) -> Step<i32> {
+        Ok(unsafe { self.ptr(addr, 4)?.cast::<i32>().read_unaligned() })
+    }
+
+    fn write(&mut self, addr: i32, value: i32) -> Step {
+        unsafe { self.ptr(addr, 4)?.cast::<i32>().write_unaligned(value) };
+        Ok(())
+    }
+
+    /// `CHKMEM` of `amx.c`: indirect accesses may not land in the free gap
+    /// between heap and stack, nor past the stack top.
+    fn check_mem(&self, addr: i32) -> Step {
+        if (addr >= self.hea && addr < self.stk) || addr < 0 || addr >= self.stp {
+            Err(AmxError::MemoryAccess)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read_checked(&self, addr: i32) -> Step<i32> {
+        self.check_mem(addr)?;
+        self.read(addr)
+    }
+
+    fn write_checked(&mut self, addr: i32, value: i32) -> Step {
+        self.check_mem(addr)?;
+        self.write(addr, value)
+    }
+
+    /// Checked access to `len` bytes at `addr`, for `MOVS`/`CMPS`/`FILL`.
+    fn range(&self, addr: i32, len: i32) -> Step<*mut u8> {
+        if len < 0 {
+            return Err(AmxError::MemoryAccess);
+        }
+        self.check_mem(addr)?;
+        if len > 0 {
+            self.check_mem(addr.wrapping_add(len - 1))?;
+        }
+        self.ptr(addr, len)
+    }
+
+    fn push(&mut self, value: i32) -> Step {
+        self.stk = self.stk.wrapping_sub(4);
+        self.write(self.stk, value)
+    }
+
+    fn pop(&mut self) -> Step<i32> {
+        let value = self.read(self.stk)?;
+        self.stk = self.stk.wrapping_add(4);
+        Ok(value)
+    }
+
+    fn check_margin(&self) -> Step {
+        if self.hea + STKMARGIN > self.stk {
+            Err(AmxError::StackError)
+        } else {
+            Ok(())
+        }
+    }
+
+    // -- code -----------------------------------------------------------------
+
+    fn fetch(&mut self) -> Step<i32> {
+        if self.cip < 0 || self.cip % 4 != 0 || self.cip > self.code_size - 4 {
+            return Err(AmxError::MemoryAccess);
+        }
+        let cell = unsafe {
+            self.code
+                .add(self.cip as usize)
+                .cast::<i32>()
+                .read_unaligned()
+        };
+        self.cip += 4;
+        Ok(cell)
+    }
+
+    fn read_code(&self, offset: i32) -> Step<i32> {
+        if offset < 0 || offset % 4 != 0 || offset > self.code_size - 4 {
+            return Err(AmxError::MemoryAccess);
+        }
+        Ok(unsafe {
+            self.code
+                .add(offset as usize)
+                .cast::<i32>()
+                .read_unaligned()
+        })
+    }
+
+    fn jump_if(&mut self, condition: bool) -> Step {
+        let target = self.fetch()?;
+        if condition {
+            self.cip = target;
+        }
+        Ok(())
+    }
+
+    /// Stores the registers a native or debug hook may inspect (`amx.c` does
+    /// the same before `amx->callback`/`amx->debug`).
+    unsafe fn sync(&self) {
+        unsafe {
+            amx_set!(self.vm, cip, self.cip);
+            amx_set!(self.vm, hea, self.hea);
+            amx_set!(self.vm, frm, self.frm);
+            amx_set!(self.vm, stk, self.stk);
+        }
+    }
+
+    fn sysreq(&mut self, index: i32) -> Step {
+        // Natives dereference `params` as `*mut i32`: it must be cell-aligned.
+        if self.stk % 4 != 0 {
+            return Err(AmxError::MemoryAccess);
+        }
+        let params = self.ptr(self.stk, 4)?.cast::<i32>();
+        let mut pri = self.pri;
+        let rc = unsafe {
+            self.sync();
+            let callback = amx_get!(self.vm, callback);
+            callback(self.amx(), index, &raw mut pri, params)
+        };
+        self.pri = pri;
+        match rc {
+            0 => Ok(()),
+            rc => Err(AmxError::from(rc)),
+        }
+    }
+
+    // -- arithmetic -------------------------------------------------------------
+
+    /// Floored division with the matching remainder, as `amx.c` computes it.
+    fn floored_div(dividend: i32, divisor: i32) -> Step<(i32, i32)> {
+        if divisor == 0 {
+            return Err(AmxError::Divide);
+        }
+        let mut quotient = dividend.wrapping_div(divisor);
+        let mut remainder = dividend.wrapping_rem(divisor);
+        if remainder != 0 && (remainder ^ divisor) < 0 {
+            quotient = quotient.wrapping_sub(1);
+            remainder = remainder.wrapping_add(divisor);
+        }
+        Ok((quotient, remainder))
+    }
+
+    fn unsigned_div(dividend: i32, divisor: i32) -> Step<(i32, i32)> {
+        if divisor == 0 {
+            return Err(AmxError::Divide);
+        }
+        let (a, b) = (dividend.cast_unsigned(), divisor.cast_unsigned());
+        Ok(((a / b).cast_signed(), (a % b).cast_signed()))
+    }
+
+    fn lt(a: i32, b: i32) -> bool {
+        a.cast_unsigned() < b.cast_unsigned()
+    }
+
+    // -- main loop ----------------------------------------------------------------
+
+    #[allow(clippy::too_many_lines)] // one arm per opcode, mirroring `amx.c`
+    fn run(&mut self) -> Step<i32> {
+        loop {
+            match self.fetch()? {
+                op::LOAD_PRI => {
+                    let a = self.fetch()?;
+                    self.pri = self.read(a)?;
+                }
+                op::LOAD_ALT => {
+                    let a = self.fetch()?;
+                    self.alt = self.read(a)?;
+                }
+                op::LOAD_S_PRI => {
+                    let o = self.fetch()?;
+                    self.pri = self.read(self.frm.wrapping_add(o))?;
+                }
+                op::LOAD_S_ALT => {
+                    let o = self.fetch()?;
+                    self.alt = self.read(self.frm.wrapping_add(o))?;
+                }
+                op::LREF_PRI => {
+                    let a = self.fetch()?;
+                    let a = self.read(a)?;
+                    self.pri = self.read_checked(a)?;
+                }
+                op::LREF_ALT => {
+                    let a = self.fetch()?;
+                    let a = self.read(a)?;
+                    self.alt = self.read_checked(a)?;
+                }
+                op::LREF_S_PRI => {
+                    let o = self.fetch()?;
+                    let a = self.read(self.frm.wrapping_add(o))?;
+                    self.pri = self.read_checked(a)?;
+                }
+                op::LREF_S_ALT => {
+                    let o = self.fetch()?;
+                    let a = self.read(self.frm.wrapping_add(o))?;
+                    self.alt = self.read_checked(a)?;
+                }
+                op::LOAD_I => self.pri = self.read_checked(self.pri)?,
+                op::LODB_I => {
+                    let width = self.fetch()?;
+                    self.check_mem(self.pri)?;
+                    let p = self.ptr(self.pri, width.clamp(1, 4))?;
+                    self.pri = unsafe {
+                        match width {
+                            1 => i32::from(p.read()),
+                            2 => i32::from(p.cast::<u16>().read_unaligned()),
+                            4 => p.cast::<i32>().read_unaligned(),
+                            _ => return Err(AmxError::InvalidInstruction),
+                        }
+                    };
+                }
+                op::CONST_PRI => self.pri = self.fetch()?,
+                op::CONST_ALT => self.alt = self.fetch()?,
+                op::ADDR_PRI => self.pri = self.frm.wrapping_add(self.fetch()?),
+                op::ADDR_ALT => self.alt = self.frm.wrapping_add(self.fetch()?),
+                op::STOR_PRI => {
+                    let a = self.fetch()?;
+                    self.write(a, self.pri)?;
+                }
+                op::STOR_ALT => {
+                    let a = self.fetch()?;
+                    self.write(a, self.alt)?;
+                }
+                op::STOR_S_PRI => {
+                    let o = self.fetch()?;
+                    self.write(self.frm.wrapping_add(o), self.pri)?;
+                }
+                op::STOR_S_ALT => {
+                    let o = self.fetch()?;
+                    self.write(self.frm.wrapping_add(o), self.alt)?;
+                }
+                op::SREF_PRI => {
+                    let a = self.fetch()?;
+                    let a = self.read(a)?;
+                    self.write_checked(a, self.pri)?;
+                }
+                op::SREF_ALT => {
+                    let a = self.fetch()?;
+                    let a = self.read(a)?;
+                    self.write_checked(a, self.alt)?;
+                }
+                op::SREF_S_PRI => {
+                    let o = self.fetch()?;
+                    let a = self.read(self.frm.wrapping_add(o))?;
+                    self.write_checked(a, self.pri)?;
+                }
+                op::SREF_S_ALT => {
+                    let o = self.fetch()?;
+                    let a = self.read(self.frm.wrapping_add(o))?;
+                    self.write_checked(a, self.alt)?;
+                }
+                op::STOR_I => self.write_checked(self.alt, self.pri)?,
+                op::STRB_I => {
+                    let width = self.fetch()?;
+                    self.check_mem(self.alt)?;
+                    let p = self.ptr(self.alt, width.clamp(1, 4))?;
+                    let bytes = self.pri.to_le_bytes();
+                    unsafe {
+                        match width {
+                            1 => p.write(bytes[0]),
+                            2 => p.cast::<[u8; 2]>().write_unaligned([bytes[0], bytes[1]]),
+                            4 => p.cast::<i32>().write_unaligned(self.pri),
+                            _ => return Err(AmxError::InvalidInstruction),
+                        }
+                    }
+                }
+                op::LIDX => {
+                    let a = self.alt.wrapping_add(self.pri.wrapping_mul(4));
+                    self.pri = self.read_checked(a)?;
+                }
+                op::LIDX_B => {
+                    let shift = self.fetch()?;
+                    let a = self
+                        .alt
+                        .wrapping_add(self.pri.wrapping_shl(shift.cast_unsigned()));
+                    self.pri = self.read_checked(a)?;
+                }
+                op::IDXADDR => self.pri = self.alt.wrapping_add(self.pri.wrapping_mul(4)),
+                op::IDXADDR_B => {
+                    let shift = self.fetch()?;
+                    self.pri = self
+                        .alt
+                        .wrapping_add(self.pri.wrapping_shl(shift.cast_unsigned()));
+                }
+                // Little endian: byte `n` of a packed cell lives at offset `3 - n`.
+                op::ALIGN_PRI => {
+                    let n = self.fetch()?;
+                    if (0..4).contains(&n) {
+                        self.pri ^= 4 - n;
+                    }
+                }
+                op::ALIGN_ALT => {
+                    let n = self.fetch()?;
+                    if (0..4).contains(&n) {
+                        self.alt ^= 4 - n;
+                    }
+                }
+                op::LCTRL => {
+                    self.pri = match self.fetch()? {
+                        0 => self.cod,
+                        1 => self.dat,
+                        2 => self.hea,
+                        3 => self.stp,
+                        4 => self.stk,
+                        5 => self.frm,
+                        6 => self.cip,
+                        _ => return Err(AmxError::InvalidInstruction),
+                    };
+                }
+                op::SCTRL => match self.fetch()? {
+                    2 => self.hea = self.pri,
+                    4 => self.stk = self.pri,
+                    5 => self.frm = self.pri,
+                    6 => self.cip = self.pri,
+                    _ => return Err(AmxError::InvalidInstruction),
+                },
+                op::MOVE_PRI => self.pri = self.alt,
+                op::MOVE_ALT => self.alt = self.pri,
+                op::XCHG => std::mem::swap(&mut self.pri, &mut self.alt),
+                op::PUSH_PRI => self.push(self.pri)?,
+                op::PUSH_ALT => self.push(self.alt)?,
+                op::PUSH_R => {
+                    let count = self.fetch()?;
+                    for _ in 0..count {
+                        self.push(self.pri)?;
+                    }
+                }
+                op::PUSH_C => {
+                    let v = self.fetch()?;
+                    self.push(v)?;
+                }
+                op::PUSH => {
+                    let a = self.fetch()?;
+                    let v = self.read(a)?;
+                    self.push(v)?;
+                }
+                op::PUSH_S => {
+                    let o = self.fetch()?;
+                    let v = self.read(self.frm.wrapping_add(o))?;
+                    self.push(v)?;
+                }
+                op::PUSH_ADR => {
+                    let o = self.fetch()?;
+                    self.push(self.frm.wrapping_add(o))?;
+                }
+                op::POP_PRI => self.pri = self.pop()?,
+                op::POP_ALT => self.alt = self.pop()?,
+                op::STACK => {
+                    let v = self.fetch()?;
+                    self.alt = self.stk;
+                    self.stk = self.stk.wrapping_add(v);
+                    self.check_margin()?;
+                    if self.stk > self.stp {
+                        return Err(AmxError::StackLow);
+                    }
+                }
+                op::HEAP => {
+                    let v = self.fetch()?;
+                    self.alt = self.hea;
+                    self.hea = self.hea.wrapping_add(v);
+                    self.check_margin()?;
+                    if self.hea < self.hlw {
+                        return Err(AmxError::HeapLow);
+                    }
+                }
+                op::PROC => {
+                    self.push(self.frm)?;
+                    self.frm = self.stk;
+                    self.check_margin()?;
+                }
+                op::RET => {
+                    self.frm = self.pop()?;
+                    self.cip = self.pop()?;
+                }
+                op::RETN => {
+                    self.frm = self.pop()?;
+                    self.cip = self.pop()?;
+                    let args = self.read(self.stk)?;
+                    self.stk = self.stk.wrapping_add(args).wrapping_add(4);
+                }
+                op::CALL => {
+                    let target = self.fetch()?;
+                    self.push(self.cip)?;
+                    self.cip = target;
+                }
+                op::CALL_PRI => {
+                    self.push(self.cip)?;
+                    self.cip = self.pri;
+                }
+                op::JUMP => self.jump_if(true)?,
+                op::JREL => {
+                    let o = self.fetch()?;
+                    self.cip = self.cip.wrapping_add(o);
+                }
+                op::JZER => self.jump_if(self.pri == 0)?,
+                op::JNZ => self.jump_if(self.pri != 0)?,
+                op::JEQ => self.jump_if(self.pri == self.alt)?,
+                op::JNEQ => self.jump_if(self.pri != self.alt)?,
+                op::JLESS => self.jump_if(Self::lt(self.pri, self.alt))?,
+                op::JLEQ => self.jump_if(!Self::lt(self.alt, self.pri))?,
+                op::JGRTR => self.jump_if(Self::lt(self.alt, self.pri))?,
+                op::JGEQ => self.jump_if(!Self::lt(self.pri, self.alt))?,
+                op::JSLESS => self.jump_if(self.pri < self.alt)?,
+                op::JSLEQ => self.jump_if(self.pri <= self.alt)?,
+                op::JSGRTR => self.jump_if(self.pri > self.alt)?,
+                op::JSGEQ => self.jump_if(self.pri >= self.alt)?,
+                op::SHL => self.pri = self.pri.wrapping_shl(self.alt.cast_unsigned()),
+                op::SHR => {
+                    self.pri = self
+                        .pri
+                        .cast_unsigned()
+                        .wrapping_shr(self.alt.cast_unsigned())
+                        .cast_signed();
+                }
+                op::SSHR => self.pri = self.pri.wrapping_shr(self.alt.cast_unsigned()),
+                op::SHL_C_PRI => self.pri = self.pri.wrapping_shl(self.fetch()?.cast_unsigned()),
+                op::SHL_C_ALT => self.alt = self.alt.wrapping_shl(self.fetch()?.cast_unsigned()),
+                op::SHR_C_PRI => {
+                    let n = self.fetch()?.cast_unsigned();
+                    self.pri = self.pri.cast_unsigned().wrapping_shr(n).cast_signed();
+                }
+                op::SHR_C_ALT => {
+                    let n = self.fetch()?.cast_unsigned();
+                    self.alt = self.alt.cast_unsigned().wrapping_shr(n).cast_signed();
+                }
+                op::SMUL => self.pri = self.pri.wrapping_mul(self.alt),
+                op::SDIV => (self.pri, self.alt) = Self::floored_div(self.pri, self.alt)?,
+                op::SDIV_ALT => (self.pri, self.alt) = Self::floored_div(self.alt, self.pri)?,
+                op::UMUL => {
+                    self.pri = self
+                        .pri
+                        .cast_unsigned()
+                        .wrapping_mul(self.alt.cast_unsigned())
+                        .cast_signed();
+                }
+                op::UDIV => (self.pri, self.alt) = Self::unsigned_div(self.pri, self.alt)?,
+                op::UDIV_ALT => (self.pri, self.alt) = Self::unsigned_div(self.alt, self.pri)?,
+                op::ADD => self.pri = self.pri.wrapping_add(self.alt),
+                op::SUB => self.pri = self.pri.wrapping_sub(self.alt),
+                op::SUB_ALT => self.pri = self.alt.wrapping_sub(self.pri),
+                op::AND => self.pri &= self.alt,
+                op::OR => self.pri |= self.alt,
+                op::XOR => self.pri ^= self.alt,
+                op::NOT => self.pri = i32::from(self.pri == 0),
+                op::NEG => self.pri = self.pri.wrapping_neg(),
+                op::INVERT => self.pri = !self.pri,
+                op::ADD_C => self.pri = self.pri.wrapping_add(self.fetch()?),
+                op::SMUL_C => self.pri = self.pri.wrapping_mul(self.fetch()?),
+                op::ZERO_PRI => self.pri = 0,
+                op::ZERO_ALT => self.alt = 0,
+                op::ZERO => {
+                    let a = self.fetch()?;
+                    self.write(a, 0)?;
+                }
+                op::ZERO_S => {
+                    let o = self.fetch()?;
+                    self.write(self.frm.wrapping_add(o), 0)?;
+                }
+                op::SIGN_PRI => self.pri = i32::from(self.pri.to_le_bytes()[0].cast_signed()),
+                op::SIGN_ALT => self.alt = i32::from(self.alt.to_le_bytes()[0].cast_signed()),
+                op::EQ => self.pri = i32::from(self.pri == self.alt),
+                op::NEQ => self.pri = i32::from(self.pri != self.alt),
+                op::LESS => self.pri = i32::from(Self::lt(self.pri, self.alt)),
+                op::LEQ => self.pri = i32::from(!Self::lt(self.alt, self.pri)),
+                op::GRTR => self.pri = i32::from(Self::lt(self.alt, self.pri)),
+                op::GEQ => self.pri = i32::from(!Self::lt(self.pri, self.alt)),
+                op::SLESS => self.pri = i32::from(self.pri < self.alt),
+                op::SLEQ => self.pri = i32::from(self.pri <= self.alt),
+                op::SGRTR => self.pri = i32::from(self.pri > self.alt),
+                op::SGEQ => self.pri = i32::from(self.pri >= self.alt),
+                op::EQ_C_PRI => self.pri = i32::from(self.pri == self.fetch()?),
+                op::EQ_C_ALT => self.pri = i32::from(self.alt == self.fetch()?),
+                op::INC_PRI => self.pri = self.pri.wrapping_add(1),
+                op::INC_ALT => self.alt = self.alt.wrapping_add(1),
+                op::INC => {
+                    let a = self.fetch()?;
+                    self.write(a, self.read(a)?.wrapping_add(1))?;
+                }
+                op::INC_S => {
+                    let a = self.frm.wrapping_add(self.fetch()?);
+                    self.write(a, self.read(a)?.wrapping_add(1))?;
+                }
+                op::INC_I => {
+                    let v = self.read_checked(self.pri)?;
+                    self.write(self.pri, v.wrapping_add(1))?;
+                }
+                op::DEC_PRI => self.pri = self.pri.wrapping_sub(1),
+                op::DEC_ALT => self.alt = self.alt.wrapping_sub(1),
+                op::DEC => {
+                    let a = self.fetch()?;
+                    self.write(a, self.read(a)?.wrapping_sub(1))?;
+                }
+                op::DEC_S => {
+                    let a = self.frm.wrapping_add(self.fetch()?);
+                    self.write(a, self.read(a)?.wrapping_sub(1))?;
+                }
+                op::DEC_I => {
+                    let v = self.read_checked(self.pri)?;
+                    self.write(self.pri, v.wrapping_sub(1))?;
+                }
+                op::MOVS => {
+                    let len = self.fetch()?;
+                    let src = self.range(self.pri, len)?;
+                    let dst = self.range(self.alt, len)?;
+                    unsafe { std::ptr::copy(src, dst, len as usize) };
+                }
+                op::CMPS => {
+                    let len = self.fetch()?;
+                    let a = self.range(self.alt, len)?;
+                    let b = self.range(self.pri, len)?;
+                    let (a, b) = unsafe {
+                        (
+                            std::slice::from_raw_parts(a, len as usize),
+                            std::slice::from_raw_parts(b, len as usize),
+                        )
+                    };
+                    self.pri = match a.cmp(b) {
+                        Ordering::Less => -1,
+                        Ordering::Equal => 0,
+                        Ordering::Greater => 1,
+                    };
+                }
+                op::FILL => {
+                    let len = self.fetch()?;
+                    self.range(self.alt, len)?;
+                    for offset in (0..len - len % 4).step_by(4) {
+                        self.write(self.alt.wrapping_add(offset), self.pri)?;
+                    }
+                }
+                op::HALT => {
+                    let halt = self.fetch()?;
+                    return Ok(halt);
+                }
+                op::BOUNDS => {
+                    let max = self.fetch()?;
+                    if Self::lt(max, self.pri) {
+                        return Err(AmxError::Bounds);
+                    }
+                }
+                op::SYSREQ_PRI => self.sysreq(self.pri)?,
+                op::SYSREQ_C => {
+                    let index = self.fetch()?;
+                    self.sysreq(index)?;
+                }
+                op::JUMP_PRI => self.cip = self.pri,
+                op::SWITCH => {
+                    // The operand points at a CASETBL: opcode, record count,
+                    // default target, then (value, target) records.
+                    let table = self.fetch()?.wrapping_add(4);
+                    let records = self.read_code(table)?;
+                    self.cip = self.read_code(table.wrapping_add(4))?;
+                    for record in 0..records {
+                        let at = table.wrapping_add(8).wrapping_add(record.wrapping_mul(8));
+                        if self.read_code(at)? == self.pri {
+                            self.cip = self.read_code(at.wrapping_add(4))?;
+                            break;
+                        }
+                    }
+                }
+                op::SWAP_PRI => {
+                    let v = self.read(self.stk)?;
+                    self.write(self.stk, self.pri)?;
+                    self.pri = v;
+                }
+                op::SWAP_ALT => {
+                    let v = self.read(self.stk)?;
+                    self.write(self.stk, self.alt)?;
+                    self.alt = v;
+                }
+                op::NOP => {}
+                op::BREAK => {
+                    let rc = unsafe {
+                        self.sync();
+                        let debug = amx_get!(self.vm, debug);
+                        debug(self.amx())
+                    };
+                    if rc != 0 {
+                        return Err(AmxError::from(rc));
+                    }
+                }
+                // NONE, CASETBL (data, never executed), SYSREQ.D and the
+                // obsolete debug opcodes.
+                _ => return Err(AmxError::InvalidInstruction),
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Pure-Rust AMX interpreter for testing plugins without a server.
+//!
+//! Loads a compiled `.amx` (plain or compact-encoded, file version 6–8) and
+//! exposes the same `amx_Exports` function table the server hands to plugins,
+//! so [`Amx::new(ptr, fn_table)`](crate::amx::Amx::new) — and everything built
+//! on it: natives, `exec_public!`, `Allocator`, `Args` — runs unchanged in
+//! `cargo test`:
+//!
+//! ```rust,no_run
+//! use samp_sdk::test_vm::TestVm;
+//!
+//! let vm = TestVm::from_file("tests/gamemode.amx").unwrap();
+//! vm.amx().register(&my_natives()).unwrap();
+//! assert_eq!(vm.call("OnGameModeInit", &[]).unwrap(), 1);
+//! # fn my_natives() -> Vec<samp_sdk::raw::types::AMX_NATIVE_INFO> { vec![] }
+//! ```
+//!
+//! For end-to-end tests through the `samp` runtime, pass [`server_data`] to
+//! the generated `Load()` and [`TestVm::as_ptr`] to `AmxLoad()`, exactly as
+//! the server would. Scripts can also be hand-assembled with [`ImageBuilder`]
+//! when shipping a compiled `.amx` is not an option. On open.mp, [`omp::MockServer`]
+//! plays the server side of the component lifecycle instead.
+//!
+//! Scope and limitations — this is a test double, not a server:
+//! - Implements every opcode of the Pawn 3.x instruction set except the
+//!   obsolete debug ones and `SYSREQ.D` (never emitted without a JIT).
+//! - Jump targets stay code-relative; `AMX_FLAG_RELOC` is never set, so
+//!   [`Amx::opcode_table`](crate::amx::Amx::opcode_table) returns `None`.
+//! - `sleep` is not supported (`AmxExecIdx::Continue` fails with `InvalidState`).
+//! - On 64-bit hosts native addresses do not fit the 32-bit slots of the
+//!   `.amx` natives table: `amx_Register` keeps them in a side table instead,
+//!   which the script and `amx_Callback` dispatch through. The exports table
+//!   registers a reader for it, so [`Amx::call_native`](crate::amx::Amx::call_native)
+//!   finds them for any AMX wired to the interpreter; reading the table
+//!   directly only works on 32-bit targets.
+//! - `amx_Clone`, `amx_Init` and `amx_InitJIT` are not supported — use
+//!   [`TestVm::from_bytes`] to load a script.
+//!
+//! Enabled by the `test-vm` feature; meant for `[dev-dependencies]`.
+
+/// Reads a field of the packed `AMX` inside a `*mut VmState`.
+macro_rules! amx_get {
+    ($vm:expr, $field:ident) => {
+        std::ptr::addr_of!((*$vm).amx.$field).read_unaligned()
+    };
+}
+
+/// Writes a field of the packed `AMX` inside a `*mut VmState`.
+macro_rules! amx_set {
+    ($vm:expr, $field:ident, $value:expr) => {
+        std::ptr::addr_of_mut!((*$vm).amx.$field).write_unaligned($value)
+    };
+}
+
+mod exports;
+mod image;
+mod interp;
+// Same gate as `crate::omp`, whose bindings the mock server answers.
+#[cfg(all(
+    not(feature = "samp-only"),
+    not(all(windows, target_env = "msvc", target_pointer_width = "64"))
+))]
+pub mod omp;
+pub mod opcodes;
+
+pub use image::ImageBuilder;
+
+use crate::amx::Amx;
+use crate::consts::{AmxExecIdx, AmxFlags};
+use crate::error::{AmxError, AmxResult};
+use crate::raw::functions::AmxNative;
+use crate::raw::types::AMX;
+use image::Layout;
+
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Interpreter state. `amx` must stay the first field: the exported functions
+/// receive `*mut AMX` and cast it back to `*mut VmState`.
+#[repr(C)]
+struct VmState {
+    amx: AMX,
+    layout: Layout,
+    /// Backing memory: header + code + data + heap + stack, as cells so every
+    /// address handed out is cell-aligned. Never resized after load.
+    memory: Vec<i32>,
+    /// Host functions bound by `amx_Register`, indexed like the natives table.
+    natives: Vec<Option<AmxNative>>,
+}
+
+/// The host function `amx_Register` bound to native `index` of `amx`, from
+/// the side table that stands in for the natives table on 64-bit hosts.
+/// Handed to [`Amx`] as the reader for AMXs using the interpreter's exports.
+#[cfg(target_pointer_width = "64")]
+fn bound_native(amx: *mut AMX, index: i32) -> Option<AmxNative> {
+    if amx.is_null() {
+        return None;
+    }
+    let vm = amx.cast::<VmState>();
+    // SAFETY: only called for AMXs whose exports are the interpreter's, which
+    // are `VmState`s, alive while the caller borrows them.
+    let natives = unsafe { &(*vm).natives };
+    natives.get(usize::try_from(index).ok()?).copied().flatten()
+}
+
+/// An AMX instance running on the test interpreter.
+///
+/// Owns the script memory; the `*mut AMX` from [`as_ptr`](Self::as_ptr) stays
+/// valid until the `TestVm` is dropped.
+#[derive(Debug)]
+pub struct TestVm {
+    state: NonNull<VmState>,
+}
+
+impl TestVm {
+    /// Loads a compiled `.amx` file.
+    ///
+    /// # Errors
+    /// I/O errors from reading the file; an invalid image is reported as
+    /// [`std::io::ErrorKind::InvalidData`] wrapping the [`AmxError`].
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<TestVm> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Loads an `.amx` image from memory.
+    ///
+    /// # Errors
+    /// `AmxError::Format` for a malformed image (bad magic, misaligned or
+    /// out-of-order segments, corrupt compact encoding) and
+    /// `AmxError::Version` for an unsupported file version.
+    pub fn from_bytes(bytes: &[u8]) -> AmxResult<TestVm> {
+        let mut layout = image::parse_header(bytes)?;
+        let mut memory = image::load_image(bytes, &mut layout)?;
+        let natives = vec![None; usize::try_from(layout.num_natives()).unwrap_or(0)];
+
+        // Same as `amx_Init`: a script without natives needs no registration.
+        if natives.is_empty() {
+            layout.flags |= AmxFlags::NTVREG.bits();
+        }
+
+        let base = memory.as_mut_ptr().cast::<u8>();
+        let hlw = layout.hea - layout.dat;
+        let stp = layout.stp - layout.dat - 4;
+        let amx = AMX {
+            base,
+            data: std::ptr::null_mut(),
+            callback: exports::callback,
+            debug: exports::no_debug,
+            cip: 0,
+            frm: 0,
+            hea: hlw,
+            hlw,
+            stk: stp,
+            stp,
+            flags: i32::from(layout.flags),
+            usertags: [0; 4],
+            userdata: [std::ptr::null_mut(); 4],
+            error: 0,
+            paramcount: 0,
+            pri: 0,
+            alt: 0,
+            reset_stk: 0,
+            reset_hea: 0,
+            sysreq_d: 0,
+        };
+
+        let state = Box::new(VmState {
+            amx,
+            layout,
+            memory,
+            natives,
+        });
+        Ok(TestVm {
+            state: NonNull::from(Box::leak(state)),
+        })
+    }
+
+    /// The raw `*mut AMX`, as the server passes it to `AmxLoad`.
+    #[must_use]
+    pub fn as_ptr(&self) -> *mut AMX {
+        // `amx` is the first field of the `#[repr(C)]` state.
+        self.state.as_ptr().cast::<AMX>()
+    }
+
+    /// Address of the `amx_Exports` table implemented by the interpreter.
+    #[must_use]
+    pub fn exports(&self) -> usize {
+        exports::table()
+    }
+
+    /// Safe wrapper over this instance, wired to the interpreter's exports.
+    #[must_use]
+    pub fn amx(&self) -> Amx {
+        Amx::new(self.as_ptr(), self.exports())
+    }
+
+    /// Calls a public by name with integer arguments (in declaration order)
+    /// and returns its result. For strings and arrays, go through
+    /// [`amx`](Self::amx) and `exec_public!`/`Allocator` instead.
+    ///
+    /// # Errors
+    /// `AmxError::NotFound` if the public does not exist; otherwise any
+    /// runtime error raised while executing it.
+    pub fn call(&self, public: &str, args: &[i32]) -> AmxResult<i32> {
+        let amx = self.amx();
+        let index = amx.find_public(public)?;
+        for &arg in args.iter().rev() {
+            amx.push(arg)?;
+        }
+        amx.exec(index)
+    }
+
+    /// Runs `main()`.
+    ///
+    /// # Errors
+    /// `AmxError::Index` if the script has no `main`; otherwise any runtime error.
+    pub fn run_main(&self) -> AmxResult<i32> {
+        self.amx().exec(AmxExecIdx::Main)
+    }
+
+    /// Reads a NUL-terminated string (packed or unpacked) at a data address.
+    /// `None` when the address is outside the data segment.
+    #[must_use]
+    pub fn read_string(&self, addr: i32) -> Option<String> {
+        let amx = self.amx();
+        let first = amx.read_cell(addr)?;
+        let mut bytes = Vec::new();
+        if first.cast_unsigned() > crate::cell::string::MAX_UNPACKED.cast_unsigned() {
+            let mut offset = 0;
+            'cells: loop {
+                let cell = amx.read_cell(addr + offset)?;
+                for byte in cell.to_be_bytes() {
+                    if byte == 0 {
+                        break 'cells;
+                    }
+                    bytes.push(byte);
+                }
+                offset += 4;
+            }
+        } else {
+            let mut offset = 0;
+            loop {
+                let cell = amx.read_cell(addr + offset)?;
+                if cell == 0 {
+                    break;
+                }
+                // Unpacked strings hold one character per cell; keep the low byte.
+                bytes.push(cell.to_le_bytes()[0]);
+                offset += 4;
+            }
+        }
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl Drop for TestVm {
+    fn drop(&mut self) {
+        // A dropped gamemode leaves `CallPublicGm` with no script, and a
+        // dropped filterscript leaves `CallPublicFs`.
+        let _ = GAMEMODE.compare_exchange(
+            self.as_ptr(),
+            std::ptr::null_mut(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        FILTERSCRIPTS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|&amx| amx != self.as_ptr() as usize);
+        // SAFETY: `state` came from `Box::leak` in `from_bytes` and is only freed here.
+        drop(unsafe { Box::from_raw(self.state.as_ptr()) });
+    }
+}
+
+/// Lines written through the `logprintf` of [`server_data`].
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Script the `CallPublicGm` of [`server_data`] runs publics in.
+static GAMEMODE: AtomicPtr<AMX> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Scripts the `CallPublicFs` of [`server_data`] runs publics in, in the
+/// order they were added.
+static FILTERSCRIPTS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// The `ppData` table the server passes to a plugin's `Load()`: `logprintf`
+/// at [`ServerData::Logprintf`], the interpreter's `amx_Exports` at
+/// [`ServerData::AmxExports`], a `CallPublicFs` at
+/// [`ServerData::CallPublicFs`] that runs in the scripts added with
+/// [`add_filterscript`] and a `CallPublicGm` at [`ServerData::CallPublicGm`]
+/// that runs in the script set by [`set_gamemode`]. Lines logged through it
+/// are collected by [`take_log`].
+///
+/// [`ServerData::Logprintf`]: crate::consts::ServerData::Logprintf
+/// [`ServerData::AmxExports`]: crate::consts::ServerData::AmxExports
+/// [`ServerData::CallPublicFs`]: crate::consts::ServerData::CallPublicFs
+/// [`ServerData::CallPublicGm`]: crate::consts::ServerData::CallPublicGm
+#[must_use]
+pub fn server_data() -> *const usize {
+    exports::server_data()
+}
+
+/// Makes `vm` the gamemode of [`server_data`], the script its `CallPublicGm`
+/// runs publics in; `None` unloads it. Dropping that `TestVm` clears it too.
+pub fn set_gamemode(vm: Option<&TestVm>) {
+    let amx = vm.map_or(std::ptr::null_mut(), TestVm::as_ptr);
+    GAMEMODE.store(amx, Ordering::Release);
+}
+
+/// Adds `vm` to the filterscripts of [`server_data`], the scripts its
+/// `CallPublicFs` runs publics in. Dropping that `TestVm` removes it.
+pub fn add_filterscript(vm: &TestVm) {
+    let mut filterscripts = FILTERSCRIPTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let amx = vm.as_ptr() as usize;
+    if !filterscripts.contains(&amx) {
+        filterscripts.push(amx);
+    }
+}
+
+/// Drains the lines logged through [`server_data`]'s `logprintf`.
+#[must_use]
+pub fn take_log() -> Vec<String> {
+    std::mem::take(
+        &mut *LOG
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    )
+}
+
+/// Convenience for exported functions: `AmxError` → the `AMX_ERR_*` code.
+fn code(err: AmxError) -> i32 {
+    err as i32
+}
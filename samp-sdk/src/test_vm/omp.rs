@@ -0,0 +1,770 @@
+//! Mock Open Multiplayer server for driving a component through its lifecycle.
+//!
+//! [`MockServer`] fabricates the server objects a component talks to —
+//! `ICore` (`getPlayers()` and its `ILogger` subobject), `IComponentList`,
+//! `IPawnComponent` with its `IEventDispatcher<PawnEventHandler>`,
+//! `ITimersComponent`/`ITimer`, `IPawnScript`, and `IPlayerPool` with its
+//! `IEventDispatcher<PlayerConnectEventHandler>` and `IPlayer`s — with vtables laid out for the target ABI (Itanium on
+//! Linux, MSVC on Windows). It loads a plugin through its generated
+//! `ComponentEntryPoint` and calls the `IComponent` vtable in the order the
+//! server does:
+//!
+//! ```rust,ignore
+//! use samp::test_vm::{TestVm, omp::MockServer};
+//!
+//! let vm = TestVm::from_file("tests/gamemode.amx")?;
+//! let mut server = MockServer::new();
+//! server.load(crate::__omp_component::ComponentEntryPoint); // + onLoad
+//! server.init();                                          // onInit
+//! server.load_script(&vm); // the gamemode loads before onReady, as on 1.5.x
+//! server.ready();                                         // onReady
+//! assert_eq!(vm.call("OnGameModeInit", &[])?, 1);
+//! server.tick();                                          // fires the timers
+//! server.connect_player(0);                               // onPlayerConnect
+//! server.free();                                          // onFree + free
+//! ```
+//!
+//! Like open.mp 1.5.x, `getAmxFunctions()` returns null until [`ready`] and
+//! the interpreter's table afterwards; the open.mp-only entries past
+//! `amx_UTF8Put` are null.
+//!
+//! The slot indices below are written out from the layouts documented in
+//! `docs/internals/omp-abi.md` rather than taken from [`crate::omp`], so a
+//! binding that drifts from the documented ABI fails against the mock.
+//!
+//! [`ready`]: MockServer::ready
+
+use super::{TestVm, exports};
+use crate::omp::component::{IComponentList, IComponentVTable, ICore, OmpComponent};
+use crate::omp::events::{PawnEventHandler, PawnEventHandlerVTable};
+use crate::omp::players::{IPlayer, PlayerConnectEventHandler, PlayerConnectEventHandlerVTable};
+use crate::omp::server::{IPawnScript, PAWN_COMPONENT_UID};
+use crate::omp::timers::{ITimer, TIMERS_COMPONENT_UID, TimerTimeOutHandler};
+use crate::omp::types::UID;
+use crate::raw::types::AMX;
+
+use std::cell::{Cell, RefCell};
+use std::ffi::{CStr, c_char, c_int};
+use std::ptr::NonNull;
+
+/// Offset of the `ILogger` subobject inside `ICore`.
+#[cfg(not(target_env = "msvc"))]
+const LOGGER_OFFSET: usize = 40;
+#[cfg(target_env = "msvc")]
+const LOGGER_OFFSET: usize = 56;
+
+/// `ILogger`: `printLn`, `vprintLn`, `logLn`, `vlogLn` and their `U8` variants.
+const LOGGER_SLOTS: usize = 8;
+
+/// `ICore::getPlayers` — after `IExtensible`, its destructor(s), `getVersion`
+/// and `getNetworkBitStreamVersion`.
+#[cfg(not(target_env = "msvc"))]
+const CORE_GET_PLAYERS: usize = 8;
+#[cfg(target_env = "msvc")]
+const CORE_GET_PLAYERS: usize = 7;
+
+/// `IPlayerPool::getPlayerConnectDispatcher` — after `IExtensible`, its
+/// destructor(s), `entries`, `players`, `bots`, `isNameTaken` and
+/// `getPlayerSpawnDispatcher`.
+#[cfg(not(target_env = "msvc"))]
+const POOL_CONNECT_DISPATCHER: usize = 11;
+#[cfg(target_env = "msvc")]
+const POOL_CONNECT_DISPATCHER: usize = 10;
+
+/// `IIDProvider::getID`, first slot of `IPlayer`'s `IEntity` subobject.
+const ENTITY_GET_ID: usize = 0;
+
+/// `IComponentList::queryComponent` — after `IExtensible` and its destructor(s).
+#[cfg(not(target_env = "msvc"))]
+const LIST_QUERY: usize = 6;
+#[cfg(target_env = "msvc")]
+const LIST_QUERY: usize = 5;
+
+/// `IPawnComponent::getEventDispatcher`; `getAmxFunctions` follows it.
+#[cfg(not(target_env = "msvc"))]
+const PAWN_DISPATCHER: usize = 18;
+#[cfg(target_env = "msvc")]
+const PAWN_DISPATCHER: usize = 16;
+
+/// `getScript` x2, `mainScript` and `sideScripts` trail the two above.
+const PAWN_MAIN_SCRIPT: usize = PAWN_DISPATCHER + 4;
+const PAWN_SLOTS: usize = PAWN_DISPATCHER + 2 + 4;
+
+/// `IEventDispatcher`: `addEventHandler`, `removeEventHandler`, `hasEventHandler`, `count`.
+const DISPATCHER_SLOTS: usize = 4;
+
+/// `ITimersComponent::create(handler, interval, repeating)`.
+const TIMERS_CREATE: usize = 16;
+const TIMERS_SLOTS: usize = 19;
+
+/// `ITimer::kill`.
+const TIMER_KILL: usize = 10;
+const TIMER_SLOTS: usize = 12;
+
+/// `IPawnScript::GetAMX`.
+const SCRIPT_GET_AMX: usize = 57;
+
+/// Open Multiplayer `LogLevel` prefixes, as the server prints them.
+const LOG_LEVELS: [&str; 4] = ["Debug", "Info", "Warning", "Error"];
+
+/// A server-owned object: vtable pointer first, then a way back to the server.
+#[repr(C)]
+struct Object {
+    vtable: *const usize,
+    server: *const ServerState,
+}
+
+impl Object {
+    const fn null() -> Self {
+        Object {
+            vtable: std::ptr::null(),
+            server: std::ptr::null(),
+        }
+    }
+}
+
+/// `ICore`: `getPlayers()` on the primary vtable, and the `ILogger` subobject.
+#[repr(C)]
+struct Core {
+    primary: Object,
+    _extensible: [u8; LOGGER_OFFSET - size_of::<Object>()],
+    logger: Object,
+}
+
+/// An `ITimer` handed out by `ITimersComponent::create`.
+#[repr(C)]
+struct Timer {
+    vtable: *const usize,
+    handler: Cell<*mut TimerTimeOutHandler>,
+    interval_ms: i64,
+    repeating: bool,
+}
+
+/// An `IPlayer`: the `IExtensible` vtable pointer, then the `IEntity` subobject.
+#[repr(C)]
+struct Player {
+    /// `IExtensible` is never called; left null.
+    vtable: *const usize,
+    entity: Entity,
+}
+
+/// `IEntity` subobject of a [`Player`]; `getID` reads `id`.
+#[repr(C)]
+struct Entity {
+    vtable: *const usize,
+    id: c_int,
+}
+
+/// An `IPawnScript` wrapping a [`TestVm`] instance.
+#[repr(C)]
+struct Script {
+    vtable: *const usize,
+    amx: *mut AMX,
+}
+
+struct ServerState {
+    core: Core,
+    components: Object,
+    pawn: Object,
+    dispatcher: Object,
+    timers: Object,
+    players: Object,
+    connect_dispatcher: Object,
+    core_vtable: [usize; CORE_GET_PLAYERS + 1],
+    logger_vtable: [usize; LOGGER_SLOTS],
+    list_vtable: [usize; LIST_QUERY + 1],
+    pawn_vtable: [usize; PAWN_SLOTS],
+    dispatcher_vtable: [usize; DISPATCHER_SLOTS],
+    connect_dispatcher_vtable: [usize; DISPATCHER_SLOTS],
+    timers_vtable: [usize; TIMERS_SLOTS],
+    timer_vtable: [usize; TIMER_SLOTS],
+    script_vtable: [usize; SCRIPT_GET_AMX + 1],
+    pool_vtable: [usize; POOL_CONNECT_DISPATCHER + 1],
+    entity_vtable: [usize; ENTITY_GET_ID + 1],
+    /// Flipped by `ready()`: `getAmxFunctions()` returns null before it.
+    amx_functions: Cell<bool>,
+    /// AMX of the script `mainScript()` returns; null without a gamemode.
+    main_script: Cell<*mut AMX>,
+    handlers: RefCell<Vec<*mut PawnEventHandler>>,
+    connect_handlers: RefCell<Vec<*mut PlayerConnectEventHandler>>,
+    // Boxed so the `ITimer*`/`IPawnScript*` handed out survive the Vec growing.
+    #[allow(clippy::vec_box)]
+    created_timers: RefCell<Vec<Box<Timer>>>,
+    #[allow(clippy::vec_box)]
+    scripts: RefCell<Vec<Box<Script>>>,
+    #[allow(clippy::vec_box)]
+    connected: RefCell<Vec<Box<Player>>>,
+    log: RefCell<Vec<String>>,
+}
+
+/// A timer the component created through `ITimersComponent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockTimer {
+    /// Interval passed to `create`, in milliseconds.
+    pub interval_ms: i64,
+    /// Whether the timer was created as repeating.
+    pub repeating: bool,
+    /// Whether `kill()` was called on it.
+    pub killed: bool,
+}
+
+/// A fake Open Multiplayer server hosting a single component.
+///
+/// Every object it hands out stays valid until the `MockServer` is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    state: NonNull<ServerState>,
+    component: *mut OmpComponent,
+}
+
+impl Default for MockServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockServer {
+    /// Creates a server with the Pawn and Timers components available.
+    #[must_use]
+    pub fn new() -> MockServer {
+        let state = Box::new(ServerState {
+            core: Core {
+                primary: Object::null(),
+                _extensible: [0; LOGGER_OFFSET - size_of::<Object>()],
+                logger: Object::null(),
+            },
+            components: Object::null(),
+            pawn: Object::null(),
+            dispatcher: Object::null(),
+            timers: Object::null(),
+            players: Object::null(),
+            connect_dispatcher: Object::null(),
+            core_vtable: [0; CORE_GET_PLAYERS + 1],
+            logger_vtable: [0; LOGGER_SLOTS],
+            list_vtable: [0; LIST_QUERY + 1],
+            pawn_vtable: [0; PAWN_SLOTS],
+            dispatcher_vtable: [0; DISPATCHER_SLOTS],
+            connect_dispatcher_vtable: [0; DISPATCHER_SLOTS],
+            timers_vtable: [0; TIMERS_SLOTS],
+            timer_vtable: [0; TIMER_SLOTS],
+            script_vtable: [0; SCRIPT_GET_AMX + 1],
+            pool_vtable: [0; POOL_CONNECT_DISPATCHER + 1],
+            entity_vtable: [0; ENTITY_GET_ID + 1],
+            amx_functions: Cell::new(false),
+            main_script: Cell::new(std::ptr::null_mut()),
+            handlers: RefCell::new(Vec::new()),
+            connect_handlers: RefCell::new(Vec::new()),
+            created_timers: RefCell::new(Vec::new()),
+            scripts: RefCell::new(Vec::new()),
+            connected: RefCell::new(Vec::new()),
+            log: RefCell::new(Vec::new()),
+        });
+        let state = Box::into_raw(state);
+
+        // SAFETY: `state` is freshly allocated and not shared yet; the
+        // objects point into it and are only read after this block.
+        unsafe {
+            let s = &mut *state;
+            // Variadic methods are cdecl on both ABIs.
+            s.core_vtable[CORE_GET_PLAYERS] = abi::get_players as *const () as usize;
+            s.logger_vtable[0] = print_ln as *const () as usize;
+            s.logger_vtable[2] = log_ln as *const () as usize;
+            s.logger_vtable[4] = print_ln as *const () as usize;
+            s.logger_vtable[6] = log_ln as *const () as usize;
+            s.list_vtable[LIST_QUERY] = abi::query_component as *const () as usize;
+            s.pawn_vtable[PAWN_DISPATCHER] = abi::get_event_dispatcher as *const () as usize;
+            s.pawn_vtable[PAWN_DISPATCHER + 1] = abi::get_amx_functions as *const () as usize;
+            s.pawn_vtable[PAWN_MAIN_SCRIPT] = abi::main_script as *const () as usize;
+            s.dispatcher_vtable[0] = abi::add_event_handler as *const () as usize;
+            s.dispatcher_vtable[1] = abi::remove_event_handler as *const () as usize;
+            s.connect_dispatcher_vtable[0] = abi::add_connect_handler as *const () as usize;
+            s.connect_dispatcher_vtable[1] = abi::remove_connect_handler as *const () as usize;
+            s.timers_vtable[TIMERS_CREATE] = abi::create_timer as *const () as usize;
+            s.timer_vtable[TIMER_KILL] = abi::kill_timer as *const () as usize;
+            s.script_vtable[SCRIPT_GET_AMX] = abi::get_amx as *const () as usize;
+            s.pool_vtable[POOL_CONNECT_DISPATCHER] =
+                abi::get_connect_dispatcher as *const () as usize;
+            s.entity_vtable[ENTITY_GET_ID] = abi::get_id as *const () as usize;
+
+            s.core.primary = Object {
+                vtable: s.core_vtable.as_ptr(),
+                server: state,
+            };
+            s.core.logger = Object {
+                vtable: s.logger_vtable.as_ptr(),
+                server: state,
+            };
+            s.components = Object {
+                vtable: s.list_vtable.as_ptr(),
+                server: state,
+            };
+            s.pawn = Object {
+                vtable: s.pawn_vtable.as_ptr(),
+                server: state,
+            };
+            s.dispatcher = Object {
+                vtable: s.dispatcher_vtable.as_ptr(),
+                server: state,
+            };
+            s.timers = Object {
+                vtable: s.timers_vtable.as_ptr(),
+                server: state,
+            };
+            s.players = Object {
+                vtable: s.pool_vtable.as_ptr(),
+                server: state,
+            };
+            s.connect_dispatcher = Object {
+                vtable: s.connect_dispatcher_vtable.as_ptr(),
+                server: state,
+            };
+        }
+
+        MockServer {
+            // SAFETY: `Box::into_raw` never returns null.
+            state: unsafe { NonNull::new_unchecked(state) },
+            component: std::ptr::null_mut(),
+        }
+    }
+
+    fn state(&self) -> &ServerState {
+        // SAFETY: `state` lives until `drop`; callbacks only take shared references.
+        unsafe { self.state.as_ref() }
+    }
+
+    /// The `ICore*` passed to `onLoad`.
+    #[must_use]
+    pub fn core(&self) -> *mut ICore {
+        (&raw const self.state().core).cast_mut().cast::<ICore>()
+    }
+
+    /// The `IComponentList*` passed to `onInit`.
+    #[must_use]
+    pub fn component_list(&self) -> *mut IComponentList {
+        (&raw const self.state().components)
+            .cast_mut()
+            .cast::<IComponentList>()
+    }
+
+    /// Calls the component's `ComponentEntryPoint`, then `onLoad(core)`.
+    /// Returns the component, whose `uid` can be checked.
+    ///
+    /// # Panics
+    /// If a component is already loaded or the entry point returns null.
+    pub fn load(&mut self, entry_point: extern "C" fn() -> *mut OmpComponent) -> *mut OmpComponent {
+        assert!(self.component.is_null(), "a component is already loaded");
+        let component = entry_point();
+        assert!(!component.is_null(), "ComponentEntryPoint returned null");
+        self.component = component;
+        let core = self.core();
+        // SAFETY: `component` came from the entry point and is live until `free`.
+        unsafe { (self.vtable().on_load)(component, core) };
+        component
+    }
+
+    /// Calls `onInit(components)`.
+    pub fn init(&mut self) {
+        let components = self.component_list();
+        // SAFETY: see `load`.
+        unsafe { (self.vtable().on_init)(self.component, components) };
+    }
+
+    /// Makes `getAmxFunctions()` available, then calls `onReady()`.
+    pub fn ready(&mut self) {
+        self.state().amx_functions.set(true);
+        // SAFETY: see `load`.
+        unsafe {
+            #[cfg(not(target_env = "msvc"))]
+            (self.vtable().on_ready)(self.component);
+            // MSVC: `this` travels in ECX and the slot takes no parameters.
+            #[cfg(target_env = "msvc")]
+            (self.vtable().on_ready)();
+        }
+    }
+
+    /// Calls `onFree(pawn)` — the Pawn component going away first, as on
+    /// server shutdown — then `free()`. The component is released afterwards.
+    pub fn free(&mut self) {
+        let vtable = self.vtable();
+        let pawn = (&raw const self.state().pawn)
+            .cast_mut()
+            .cast::<OmpComponent>();
+        // SAFETY: see `load`; `free` is the last call the component receives.
+        unsafe {
+            (vtable.on_free)(self.component, pawn);
+            #[cfg(not(target_env = "msvc"))]
+            (vtable.free)(self.component);
+            #[cfg(target_env = "msvc")]
+            (vtable.free)();
+        }
+        self.component = std::ptr::null_mut();
+    }
+
+    /// Loads `vm` as the gamemode — the script `mainScript()` returns from
+    /// now on — and fires `onAmxLoad` on every registered `PawnEventHandler`.
+    pub fn load_script(&mut self, vm: &TestVm) {
+        self.state().main_script.set(vm.as_ptr());
+        self.add_script(vm);
+    }
+
+    /// Loads `vm` as a side script (a filterscript, in open.mp's terms) and
+    /// fires `onAmxLoad` on every registered `PawnEventHandler`.
+    pub fn load_side_script(&mut self, vm: &TestVm) {
+        self.add_script(vm);
+    }
+
+    fn add_script(&mut self, vm: &TestVm) {
+        let state = self.state();
+        let mut script = Box::new(Script {
+            vtable: state.script_vtable.as_ptr(),
+            amx: vm.as_ptr(),
+        });
+        let ptr = (&raw mut *script).cast::<IPawnScript>();
+        state.scripts.borrow_mut().push(script);
+        for handler in self.handlers() {
+            // SAFETY: handlers stay registered (and alive) until removed.
+            unsafe { (handler_vtable(handler).on_amx_load)(handler, ptr) };
+        }
+    }
+
+    /// Fires `onAmxUnload` on every registered `PawnEventHandler`.
+    ///
+    /// # Panics
+    /// If `vm` was not passed to [`load_script`](Self::load_script) or
+    /// [`load_side_script`](Self::load_side_script).
+    pub fn unload_script(&mut self, vm: &TestVm) {
+        let state = self.state();
+        let script = {
+            let mut scripts = state.scripts.borrow_mut();
+            let index = scripts
+                .iter()
+                .position(|s| s.amx == vm.as_ptr())
+                .expect("script was not loaded");
+            scripts.remove(index)
+        };
+        if state.main_script.get() == vm.as_ptr() {
+            state.main_script.set(std::ptr::null_mut());
+        }
+        let ptr = (&raw const *script).cast_mut().cast::<IPawnScript>();
+        for handler in self.handlers() {
+            // SAFETY: see `load_script`.
+            unsafe { (handler_vtable(handler).on_amx_unload)(handler, ptr) };
+        }
+    }
+
+    /// Connects player `id`: fires `onPlayerConnect` on every registered
+    /// `PlayerConnectEventHandler`.
+    pub fn connect_player(&mut self, id: i32) {
+        let state = self.state();
+        let mut player = Box::new(Player {
+            vtable: std::ptr::null(),
+            entity: Entity {
+                vtable: state.entity_vtable.as_ptr(),
+                id,
+            },
+        });
+        let ptr = (&raw mut *player).cast::<IPlayer>();
+        state.connected.borrow_mut().push(player);
+        for handler in self.connect_handler_ptrs() {
+            // SAFETY: handlers stay registered (and alive) until removed.
+            unsafe { (connect_handler_vtable(handler).on_player_connect)(handler, ptr) };
+        }
+    }
+
+    /// Disconnects player `id` for `reason` (a `PeerDisconnectReason`, as
+    /// Pawn's `OnPlayerDisconnect` receives it): fires `onPlayerDisconnect`
+    /// on every registered `PlayerConnectEventHandler`.
+    ///
+    /// # Panics
+    /// If `id` was not passed to [`connect_player`](Self::connect_player).
+    pub fn disconnect_player(&mut self, id: i32, reason: i32) {
+        let state = self.state();
+        let player = {
+            let mut connected = state.connected.borrow_mut();
+            let index = connected
+                .iter()
+                .position(|p| p.entity.id == id)
+                .expect("player is not connected");
+            connected.remove(index)
+        };
+        let ptr = (&raw const *player).cast_mut().cast::<IPlayer>();
+        for handler in self.connect_handler_ptrs() {
+            // SAFETY: see `connect_player`.
+            unsafe {
+                (connect_handler_vtable(handler).on_player_disconnect)(handler, ptr, reason);
+            };
+        }
+    }
+
+    /// Fires `timeout` once on every timer that was not killed.
+    pub fn tick(&mut self) {
+        let live: Vec<(*mut TimerTimeOutHandler, *mut ITimer)> = self
+            .state()
+            .created_timers
+            .borrow()
+            .iter()
+            .filter(|t| !t.handler.get().is_null())
+            .map(|t| {
+                (
+                    t.handler.get(),
+                    (&raw const **t).cast_mut().cast::<ITimer>(),
+                )
+            })
+            .collect();
+        for (handler, timer) in live {
+            // SAFETY: the handler stays alive until `kill` hands it to `free`.
+            unsafe { ((*(*handler).vtable).timeout)(handler, timer) };
+        }
+    }
+
+    /// Number of `PawnEventHandler`s currently registered on the dispatcher.
+    #[must_use]
+    pub fn event_handlers(&self) -> usize {
+        self.state().handlers.borrow().len()
+    }
+
+    /// Number of `PlayerConnectEventHandler`s currently registered on the
+    /// player pool.
+    #[must_use]
+    pub fn connect_handlers(&self) -> usize {
+        self.state().connect_handlers.borrow().len()
+    }
+
+    /// Every timer created so far, in creation order.
+    #[must_use]
+    pub fn timers(&self) -> Vec<MockTimer> {
+        self.state()
+            .created_timers
+            .borrow()
+            .iter()
+            .map(|t| MockTimer {
+                interval_ms: t.interval_ms,
+                repeating: t.repeating,
+                killed: t.handler.get().is_null(),
+            })
+            .collect()
+    }
+
+    /// Drains the lines logged through `ICore`'s `ILogger`. Lines logged
+    /// with a level carry the server's prefix (`[Info] ...`).
+    #[must_use]
+    pub fn take_log(&self) -> Vec<String> {
+        std::mem::take(&mut *self.state().log.borrow_mut())
+    }
+
+    fn vtable(&self) -> &'static IComponentVTable {
+        assert!(!self.component.is_null(), "no component loaded");
+        // SAFETY: the component's first field is its vtable pointer, which
+        // points at a `static` in the plugin.
+        unsafe { &**self.component.cast::<*const IComponentVTable>() }
+    }
+
+    fn handlers(&self) -> Vec<*mut PawnEventHandler> {
+        self.state().handlers.borrow().clone()
+    }
+
+    fn connect_handler_ptrs(&self) -> Vec<*mut PlayerConnectEventHandler> {
+        self.state().connect_handlers.borrow().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        // SAFETY: `state` came from `Box::into_raw` in `new` and is only freed here.
+        drop(unsafe { Box::from_raw(self.state.as_ptr()) });
+    }
+}
+
+/// Vtable of a `PawnEventHandler` (its first field).
+unsafe fn handler_vtable(handler: *mut PawnEventHandler) -> &'static PawnEventHandlerVTable {
+    unsafe { &**handler.cast::<*const PawnEventHandlerVTable>() }
+}
+
+/// Vtable of a `PlayerConnectEventHandler` (its first field).
+unsafe fn connect_handler_vtable(
+    handler: *mut PlayerConnectEventHandler,
+) -> &'static PlayerConnectEventHandlerVTable {
+    unsafe { &**handler.cast::<*const PlayerConnectEventHandlerVTable>() }
+}
+
+unsafe fn server<'a>(this: *const Object) -> &'a ServerState {
+    unsafe { &*(*this).server }
+}
+
+unsafe fn record(
+    this: *const Object,
+    level: Option<c_int>,
+    fmt: *const c_char,
+    arg: *const c_char,
+) {
+    // The SDK always logs through `"%s"` with the message as the only argument.
+    let message = if fmt.is_null() || unsafe { CStr::from_ptr(fmt) } != c"%s" || arg.is_null() {
+        fmt
+    } else {
+        arg
+    };
+    if message.is_null() {
+        return;
+    }
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    let line = match level.and_then(|l| LOG_LEVELS.get(usize::try_from(l).ok()?)) {
+        Some(prefix) => format!("[{prefix}] {message}"),
+        None => message.into_owned(),
+    };
+    unsafe { server(this) }.log.borrow_mut().push(line);
+}
+
+/// `printLn(fmt, ...)` / `printLnU8(fmt, ...)`.
+unsafe extern "C" fn print_ln(this: *mut Object, fmt: *const c_char, arg: *const c_char) {
+    unsafe { record(this, None, fmt, arg) };
+}
+
+/// `logLn(level, fmt, ...)` / `logLnU8(level, fmt, ...)`.
+unsafe extern "C" fn log_ln(
+    this: *mut Object,
+    level: c_int,
+    fmt: *const c_char,
+    arg: *const c_char,
+) {
+    unsafe { record(this, Some(level), fmt, arg) };
+}
+
+/// Server methods, compiled once per ABI — only the calling convention changes.
+macro_rules! server_methods {
+    ($abi:literal) => {
+        pub(super) unsafe extern $abi fn query_component(this: *mut Object, uid: UID) -> *mut Object {
+            let s = unsafe { server(this) };
+            let found = match uid {
+                PAWN_COMPONENT_UID => &s.pawn,
+                TIMERS_COMPONENT_UID => &s.timers,
+                _ => return std::ptr::null_mut(),
+            };
+            (&raw const *found).cast_mut()
+        }
+
+        pub(super) unsafe extern $abi fn get_event_dispatcher(this: *mut Object) -> *mut Object {
+            (&raw const unsafe { server(this) }.dispatcher).cast_mut()
+        }
+
+        pub(super) unsafe extern $abi fn get_amx_functions(this: *const Object) -> *const usize {
+            if unsafe { server(this) }.amx_functions.get() {
+                exports::omp_table() as *const usize
+            } else {
+                std::ptr::null()
+            }
+        }
+
+        pub(super) unsafe extern $abi fn main_script(this: *mut Object) -> *mut Script {
+            let s = unsafe { server(this) };
+            let main = s.main_script.get();
+            s.scripts
+                .borrow()
+                .iter()
+                .find(|script| !main.is_null() && script.amx == main)
+                .map_or(std::ptr::null_mut(), |script| (&raw const **script).cast_mut())
+        }
+
+        pub(super) unsafe extern $abi fn add_event_handler(
+            this: *mut Object,
+            handler: *mut PawnEventHandler,
+            _priority: i8,
+        ) -> bool {
+            let mut handlers = unsafe { server(this) }.handlers.borrow_mut();
+            if handler.is_null() || handlers.contains(&handler) {
+                return false;
+            }
+            handlers.push(handler);
+            true
+        }
+
+        pub(super) unsafe extern $abi fn remove_event_handler(
+            this: *mut Object,
+            handler: *mut PawnEventHandler,
+        ) -> bool {
+            let mut handlers = unsafe { server(this) }.handlers.borrow_mut();
+            let before = handlers.len();
+            handlers.retain(|&h| h != handler);
+            handlers.len() != before
+        }
+
+        pub(super) unsafe extern $abi fn get_players(this: *mut Object) -> *mut Object {
+            (&raw const unsafe { server(this) }.players).cast_mut()
+        }
+
+        pub(super) unsafe extern $abi fn get_connect_dispatcher(this: *mut Object) -> *mut Object {
+            (&raw const unsafe { server(this) }.connect_dispatcher).cast_mut()
+        }
+
+        pub(super) unsafe extern $abi fn add_connect_handler(
+            this: *mut Object,
+            handler: *mut PlayerConnectEventHandler,
+            _priority: i8,
+        ) -> bool {
+            let mut handlers = unsafe { server(this) }.connect_handlers.borrow_mut();
+            if handler.is_null() || handlers.contains(&handler) {
+                return false;
+            }
+            handlers.push(handler);
+            true
+        }
+
+        pub(super) unsafe extern $abi fn remove_connect_handler(
+            this: *mut Object,
+            handler: *mut PlayerConnectEventHandler,
+        ) -> bool {
+            let mut handlers = unsafe { server(this) }.connect_handlers.borrow_mut();
+            let before = handlers.len();
+            handlers.retain(|&h| h != handler);
+            handlers.len() != before
+        }
+
+        pub(super) unsafe extern $abi fn get_id(this: *mut Entity) -> c_int {
+            unsafe { (*this).id }
+        }
+
+        pub(super) unsafe extern $abi fn create_timer(
+            this: *mut Object,
+            handler: *mut TimerTimeOutHandler,
+            interval_ms: i64,
+            repeating: bool,
+        ) -> *mut Timer {
+            let s = unsafe { server(this) };
+            let mut timer = Box::new(Timer {
+                vtable: s.timer_vtable.as_ptr(),
+                handler: Cell::new(handler),
+                interval_ms,
+                repeating,
+            });
+            let ptr = &raw mut *timer;
+            s.created_timers.borrow_mut().push(timer);
+            ptr
+        }
+
+        /// Stops the timer and hands its handler back through `free`.
+        pub(super) unsafe extern $abi fn kill_timer(this: *mut Timer) {
+            let handler = unsafe { &*this }.handler.replace(std::ptr::null_mut());
+            if !handler.is_null() {
+                unsafe { ((*(*handler).vtable).free)(handler, this.cast::<ITimer>()) };
+            }
+        }
+
+        pub(super) unsafe extern $abi fn get_amx(this: *mut Script) -> *mut AMX {
+            unsafe { (*this).amx }
+        }
+    };
+}
+
+#[cfg(not(target_env = "msvc"))]
+mod abi {
+    use super::*;
+    server_methods!("C");
+}
+
+#[cfg(target_env = "msvc")]
+mod abi {
+    use super::*;
+    server_methods!("thiscall");
+}
+
+const _: () = assert!(std::mem::offset_of!(Core, logger) == LOGGER_OFFSET);
+const _: () = assert!(std::mem::offset_of!(Player, entity) == size_of::<usize>());
@@ -0,0 +1,146 @@
+//! Opcode numbers of the Pawn 3.x abstract machine (file version 8 — the
+//! format produced for SA-MP and open.mp). Values identical to the `OP_*`
+//! enum in `amx.c`; the obsolete debug opcodes (`FILE`, `LINE`, `SYMBOL`,
+//! `SRANGE`, `SYMTAG`) are listed for completeness but rejected by the
+//! interpreter, as modern compilers never emit them.
+//!
+//! Used by [`super::ImageBuilder`] to hand-assemble test scripts.
+
+pub const NONE: i32 = 0;
+pub const LOAD_PRI: i32 = 1;
+pub const LOAD_ALT: i32 = 2;
+pub const LOAD_S_PRI: i32 = 3;
+pub const LOAD_S_ALT: i32 = 4;
+pub const LREF_PRI: i32 = 5;
+pub const LREF_ALT: i32 = 6;
+pub const LREF_S_PRI: i32 = 7;
+pub const LREF_S_ALT: i32 = 8;
+pub const LOAD_I: i32 = 9;
+pub const LODB_I: i32 = 10;
+pub const CONST_PRI: i32 = 11;
+pub const CONST_ALT: i32 = 12;
+pub const ADDR_PRI: i32 = 13;
+pub const ADDR_ALT: i32 = 14;
+pub const STOR_PRI: i32 = 15;
+pub const STOR_ALT: i32 = 16;
+pub const STOR_S_PRI: i32 = 17;
+pub const STOR_S_ALT: i32 = 18;
+pub const SREF_PRI: i32 = 19;
+pub const SREF_ALT: i32 = 20;
+pub const SREF_S_PRI: i32 = 21;
+pub const SREF_S_ALT: i32 = 22;
+pub const STOR_I: i32 = 23;
+pub const STRB_I: i32 = 24;
+pub const LIDX: i32 = 25;
+pub const LIDX_B: i32 = 26;
+pub const IDXADDR: i32 = 27;
+pub const IDXADDR_B: i32 = 28;
+pub const ALIGN_PRI: i32 = 29;
+pub const ALIGN_ALT: i32 = 30;
+pub const LCTRL: i32 = 31;
+pub const SCTRL: i32 = 32;
+pub const MOVE_PRI: i32 = 33;
+pub const MOVE_ALT: i32 = 34;
+pub const XCHG: i32 = 35;
+pub const PUSH_PRI: i32 = 36;
+pub const PUSH_ALT: i32 = 37;
+pub const PUSH_R: i32 = 38;
+pub const PUSH_C: i32 = 39;
+pub const PUSH: i32 = 40;
+pub const PUSH_S: i32 = 41;
+pub const POP_PRI: i32 = 42;
+pub const POP_ALT: i32 = 43;
+pub const STACK: i32 = 44;
+pub const HEAP: i32 = 45;
+pub const PROC: i32 = 46;
+pub const RET: i32 = 47;
+pub const RETN: i32 = 48;
+pub const CALL: i32 = 49;
+pub const CALL_PRI: i32 = 50;
+pub const JUMP: i32 = 51;
+pub const JREL: i32 = 52;
+pub const JZER: i32 = 53;
+pub const JNZ: i32 = 54;
+pub const JEQ: i32 = 55;
+pub const JNEQ: i32 = 56;
+pub const JLESS: i32 = 57;
+pub const JLEQ: i32 = 58;
+pub const JGRTR: i32 = 59;
+pub const JGEQ: i32 = 60;
+pub const JSLESS: i32 = 61;
+pub const JSLEQ: i32 = 62;
+pub const JSGRTR: i32 = 63;
+pub const JSGEQ: i32 = 64;
+pub const SHL: i32 = 65;
+pub const SHR: i32 = 66;
+pub const SSHR: i32 = 67;
+pub const SHL_C_PRI: i32 = 68;
+pub const SHL_C_ALT: i32 = 69;
+pub const SHR_C_PRI: i32 = 70;
+pub const SHR_C_ALT: i32 = 71;
+pub const SMUL: i32 = 72;
+pub const SDIV: i32 = 73;
+pub const SDIV_ALT: i32 = 74;
+pub const UMUL: i32 = 75;
+pub const UDIV: i32 = 76;
+pub const UDIV_ALT: i32 = 77;
+pub const ADD: i32 = 78;
+pub const SUB: i32 = 79;
+pub const SUB_ALT: i32 = 80;
+pub const AND: i32 = 81;
+pub const OR: i32 = 82;
+pub const XOR: i32 = 83;
+pub const NOT: i32 = 84;
+pub const NEG: i32 = 85;
+pub const INVERT: i32 = 86;
+pub const ADD_C: i32 = 87;
+pub const SMUL_C: i32 = 88;
+pub const ZERO_PRI: i32 = 89;
+pub const ZERO_ALT: i32 = 90;
+pub const ZERO: i32 = 91;
+pub const ZERO_S: i32 = 92;
+pub const SIGN_PRI: i32 = 93;
+pub const SIGN_ALT: i32 = 94;
+pub const EQ: i32 = 95;
+pub const NEQ: i32 = 96;
+pub const LESS: i32 = 97;
+pub const LEQ: i32 = 98;
+pub const GRTR: i32 = 99;
+pub const GEQ: i32 = 100;
+pub const SLESS: i32 = 101;
+pub const SLEQ: i32 = 102;
+pub const SGRTR: i32 = 103;
+pub const SGEQ: i32 = 104;
+pub const EQ_C_PRI: i32 = 105;
+pub const EQ_C_ALT: i32 = 106;
+pub const INC_PRI: i32 = 107;
+pub const INC_ALT: i32 = 108;
+pub const INC: i32 = 109;
+pub const INC_S: i32 = 110;
+pub const INC_I: i32 = 111;
+pub const DEC_PRI: i32 = 112;
+pub const DEC_ALT: i32 = 113;
+pub const DEC: i32 = 114;
+pub const DEC_S: i32 = 115;
+pub const DEC_I: i32 = 116;
+pub const MOVS: i32 = 117;
+pub const CMPS: i32 = 118;
+pub const FILL: i32 = 119;
+pub const HALT: i32 = 120;
+pub const BOUNDS: i32 = 121;
+pub const SYSREQ_PRI: i32 = 122;
+pub const SYSREQ_C: i32 = 123;
+pub const FILE: i32 = 124;
+pub const LINE: i32 = 125;
+pub const SYMBOL: i32 = 126;
+pub const SRANGE: i32 = 127;
+pub const JUMP_PRI: i32 = 128;
+pub const SWITCH: i32 = 129;
+pub const CASETBL: i32 = 130;
+pub const SWAP_PRI: i32 = 131;
+pub const SWAP_ALT: i32 = 132;
+pub const PUSH_ADR: i32 = 133;
+pub const NOP: i32 = 134;
+pub const SYSREQ_D: i32 = 135;
+pub const SYMTAG: i32 = 136;
+pub const BREAK: i32 = 137;
@@ -5,3 +5,4 @@ mod amx_string;
 mod buffer;
 #[cfg(not(feature = "samp-only"))]
 mod omp_lifecycle;
+mod test_vm;
@@ -4,11 +4,16 @@
 //! - Layout of [`PawnEventHandlerVTable`] (the vtable WE implement
 //!   to receive `onAmxLoad`/`onAmxUnload`)
 //!
-//! Real integration tests (calls via mock `ICore`, full `on_load` → `on_init`
-//! → `on_ready`) are out of scope — they require heavy mocks and have been
-//! deferred in the ROADMAP.
+//! - The SDK bindings against [`MockServer`]'s fake `ICore`,
+//!   `IComponentList`, `IPawnComponent` and `ITimersComponent`, and the
+//!   order in which it drives a component (`on_load` → `on_init` →
+//!   `on_ready` → `on_free` → `free`)
+//!
+//! The same sequence through a real generated `ComponentEntryPoint` and the
+//! `samp` runtime is exercised by the `counter` example's tests.
 //!
 //! [`PawnEventHandlerVTable`]: crate::omp::events::PawnEventHandlerVTable
+//! [`MockServer`]: crate::test_vm::omp::MockServer
 
 use crate::omp::events::{PawnEventHandler, PawnEventHandlerVTable};
 use crate::omp::server::{IPawnScript, PAWN_COMPONENT_UID};
@@ -59,3 +64,275 @@ fn pawn_event_handler_new_stores_vtable_ptr() {
     // accessor, so we cannot assert pointer equality directly.
     let _ = handler;
 }
+
+// ---------------------------------------------------------------------------
+// MockServer
+// ---------------------------------------------------------------------------
+
+use crate::omp::core::{LogLevel, core_log_ln_u8, core_print_ln};
+use crate::omp::server::{ServerComponentList, get_amx_functions, query_component};
+use crate::omp::timers::{
+    ITimer, TIMERS_COMPONENT_UID, TimerHandlerVTable, TimerTimeOutHandler, create_repeating_timer,
+    kill_timer, query_timers_component,
+};
+use crate::test_vm::omp::{MockServer, MockTimer};
+use std::sync::Mutex;
+
+/// Serializes the tests below: the mock callbacks record into `EVENTS`.
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record(event: impl Into<String>) {
+    EVENTS.lock().unwrap().push(event.into());
+}
+
+fn take_events() -> Vec<String> {
+    std::mem::take(&mut *EVENTS.lock().unwrap())
+}
+
+fn list(server: &MockServer) -> *mut ServerComponentList {
+    server.component_list().cast::<ServerComponentList>()
+}
+
+#[test]
+fn mock_server_answers_component_queries() {
+    let server = MockServer::new();
+    let list = list(&server);
+    unsafe {
+        assert!(!query_component(list, PAWN_COMPONENT_UID).is_null());
+        assert!(!query_component(list, TIMERS_COMPONENT_UID).is_null());
+        assert!(query_component(list, 0x1234).is_null());
+        // open.mp 1.5.x: no AMX functions before onReady.
+        assert_eq!(
+            get_amx_functions(query_component(list, PAWN_COMPONENT_UID)),
+            0
+        );
+    }
+}
+
+#[test]
+fn mock_server_collects_core_log() {
+    let server = MockServer::new();
+    unsafe {
+        assert!(core_print_ln(server.core(), "plain line"));
+        assert!(core_log_ln_u8(
+            server.core(),
+            LogLevel::Warning,
+            "100% done"
+        ));
+    }
+    assert_eq!(server.take_log(), ["plain line", "[Warning] 100% done"]);
+    assert!(server.take_log().is_empty());
+}
+
+#[cfg(not(target_env = "msvc"))]
+unsafe extern "C" fn timer_timeout(_: *mut TimerTimeOutHandler, _: *mut ITimer) {
+    record("timeout");
+}
+#[cfg(not(target_env = "msvc"))]
+unsafe extern "C" fn timer_free(handler: *mut TimerTimeOutHandler, _: *mut ITimer) {
+    drop(unsafe { Box::from_raw(handler) });
+    record("free");
+}
+
+#[cfg(target_env = "msvc")]
+unsafe extern "thiscall" fn timer_timeout(_: *mut TimerTimeOutHandler, _: *mut ITimer) {
+    record("timeout");
+}
+#[cfg(target_env = "msvc")]
+unsafe extern "thiscall" fn timer_free(handler: *mut TimerTimeOutHandler, _: *mut ITimer) {
+    drop(unsafe { Box::from_raw(handler) });
+    record("free");
+}
+
+static TIMER_VTABLE: TimerHandlerVTable = TimerHandlerVTable {
+    timeout: timer_timeout,
+    free: timer_free,
+};
+
+#[test]
+fn mock_server_fires_timers_until_killed() {
+    let _guard = TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    take_events();
+
+    let mut server = MockServer::new();
+    let handler = Box::into_raw(Box::new(TimerTimeOutHandler {
+        vtable: &raw const TIMER_VTABLE,
+    }));
+    let timer = unsafe {
+        let timers = query_timers_component(list(&server));
+        create_repeating_timer(timers, handler, 5)
+    };
+    assert!(!timer.is_null());
+
+    server.tick();
+    server.tick();
+    unsafe { kill_timer(timer) };
+    server.tick();
+
+    assert_eq!(take_events(), ["timeout", "timeout", "free"]);
+    assert_eq!(
+        server.timers(),
+        [MockTimer {
+            interval_ms: 5,
+            repeating: true,
+            killed: true,
+        }]
+    );
+}
+
+/// A component written against the raw `IComponent` vtable. The MSVC
+/// layout returns `componentName`/`componentVersion` through naked asm, so
+/// this hand-written component is Itanium-only; the generated one in the
+/// `counter` example covers both.
+#[cfg(not(target_env = "msvc"))]
+mod recording {
+    use super::{record, take_events};
+    use crate::omp::component::*;
+    use crate::omp::events::{PawnEventHandler, PawnEventHandlerVTable};
+    use crate::omp::server::{
+        IPawnScript, PAWN_COMPONENT_UID, ServerComponentList, add_pawn_event_handler,
+        get_amx_from_script, get_amx_functions, get_pawn_event_dispatcher, query_component,
+        remove_pawn_event_handler,
+    };
+    use crate::omp::types::{SemanticVersion, StringView};
+    use crate::test_vm::omp::MockServer;
+    use crate::test_vm::{ImageBuilder, TestVm, opcodes as op};
+    use std::sync::atomic::{AtomicPtr, Ordering};
+
+    static LIST: AtomicPtr<ServerComponentList> = AtomicPtr::new(std::ptr::null_mut());
+    static HANDLER: AtomicPtr<PawnEventHandler> = AtomicPtr::new(std::ptr::null_mut());
+
+    fn amx_functions() -> usize {
+        let list = LIST.load(Ordering::SeqCst);
+        unsafe { get_amx_functions(query_component(list, PAWN_COMPONENT_UID)) }
+    }
+
+    unsafe extern "C" fn on_amx_load(_: *mut PawnEventHandler, script: *mut IPawnScript) {
+        let amx = unsafe { get_amx_from_script(script) };
+        record(format!(
+            "amx_load({}, exports={})",
+            !amx.is_null(),
+            amx_functions() != 0
+        ));
+    }
+    unsafe extern "C" fn on_amx_unload(_: *mut PawnEventHandler, _: *mut IPawnScript) {
+        record("amx_unload");
+    }
+    static HANDLER_VTABLE: PawnEventHandlerVTable = PawnEventHandlerVTable {
+        on_amx_load,
+        on_amx_unload,
+    };
+
+    unsafe extern "C" fn name(_: *const OmpComponent) -> StringView {
+        StringView {
+            data: b"recording".as_ptr(),
+            len: 9,
+        }
+    }
+    unsafe extern "C" fn version(_: *const OmpComponent) -> SemanticVersion {
+        SemanticVersion::new(1, 0, 0)
+    }
+    unsafe extern "C" fn on_load(_: *mut OmpComponent, core: *mut ICore) {
+        record(format!("on_load(core={})", !core.is_null()));
+    }
+    unsafe extern "C" fn on_init(_: *mut OmpComponent, components: *mut IComponentList) {
+        let list = components.cast::<ServerComponentList>();
+        LIST.store(list, Ordering::SeqCst);
+        let handler = Box::into_raw(Box::new(PawnEventHandler::new(&raw const HANDLER_VTABLE)));
+        HANDLER.store(handler, Ordering::SeqCst);
+        unsafe {
+            let dispatcher = get_pawn_event_dispatcher(query_component(list, PAWN_COMPONENT_UID));
+            add_pawn_event_handler(dispatcher, handler);
+        }
+        record(format!("on_init(exports={})", amx_functions() != 0));
+    }
+    unsafe extern "C" fn on_ready(_: *mut OmpComponent) {
+        record(format!("on_ready(exports={})", amx_functions() != 0));
+    }
+    unsafe extern "C" fn on_free(_: *mut OmpComponent, _: *mut OmpComponent) {
+        record("on_free");
+    }
+    unsafe extern "C" fn free(this: *mut OmpComponent) {
+        let handler = HANDLER.swap(std::ptr::null_mut(), Ordering::SeqCst);
+        unsafe {
+            let list = LIST.load(Ordering::SeqCst);
+            let dispatcher = get_pawn_event_dispatcher(query_component(list, PAWN_COMPONENT_UID));
+            remove_pawn_event_handler(dispatcher, handler);
+            drop(Box::from_raw(handler));
+            drop(Box::from_raw(this));
+        }
+        record("free");
+    }
+    unsafe extern "C" fn reset(_: *mut OmpComponent) {}
+
+    static VTABLE: IComponentVTable = IComponentVTable {
+        get_extension: ext_get_extension,
+        add_extension: ext_add_extension,
+        remove_extension_ptr: ext_remove_extension_ptr,
+        remove_extension_uid: ext_remove_extension_uid,
+        destructor: ext_destructor,
+        destructor_deleting: ext_destructor_deleting,
+        supported_version: comp_supported_version,
+        component_name: name,
+        component_type: comp_component_type,
+        component_version: version,
+        on_load,
+        on_init,
+        on_ready,
+        on_free,
+        provide_configuration: comp_provide_configuration,
+        free,
+        reset,
+    };
+    static UID_VTABLE: IUIDProviderVTable = IUIDProviderVTable {
+        destructor_complete: uid_destructor_noop,
+        destructor_deleting: uid_destructor_noop,
+        get_uid: uid_get_uid,
+    };
+
+    extern "C" fn entry_point() -> *mut OmpComponent {
+        Box::into_raw(Box::new(OmpComponent::new(&VTABLE, &UID_VTABLE, 0xC0FFEE)))
+    }
+
+    #[test]
+    fn mock_server_drives_lifecycle_in_server_order() {
+        let _guard = super::TEST_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        take_events();
+
+        let mut image = ImageBuilder::new();
+        image
+            .public("Test")
+            .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        let vm = TestVm::from_bytes(&image.build()).unwrap();
+
+        let mut server = MockServer::new();
+        let component = server.load(entry_point);
+        assert_eq!(unsafe { (*component).uid }, 0xC0FFEE);
+        server.init();
+        assert_eq!(server.event_handlers(), 1);
+        server.load_script(&vm);
+        server.ready();
+        server.unload_script(&vm);
+        server.free();
+        assert_eq!(server.event_handlers(), 0);
+
+        assert_eq!(
+            take_events(),
+            [
+                "on_load(core=true)",
+                "on_init(exports=false)",
+                "amx_load(true, exports=false)",
+                "on_ready(exports=true)",
+                "amx_unload",
+                "on_free",
+                "free",
+            ]
+        );
+    }
+}
@@ -0,0 +1,987 @@
+//! End-to-end tests of the [`test_vm`](crate::test_vm) interpreter driven
+//! through the public `Amx` API — the same path a plugin takes on a server.
+
+use crate::amx::{Amx, ScratchAmx};
+use crate::args::Args;
+use crate::cell::{AmxCell, AmxString, ArrayView, Buffer2D, Ref, UnsizedBuffer};
+use crate::consts::{AmxExecIdx, ServerData};
+use crate::error::AmxError;
+use crate::exports::Export;
+use crate::raw::functions::AmxNative;
+use crate::raw::types::{AMX, AMX_HEADER, AMX_NATIVE_INFO};
+use crate::test_vm::{ImageBuilder, TestVm, opcodes as op, server_data};
+use crate::{call_native, exec_public};
+
+use std::sync::Mutex;
+
+/// `amx_Exports` as a native sees it: through the `ppData` table.
+fn exports() -> usize {
+    unsafe { *server_data().add(ServerData::AmxExports as usize) }
+}
+
+fn native_info(name: &'static std::ffi::CStr, func: AmxNative) -> AMX_NATIVE_INFO {
+    AMX_NATIVE_INFO {
+        name: name.as_ptr(),
+        func,
+    }
+}
+
+/// `public F(a, b)` returning `pri` after applying `body` to a (pri) and b (alt).
+fn binary(body: &[i32]) -> TestVm {
+    let mut image = ImageBuilder::new();
+    image
+        .public("F")
+        .code(&[op::PROC, op::LOAD_S_PRI, 12, op::LOAD_S_ALT, 16])
+        .code(body)
+        .code(&[op::RETN]);
+    TestVm::from_bytes(&image.build()).unwrap()
+}
+
+#[test]
+fn arithmetic_and_comparisons() {
+    assert_eq!(binary(&[op::SUB]).call("F", &[10, 3]).unwrap(), 7);
+    assert_eq!(binary(&[op::SMUL]).call("F", &[-4, 6]).unwrap(), -24);
+    assert_eq!(binary(&[op::SLESS]).call("F", &[-1, 1]).unwrap(), 1);
+    // Unsigned comparison: -1 is 0xFFFFFFFF.
+    assert_eq!(binary(&[op::LESS]).call("F", &[-1, 1]).unwrap(), 0);
+    assert_eq!(binary(&[op::SSHR]).call("F", &[-8, 1]).unwrap(), -4);
+    assert_eq!(binary(&[op::SHR]).call("F", &[-8, 28]).unwrap(), 0xF);
+}
+
+#[test]
+fn division_is_floored() {
+    let vm = binary(&[op::SDIV]);
+    assert_eq!(vm.call("F", &[7, 2]).unwrap(), 3);
+    assert_eq!(vm.call("F", &[-7, 2]).unwrap(), -4);
+    assert_eq!(vm.call("F", &[7, -2]).unwrap(), -4);
+    assert_eq!(vm.call("F", &[-7, -2]).unwrap(), 3);
+
+    // The remainder lands in alt, with the sign of the divisor.
+    let vm = binary(&[op::SDIV, op::MOVE_PRI]);
+    assert_eq!(vm.call("F", &[-7, 2]).unwrap(), 1);
+    assert_eq!(vm.call("F", &[7, -2]).unwrap(), -1);
+}
+
+#[test]
+fn divide_by_zero_is_reported() {
+    let vm = binary(&[op::SDIV]);
+    assert!(matches!(vm.call("F", &[1, 0]), Err(AmxError::Divide)));
+    // The failed call leaves the VM usable.
+    assert_eq!(vm.call("F", &[9, 3]).unwrap(), 3);
+}
+
+#[test]
+fn recursion_through_call() {
+    // Fact(n) = n <= 1 ? 1 : n * Fact(n - 1)
+    let mut image = ImageBuilder::new();
+    let fact = image.here();
+    image
+        .public("Fact")
+        .code(&[op::PROC, op::LOAD_S_PRI, 12, op::CONST_ALT, 1, op::JSGRTR]);
+    let recurse_patch = image.here();
+    image.code(&[0, op::CONST_PRI, 1, op::RETN]);
+    let recurse = image.here();
+    image.patch(recurse_patch, recurse).code(&[
+        op::LOAD_S_PRI,
+        12,
+        op::ADD_C,
+        -1,
+        op::PUSH_PRI,
+        op::PUSH_C,
+        4,
+        op::CALL,
+        fact,
+        op::LOAD_S_ALT,
+        12,
+        op::SMUL,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    assert_eq!(vm.call("Fact", &[1]).unwrap(), 1);
+    assert_eq!(vm.call("Fact", &[10]).unwrap(), 3_628_800);
+}
+
+#[test]
+fn switch_selects_case_or_default() {
+    let mut image = ImageBuilder::new();
+    image
+        .public("F")
+        .code(&[op::PROC, op::LOAD_S_PRI, 12, op::SWITCH]);
+    let table_patch = image.here();
+    image.code(&[0]);
+    let one = image.here();
+    image.code(&[op::CONST_PRI, 100, op::RETN]);
+    let two = image.here();
+    image.code(&[op::CONST_PRI, 200, op::RETN]);
+    let default = image.here();
+    image.code(&[op::CONST_PRI, -1, op::RETN]);
+    let table = image.here();
+    image
+        .patch(table_patch, table)
+        .code(&[op::CASETBL, 2, default, 1, one, 2, two]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    assert_eq!(vm.call("F", &[1]).unwrap(), 100);
+    assert_eq!(vm.call("F", &[2]).unwrap(), 200);
+    assert_eq!(vm.call("F", &[3]).unwrap(), -1);
+}
+
+#[test]
+fn main_runs_and_missing_main_is_index_error() {
+    let mut image = ImageBuilder::new();
+    image.main().code(&[op::PROC, op::CONST_PRI, 42, op::RETN]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    assert_eq!(vm.run_main().unwrap(), 42);
+
+    let vm = binary(&[]);
+    assert!(matches!(vm.run_main(), Err(AmxError::Index)));
+    assert!(matches!(vm.call("Missing", &[]), Err(AmxError::NotFound)));
+}
+
+#[test]
+fn call_public_gm_runs_in_the_gamemode() {
+    let call_public_gm = unsafe {
+        std::mem::transmute::<usize, crate::raw::functions::CallPublic>(
+            *server_data().add(ServerData::CallPublicGm as usize),
+        )
+    };
+    assert_eq!(call_public_gm(c"F".as_ptr()), 0);
+
+    let mut image = ImageBuilder::new();
+    image
+        .public("F")
+        .code(&[op::PROC, op::CONST_PRI, 7, op::RETN]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    crate::test_vm::set_gamemode(Some(&vm));
+    assert_eq!(call_public_gm(c"F".as_ptr()), 7);
+    assert_eq!(call_public_gm(c"Missing".as_ptr()), 0);
+
+    // Dropping the gamemode unloads it.
+    drop(vm);
+    assert_eq!(call_public_gm(c"F".as_ptr()), 0);
+}
+
+#[test]
+fn call_public_fs_runs_in_every_filterscript() {
+    let call_public_fs = unsafe {
+        std::mem::transmute::<usize, crate::raw::functions::CallPublic>(
+            *server_data().add(ServerData::CallPublicFs as usize),
+        )
+    };
+    let returning = |value| {
+        let mut image = ImageBuilder::new();
+        image
+            .public("G")
+            .code(&[op::PROC, op::CONST_PRI, value, op::RETN]);
+        TestVm::from_bytes(&image.build()).unwrap()
+    };
+    let first = returning(3);
+    let last = returning(4);
+    let without = binary(&[]);
+    crate::test_vm::add_filterscript(&first);
+    crate::test_vm::add_filterscript(&last);
+    crate::test_vm::add_filterscript(&without);
+    assert_eq!(call_public_fs(c"G".as_ptr()), 4);
+    assert_eq!(call_public_fs(c"Missing".as_ptr()), 0);
+
+    drop(last);
+    assert_eq!(call_public_fs(c"G".as_ptr()), 3);
+}
+
+#[test]
+fn runtime_errors_abort() {
+    // BOUNDS: index 5 against an array of 4.
+    let vm = binary(&[op::BOUNDS, 3]);
+    assert!(matches!(vm.call("F", &[5, 0]), Err(AmxError::Bounds)));
+    // LOAD.I far outside the data segment.
+    let vm = binary(&[op::LOAD_I]);
+    assert!(matches!(
+        vm.call("F", &[0x7fff_0000, 0]),
+        Err(AmxError::MemoryAccess)
+    ));
+    // HALT with a non-zero code (Pawn's `exit`).
+    let vm = binary(&[op::HALT, 1]);
+    assert!(matches!(vm.call("F", &[0, 0]), Err(AmxError::Exit)));
+    // Jump into nowhere.
+    let vm = binary(&[op::JUMP, 0x10_0000]);
+    assert!(matches!(vm.call("F", &[0, 0]), Err(AmxError::MemoryAccess)));
+}
+
+#[test]
+fn unbounded_recursion_is_a_stack_error() {
+    let mut image = ImageBuilder::new();
+    let entry = image.here();
+    image
+        .public("Loop")
+        .code(&[op::PROC, op::PUSH_C, 0, op::CALL, entry, op::RETN])
+        .stack_size(256);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    let stk = amx.stack();
+    assert!(matches!(vm.call("Loop", &[]), Err(AmxError::StackError)));
+    // Registers are restored after the abort.
+    assert_eq!(amx.stack(), stk);
+}
+
+#[test]
+fn pubvars_are_shared_with_the_host() {
+    let mut image = ImageBuilder::new();
+    let addr = image.pubvar("Counter", 5);
+    image
+        .public("Get")
+        .code(&[op::PROC, op::LOAD_PRI, addr, op::RETN]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    let mut counter = amx.find_pubvar::<i32>("Counter").unwrap();
+    assert_eq!(*counter, 5);
+    *counter = 11;
+    assert_eq!(vm.call("Get", &[]).unwrap(), 11);
+}
+
+static ECHOED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+extern "C" fn record(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let args = Args::new(&amx, params);
+    let Some(text) = args.get::<AmxString>(0) else {
+        return 0;
+    };
+    ECHOED.lock().unwrap().push(text.to_string());
+    i32::try_from(text.len()).unwrap_or(-1)
+}
+
+#[test]
+fn natives_receive_strings_pushed_by_exec_public() {
+    let mut image = ImageBuilder::new();
+    let native = image.native("Record");
+    image.public("Echo").code(&[
+        op::PROC,
+        op::PUSH_S,
+        12,
+        op::PUSH_C,
+        4,
+        op::SYSREQ_C,
+        native,
+        op::STACK,
+        8,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+
+    // Natives are unbound until registered.
+    assert!(matches!(
+        exec_public!(amx, "Echo", "early" => string),
+        Err(AmxError::NotFound)
+    ));
+
+    amx.register(&[native_info(c"Record", record)]).unwrap();
+    let hea = amx.heap();
+    let len = exec_public!(amx, "Echo", "hello vm" => string).unwrap();
+    assert_eq!(len, 8);
+    let len = exec_public!(amx, "Echo", "packed strings" => packed_string).unwrap();
+    assert_eq!(len, 14);
+    assert_eq!(
+        ECHOED.lock().unwrap().as_slice(),
+        ["hello vm", "packed strings"]
+    );
+    // The allocator released the strings.
+    assert_eq!(amx.heap(), hea);
+}
+
+static NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// `Collect(const names[][6], count)`: records the first `count` names.
+extern "C" fn collect(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let args = Args::new(&amx, params);
+    let (Some(names), Some(count)) = (args.get::<ArrayView<6>>(0), args.get::<usize>(1)) else {
+        return -1;
+    };
+    let mut recorded = NAMES.lock().unwrap();
+    recorded.extend(names.strings().take(count).map(|name| name.to_string()));
+    i32::try_from(names.rows()).unwrap_or(-1)
+}
+
+#[test]
+fn exec_public_passes_string_arrays() {
+    let mut image = ImageBuilder::new();
+    let native = image.native("Collect");
+    image.public("OnNames").code(&[
+        op::PROC,
+        op::PUSH_S,
+        16,
+        op::PUSH_S,
+        12,
+        op::PUSH_C,
+        8,
+        op::SYSREQ_C,
+        native,
+        op::STACK,
+        12,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    amx.register(&[native_info(c"Collect", collect)]).unwrap();
+
+    let hea = amx.heap();
+    let names = ["Alice", "Bob", "Carol"];
+    let rows = exec_public!(amx, "OnNames", &names => strings, names.len()).unwrap();
+    assert_eq!(rows, 3);
+    assert_eq!(NAMES.lock().unwrap().as_slice(), names);
+    assert_eq!(amx.heap(), hea);
+
+    // No names still passes a (single, empty) row.
+    let none: [&str; 0] = [];
+    assert_eq!(
+        exec_public!(amx, "OnNames", &none => strings, 0).unwrap(),
+        1
+    );
+}
+
+/// `Float:Mix(value, Float:scale, const text[], const values[], count)`:
+/// `(value + strlen(text) + sum(values)) * scale`.
+extern "C" fn mix(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let args = Args::new(&amx, params);
+    let (Some(value), Some(scale), Some(text), Some(values), Some(count)) = (
+        args.get::<i32>(0),
+        args.get::<f32>(1),
+        args.get::<AmxString>(2),
+        args.get::<UnsizedBuffer>(3),
+        args.get::<usize>(4),
+    ) else {
+        return -1;
+    };
+    let values = values.into_sized_buffer(count);
+    let total = value + i32::try_from(text.len()).unwrap() + values.iter().sum::<i32>();
+    #[allow(clippy::cast_precision_loss)] // small test values
+    (total as f32 * scale).as_cell()
+}
+
+/// `bool:Fill(&value, &Float:scale, values[], count, dest[], size)`:
+/// increments `value`, doubles `scale`, multiplies `values` by 10 and
+/// writes `"filled"` into `dest`.
+extern "C" fn fill(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let args = Args::new(&amx, params);
+    let (Some(mut value), Some(mut scale), Some(values), Some(count), Some(dest), Some(size)) = (
+        args.get::<Ref<i32>>(0),
+        args.get::<Ref<f32>>(1),
+        args.get::<UnsizedBuffer>(2),
+        args.get::<usize>(3),
+        args.get::<UnsizedBuffer>(4),
+        args.get::<usize>(5),
+    ) else {
+        return 0;
+    };
+    *value += 1;
+    *scale *= 2.0;
+    let mut values = values.into_sized_buffer(count);
+    values.iter_mut().for_each(|value| *value *= 10);
+    i32::from(dest.write_str(size, "filled").is_ok())
+}
+
+#[test]
+fn call_native_marshals_arguments_and_outputs() {
+    let mut image = ImageBuilder::new();
+    image.native("Mix");
+    image.native("Fill");
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    amx.register(&[native_info(c"Mix", mix), native_info(c"Fill", fill)])
+        .unwrap();
+    let hea = amx.heap();
+
+    let values = [1, 2, 3];
+    let mixed = call_native!(amx, "Mix" => f32, 4, 0.5_f32, "abcd" => string, &values => array, 3);
+    assert_eq!(mixed.unwrap(), 7.0);
+    // Without a return type the raw cell comes back.
+    let raw =
+        call_native!(amx, "Mix", 0, 1.0_f32, "" => packed_string, &values => array, 0).unwrap();
+    assert_eq!(f32::from_bits(raw.cast_unsigned()), 0.0);
+
+    let (mut value, mut scale) = (41, 1.5_f32);
+    let mut values = [1, 2, 3];
+    let mut text = String::new();
+    let filled = call_native!(
+        amx,
+        "Fill" => bool,
+        &mut value => ref,
+        &mut scale => ref,
+        &mut values => ref_array,
+        3,
+        &mut text => ref_string(8),
+        8,
+    );
+    assert!(filled.unwrap());
+    assert_eq!((value, scale), (42, 3.0));
+    assert_eq!(values, [10, 20, 30]);
+    assert_eq!(text, "filled");
+    assert_eq!(amx.heap(), hea);
+
+    assert!(matches!(
+        call_native!(amx, "Missing", 1),
+        Err(AmxError::NotFound)
+    ));
+    assert_eq!(amx.heap(), hea);
+
+    // The script expression is evaluated once.
+    let mut evaluated = 0;
+    let mut script = || {
+        evaluated += 1;
+        vm.amx()
+    };
+    call_native!(script(), "Mix", 0, 1.0_f32, "ab" => string, &values => array, 0).unwrap();
+    assert_eq!(evaluated, 1);
+}
+
+#[test]
+fn resolved_natives_are_called_until_the_script_unloads() {
+    let mut image = ImageBuilder::new();
+    image.native("Mix");
+    let empty = image.string("");
+    let bytes = image.build();
+    let vm = TestVm::from_bytes(&bytes).unwrap();
+    let amx = vm.amx();
+    amx.register(&[native_info(c"Mix", mix)]).unwrap();
+
+    let handle = amx.resolve_native("Mix").unwrap();
+    assert_eq!(handle.index(), 0);
+    assert!(handle.is_valid());
+    let values = [1, 2, 3];
+    let mixed = call_native!(amx, handle => f32, 4, 2.0_f32, "ab" => string, &values => array, 3);
+    assert_eq!(mixed.unwrap(), 24.0);
+    // Raw cells: Mix(1, 1.0, "", {}, 0).
+    let raw = handle
+        .call(&amx, &[1, 1.0_f32.as_cell(), empty, empty, 0])
+        .unwrap();
+    assert_eq!(f32::from_bits(raw.cast_unsigned()), 1.0);
+
+    // The arguments live in the script the macro allocated them in.
+    let other = TestVm::from_bytes(&bytes).unwrap();
+    let other = other.amx();
+    assert!(matches!(
+        call_native!(other, &handle, 0, 1.0_f32, "" => string, &values => array, 0),
+        Err(AmxError::InvalidState)
+    ));
+    assert!(matches!(
+        handle.call(&other, &[]),
+        Err(AmxError::InvalidState)
+    ));
+
+    let copy = handle.clone();
+    crate::amx::invalidate_natives(&amx);
+    assert!(!handle.is_valid() && !copy.is_valid());
+    assert!(matches!(
+        handle.call(&amx, &[]),
+        Err(AmxError::InvalidState)
+    ));
+    // Resolving again gets a live handle.
+    assert!(amx.resolve_native("Mix").unwrap().is_valid());
+    assert!(matches!(
+        amx.resolve_native("Missing"),
+        Err(AmxError::NotFound)
+    ));
+}
+
+#[test]
+fn scratch_image_passes_the_checks_of_amx_init() {
+    let cells = crate::amx::ScratchAmx::DEFAULT_CELLS;
+    let image = crate::amx::scratch_image(cells).unwrap();
+    let bytes: Vec<u8> = image.iter().flat_map(|cell| cell.to_le_bytes()).collect();
+    let header = unsafe { bytes.as_ptr().cast::<AMX_HEADER>().read_unaligned() };
+
+    // What Pawn 3.2's `amx_Init` rejects: bad magic or version, a stub size
+    // other than 4 or 8, a name longer than `sNAMEMAX`, no stack, or
+    // `hea != size`. No server `amx_Init` is at hand to run it through.
+    let (size, magic, flags, defsize, hea) = (
+        header.size,
+        header.magic,
+        header.flags,
+        header.defsize,
+        header.hea,
+    );
+    let (cod, dat, stp, cip, nametable) = (
+        header.cod,
+        header.dat,
+        header.stp,
+        header.cip,
+        header.nametable,
+    );
+    assert_eq!(magic, 0xF1E0);
+    assert_eq!((header.file_version, header.amx_version), (8, 8));
+    assert_eq!(flags, 0);
+    assert_eq!(defsize, 8);
+    let name_len = usize::try_from(nametable).unwrap();
+    assert!(u16::from_le_bytes([bytes[name_len], bytes[name_len + 1]]) <= 31);
+    assert!(stp > 0);
+    assert_eq!(hea, size);
+    // The code `amx_BrowseRelocate` walks is a single `HALT 0`; there is no
+    // `main`, and heap + stack are the cells asked for.
+    let code = usize::try_from(cod).unwrap();
+    assert_eq!(&image[code / 4..code / 4 + 2], &[op::HALT, 0]);
+    assert_eq!(dat - cod, 8);
+    assert_eq!(cip, -1);
+    assert_eq!(usize::try_from(stp - hea).unwrap(), cells * 4);
+    assert_eq!(bytes.len(), usize::try_from(stp).unwrap());
+
+    // The test VM's loader accepts it; the next test runs natives on it.
+    let vm = TestVm::from_bytes(&bytes).unwrap();
+    assert!(matches!(
+        vm.amx().exec(AmxExecIdx::Main),
+        Err(AmxError::Index)
+    ));
+}
+
+#[test]
+fn host_functions_run_in_scripts_that_do_not_declare_them() {
+    // The `ScratchAmx` image: no code, publics or natives, 64 cells of heap
+    // and stack.
+    let image = crate::amx::scratch_image(64).unwrap();
+    let bytes: Vec<u8> = image.iter().flat_map(|cell| cell.to_le_bytes()).collect();
+    let vm = TestVm::from_bytes(&bytes).unwrap();
+    let amx = vm.amx();
+    assert!(matches!(amx.find_native("Mix"), Err(AmxError::NotFound)));
+    assert!(amx.bound_natives().unwrap().is_empty());
+    let hea = amx.heap();
+
+    let mix: AmxNative = mix;
+    let values = [1, 2, 3];
+    let mixed = call_native!(amx, mix => f32, 4, 0.5_f32, "abcd" => string, &values => array, 3);
+    assert_eq!(mixed.unwrap(), 7.0);
+    let long = "x".repeat(64);
+    assert!(matches!(
+        call_native!(amx, mix, 0, 1.0_f32, &long => string, &values => array, 3),
+        Err(AmxError::Memory)
+    ));
+    assert_eq!(amx.heap(), hea);
+
+    // `amx_Init` belongs to the server; the test VM does not implement it.
+    assert!(matches!(
+        ScratchAmx::new(exports(), 64),
+        Err(AmxError::Init)
+    ));
+}
+
+#[test]
+fn allocator_lays_out_two_dimensional_arrays() {
+    let vm = TestVm::from_bytes(&ImageBuilder::new().build()).unwrap();
+    let amx = vm.amx();
+    let allocator = amx.allocator();
+
+    let array = allocator
+        .allot_array_2d(&[vec![1, 2, 3], vec![4], vec![5, 6]])
+        .unwrap();
+    // The compiler's layout: decoding the vector finds the same rows.
+    let decoded = Buffer2D::new(&amx, array.as_cell(), 3).unwrap();
+    let rows: Vec<Vec<i32>> = decoded.iter().map(|row| row.to_vec()).collect();
+    assert_eq!(rows, [[1, 2, 3], [4, 0, 0], [5, 6, 0]]);
+    assert_eq!((array.rows(), array.row_len()), (3, 3));
+
+    let strings = allocator.allot_string_array(&["ab", "", "abcd"]).unwrap();
+    assert_eq!(strings.row_len(), 5);
+    let decoded: Vec<String> = strings.strings().map(|s| s.to_string()).collect();
+    assert_eq!(decoded, ["ab", "", "abcd"]);
+}
+
+#[test]
+fn register_reports_missing_natives() {
+    let mut image = ImageBuilder::new();
+    image.native("Record");
+    image.native("Other");
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    assert!(matches!(
+        amx.register(&[native_info(c"Record", record)]),
+        Err(AmxError::NotFound)
+    ));
+    assert_eq!(amx.find_native("Other").unwrap(), 1);
+    assert!(matches!(amx.find_native("Nope"), Err(AmxError::NotFound)));
+}
+
+/// Calls the public `Inner(x)` of the running script and returns its result + 1.
+extern "C" fn reenter(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let x = unsafe { *params.add(1) };
+    let index = amx.find_public("Inner").unwrap();
+    amx.push(x).unwrap();
+    amx.exec(index).unwrap() + 1
+}
+
+#[test]
+fn natives_can_reenter_the_vm() {
+    let mut image = ImageBuilder::new();
+    let native = image.native("Reenter");
+    image
+        .public("Inner")
+        .code(&[op::PROC, op::LOAD_S_PRI, 12, op::SMUL_C, 10, op::RETN]);
+    image.public("Outer").code(&[
+        op::PROC,
+        op::PUSH_S,
+        12,
+        op::PUSH_C,
+        4,
+        op::SYSREQ_C,
+        native,
+        op::STACK,
+        8,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    amx.register(&[native_info(c"Reenter", reenter)]).unwrap();
+    let (stk, hea) = (amx.stack(), amx.heap());
+    assert_eq!(vm.call("Outer", &[4]).unwrap(), 41);
+    assert_eq!((amx.stack(), amx.heap()), (stk, hea));
+}
+
+static LABELS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// `native Tally(const label[], ...)`: records the label, doubles every
+/// variadic value in place and returns the sum of the originals.
+extern "C" fn tally(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let mut args = Args::new(&amx, params);
+    let rest = args.rest();
+    let Some(label) = rest.get(0).and_then(|arg| arg.string().ok()) else {
+        return -1;
+    };
+    LABELS.lock().unwrap().push(label.to_string());
+    let mut sum = 0;
+    for arg in rest.iter().skip(1) {
+        let Ok(mut value) = arg.to_ref::<i32>() else {
+            return -1;
+        };
+        sum += arg.int().unwrap();
+        *value *= 2;
+    }
+    sum
+}
+
+#[test]
+fn variadic_arguments_are_read_and_written_by_reference() {
+    let mut image = ImageBuilder::new();
+    let native = image.native("Tally");
+    let label = image.string("totals");
+    let values = image.data(&[3, 4, 5]);
+    image.public("Run").code(&[
+        op::PROC,
+        op::PUSH_C,
+        values + 8,
+        op::PUSH_C,
+        values + 4,
+        op::PUSH_C,
+        values,
+        op::PUSH_C,
+        label,
+        op::PUSH_C,
+        16,
+        op::SYSREQ_C,
+        native,
+        op::STACK,
+        20,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    amx.register(&[native_info(c"Tally", tally)]).unwrap();
+
+    assert_eq!(vm.call("Run", &[]).unwrap(), 12);
+    assert_eq!(LABELS.lock().unwrap().as_slice(), ["totals"]);
+    let doubled: Vec<i32> = (0..3)
+        .map(|i| *amx.get_ref::<i32>(values + i * 4).unwrap())
+        .collect();
+    assert_eq!(doubled, [6, 8, 10]);
+}
+
+static FORMATTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// `native Format(const fmt[], ...)`: records `format(fmt, ...)`.
+extern "C" fn format_native(amx: *mut AMX, params: *mut i32) -> i32 {
+    let amx = Amx::new(amx, exports());
+    let mut args = Args::new(&amx, params);
+    let Some(fmt) = args.next_arg::<AmxString>() else {
+        return 0;
+    };
+    match crate::format::format(&fmt, &args.rest()) {
+        Ok(text) => {
+            FORMATTED.lock().unwrap().push(text);
+            1
+        }
+        Err(_) => 0,
+    }
+}
+
+#[test]
+fn format_reads_variadic_arguments_from_the_script() {
+    let mut image = ImageBuilder::new();
+    let native = image.native("Format");
+    let fmt = image.string("%s scored %d (%.1f%%)");
+    let name = image.string("Alice");
+    let score = image.data(&[42]);
+    let ratio = image.data(&[87.5_f32.to_bits().cast_signed()]);
+    image.public("Run").code(&[
+        op::PROC,
+        op::PUSH_C,
+        ratio,
+        op::PUSH_C,
+        score,
+        op::PUSH_C,
+        name,
+        op::PUSH_C,
+        fmt,
+        op::PUSH_C,
+        16,
+        op::SYSREQ_C,
+        native,
+        op::STACK,
+        20,
+        op::RETN,
+    ]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    vm.amx()
+        .register(&[native_info(c"Format", format_native)])
+        .unwrap();
+
+    assert_eq!(vm.call("Run", &[]).unwrap(), 1);
+    assert_eq!(
+        FORMATTED.lock().unwrap().as_slice(),
+        ["Alice scored 42 (87.5%)"]
+    );
+}
+
+/// `new names[][4]` as the Pawn compiler lays it out: the indirection
+/// vector, then the rows back to back.
+fn array_2d(rows: &[[i32; 4]]) -> Vec<i32> {
+    let count = rows.len();
+    let mut cells: Vec<i32> = (0..count)
+        .map(|i| i32::try_from((count - i + i * 4) * 4).unwrap())
+        .collect();
+    cells.extend(rows.iter().flatten());
+    cells
+}
+
+#[test]
+fn two_dimensional_arrays_decode_the_indirection_vector() {
+    let mut image = ImageBuilder::new();
+    let names = image.data(&array_2d(&[
+        [0x41, 0x6c, 0x69, 0],            // "Ali"
+        [0x42, 0x6f, 0x62, 0],            // "Bob"
+        [0x4361_726f, 0x6c00_0000, 0, 0], // "Carol", packed
+    ]));
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+
+    let view = ArrayView::<4>::from_raw(&amx, names).unwrap();
+    assert_eq!((view.rows(), view.row_len()), (3, 4));
+    let strings: Vec<String> = view.strings().map(|name| name.to_string()).collect();
+    assert_eq!(strings, ["Ali", "Bob", "Carol"]);
+    assert!(view.row(3).is_none());
+
+    // Rows are views into the script's memory.
+    view.row(1).unwrap()[0] = 0x4a;
+    let row_one = names + (3 + 4) * 4;
+    assert_eq!(*amx.get_ref::<i32>(row_one).unwrap(), 0x4a);
+    assert_eq!(view.string(1).unwrap().to_string(), "Job");
+}
+
+#[test]
+fn two_dimensional_arrays_are_bounds_checked() {
+    let mut image = ImageBuilder::new();
+    let good = image.data(&array_2d(&[[1, 2, 3, 4], [5, 6, 7, 8]]));
+    let empty = image.data(&[0]);
+    let misaligned = image.data(&[6, 0, 0]);
+    let wild = image.data(&[8, 0x7fff_0000, 0]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+
+    let rows: Vec<Vec<i32>> = Buffer2D::new(&amx, good, 4)
+        .unwrap()
+        .iter()
+        .map(|row| row.to_vec())
+        .collect();
+    assert_eq!(rows, [[1, 2, 3, 4], [5, 6, 7, 8]]);
+
+    assert!(matches!(
+        Buffer2D::new(&amx, empty, 4),
+        Err(AmxError::Params)
+    ));
+    assert!(matches!(
+        Buffer2D::new(&amx, misaligned, 4),
+        Err(AmxError::Params)
+    ));
+    // The second row's offset points far outside the data region.
+    assert!(matches!(
+        Buffer2D::new(&amx, wild, 1),
+        Err(AmxError::MemoryAccess)
+    ));
+    // Rows longer than the array reach past the end of the data region.
+    assert!(matches!(
+        ArrayView::<1_000_000>::from_raw(&amx, good),
+        Err(AmxError::MemoryAccess)
+    ));
+}
+
+#[test]
+fn strings_round_trip_packed_and_unpacked() {
+    let mut image = ImageBuilder::new();
+    let unpacked = image.string("plain");
+    let packed = image.zeroed(4);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    assert_eq!(vm.read_string(unpacked).as_deref(), Some("plain"));
+
+    let amx = vm.amx();
+    let set_string = crate::exports::SetString::from_table(vm.exports());
+    let dest = amx.get_ref::<i32>(packed).unwrap();
+    let rc = set_string(dest.as_ptr().cast_mut(), c"packed!".as_ptr(), 1, 0, 4);
+    assert_eq!(rc, 0);
+    assert!(amx.read_cell(packed).unwrap().cast_unsigned() > 0x00FF_FFFF);
+    assert_eq!(vm.read_string(packed).as_deref(), Some("packed!"));
+    assert_eq!(amx.strlen(dest.as_ptr()).unwrap(), 7);
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn scripts_and_arguments_choose_their_encoding() {
+    use crate::cell::UnsizedBuffer;
+    use crate::encoding::{self, Cp1251, EncodedString, WINDOWS_1251, WINDOWS_1252};
+
+    // "Привет" in Windows-1251.
+    let mut image = ImageBuilder::new();
+    let text = image.data(&[0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2, 0]);
+    let out = image.zeroed(4);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+
+    // A typed argument ignores whatever the script uses.
+    let typed = EncodedString::<Cp1251>::from_raw(&amx, text).unwrap();
+    assert_eq!(typed.to_string(), "Привет");
+
+    encoding::set_amx_encoding(&amx, WINDOWS_1251);
+    assert_eq!(encoding::amx_encoding(&amx), WINDOWS_1251);
+    let string = AmxString::from_raw(&amx, text).unwrap();
+    assert_eq!(&*string, "Привет");
+    assert!(string.starts_with_bytes("При"));
+    // A per-argument choice still wins over the script's.
+    let western = string.with_encoding(WINDOWS_1252);
+    assert_eq!(&*western, "Ïðèâåò");
+
+    // Strings written back to the script use its encoding too.
+    let buffer = UnsizedBuffer::from_raw(&amx, out).unwrap();
+    buffer.write_str(4, "Да").unwrap();
+    assert_eq!(amx.read_cell(out).unwrap(), 0xC4);
+    assert_eq!(amx.read_cell(out + 4).unwrap(), 0xE0);
+    let allocator = amx.allocator();
+    let pushed = allocator.allot_string("Пока").unwrap();
+    assert_eq!(pushed.to_bytes(), [0xCF, 0xEE, 0xEA, 0xE0]);
+    drop(allocator);
+
+    encoding::reset_amx_encoding(&amx);
+    assert!(encoding::amx_override(&amx).is_none());
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn struct_string_fields_use_the_script_encoding() {
+    use crate::cell::string::Codec;
+    use crate::cell::structure::{read_string, write_string};
+    use crate::cell::{AmxStruct, StructRef};
+    use crate::encoding::{self, WINDOWS_1251};
+
+    struct Named {
+        name: String,
+    }
+
+    impl AmxStruct for Named {
+        const CELLS: usize = 4;
+
+        fn read(cells: &[i32], codec: Codec) -> Self {
+            Named {
+                name: read_string(cells, codec),
+            }
+        }
+
+        fn write(&self, cells: &mut [i32], codec: Codec) {
+            write_string(cells, &self.name, codec);
+        }
+    }
+
+    // "Юля" in Windows-1251.
+    let mut image = ImageBuilder::new();
+    let data = image.data(&[0xDE, 0xEB, 0xFF, 0]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    encoding::set_amx_encoding(&amx, WINDOWS_1251);
+
+    {
+        let mut named = StructRef::<Named>::from_raw(&amx, data).unwrap();
+        assert_eq!(named.name, "Юля");
+        named.name = "Да".to_owned();
+    }
+    assert_eq!(amx.read_cell(data).unwrap(), 0xC4);
+    assert_eq!(amx.read_cell(data + 4).unwrap(), 0xE0);
+    assert_eq!(amx.read_cell(data + 8).unwrap(), 0);
+
+    encoding::reset_amx_encoding(&amx);
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn unmappable_policy_decides_what_writes_do() {
+    use crate::cell::UnsizedBuffer;
+    use crate::encoding::{self, Unmappable, WINDOWS_1252};
+
+    let mut image = ImageBuilder::new();
+    let out = image.data(&[-1; 4]);
+    let vm = TestVm::from_bytes(&image.build()).unwrap();
+    let amx = vm.amx();
+    let write = |s| {
+        UnsizedBuffer::from_raw(&amx, out)
+            .unwrap()
+            .with_encoding(WINDOWS_1252)
+            .write_str(4, s)
+    };
+    let cells = || {
+        (0..3)
+            .map(|i| amx.read_cell(out + i * 4).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    // The only test that changes the global policy; it restores the default.
+    encoding::set_unmappable_policy(Unmappable::Error);
+    assert_eq!(encoding::unmappable_policy(), Unmappable::Error);
+    assert!(matches!(write("Ж"), Err(AmxError::Unmappable)));
+    assert_eq!(cells(), [-1, -1, -1]);
+    assert!(matches!(
+        amx.allocator().allot_string("Ж"),
+        Err(AmxError::Unmappable)
+    ));
+
+    encoding::set_unmappable_policy(Unmappable::Transliterate);
+    write("Ж").unwrap();
+    assert_eq!(cells(), [i32::from(b'Z'), i32::from(b'h'), 0]);
+
+    encoding::set_unmappable_policy(Unmappable::Replace);
+    write("Ж").unwrap();
+    assert_eq!(cells(), [i32::from(b'?'), 0, 0]);
+}
+
+#[test]
+fn rejects_malformed_images() {
+    assert!(matches!(
+        TestVm::from_bytes(&[0; 16]),
+        Err(AmxError::Format)
+    ));
+    let err = TestVm::from_file("does/not/exist.amx").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn continue_is_unsupported() {
+    let vm = binary(&[]);
+    assert!(matches!(
+        vm.amx().exec(AmxExecIdx::Continue),
+        Err(AmxError::InvalidState)
+    ));
+}
@@ -1,5 +1,11 @@
 //! Re-exports the SDK's `Amx` API and adds a global registry of active
-//! instances + an opaque identity to pass between callbacks.
+//! instances + an opaque identity to pass between callbacks, plus typed
+//! per-script state ([`AmxExt::state`]) and the kind of script an instance
+//! runs ([`AmxExt::kind`]).
+
+use std::any::{Any, TypeId};
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
 
 pub use samp_sdk::amx::*;
 use samp_sdk::raw::types::AMX;
@@ -49,10 +55,53 @@ impl From<*mut AMX> for AmxIdent {
     }
 }
 
+/// What a script is to the server, as reported by [`AmxExt::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmxKind {
+    /// The gamemode: SA-MP's running `gamemodeN`, open.mp's main script.
+    Gamemode,
+    /// A SA-MP filterscript.
+    Filterscript,
+    /// A script open.mp runs besides its main script (`pawn.side_scripts`
+    /// or `loadfs`) — open.mp's counterpart of a filterscript.
+    SideScript,
+}
+
 /// Extensions over `Amx` specific to the `samp` crate (not part of the base SDK).
 pub trait AmxExt {
     /// Opaque identity of the `Amx` — useful for maps and cross references.
     fn ident(&self) -> AmxIdent;
+
+    /// Whether the script is the gamemode, a filterscript or an open.mp side
+    /// script.
+    ///
+    /// On open.mp, the gamemode is the script `IPawnComponent::mainScript()`
+    /// returns as it loads; every other script is a side script. On SA-MP,
+    /// the script is a filterscript until `OnGameModeInit` runs in it before
+    /// any `OnFilterScriptInit` does — so in `on_amx_load` too, and for good
+    /// when it declares neither.
+    fn kind(&self) -> AmxKind;
+
+    /// Per-script state of type `T`, created with `T::default()` on first
+    /// access and dropped by the SDK once the script is unloaded (after
+    /// `SampPlugin::on_amx_unload`).
+    ///
+    /// Each script holds at most one value per type — use a dedicated
+    /// struct per plugin to avoid clashing with other code in the same
+    /// plugin. Different types can be borrowed at the same time.
+    ///
+    /// # Panics
+    /// If the same `T` is already borrowed for this script — e.g. a native
+    /// holding the guard calls back into Pawn, which calls a native that
+    /// asks for the same state. Drop the guard before `exec_public!`.
+    fn state<T: Default + 'static>(&self) -> RefMut<'_, T>;
+
+    /// Takes the state of type `T` out of this script, if it was created.
+    /// The next [`state`](AmxExt::state) call starts again from `T::default()`.
+    ///
+    /// # Panics
+    /// If the state is currently borrowed through [`state`](AmxExt::state).
+    fn remove_state<T: 'static>(&self) -> Option<T>;
 }
 
 impl AmxExt for Amx {
@@ -63,4 +112,182 @@ impl AmxExt for Amx {
             .as_ptr()
             .into()
     }
+
+    fn kind(&self) -> AmxKind {
+        Runtime::get().amx_kind(self.ident())
+    }
+
+    fn state<T: Default + 'static>(&self) -> RefMut<'_, T> {
+        Runtime::get()
+            .amx_state::<T>(self.ident())
+            .try_borrow_mut()
+            .unwrap_or_else(|_| {
+                panic!(
+                    "Amx::state::<{}>() is already borrowed for this script",
+                    std::any::type_name::<T>()
+                )
+            })
+    }
+
+    fn remove_state<T: 'static>(&self) -> Option<T> {
+        Runtime::get().remove_amx_state::<T>(self.ident())
+    }
+}
+
+/// One boxed `RefCell<T>` of [`AmxStates`], with the type erased.
+trait StateSlot {
+    fn in_use(&self) -> bool;
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: 'static> StateSlot for RefCell<T> {
+    fn in_use(&self) -> bool {
+        self.try_borrow_mut().is_err()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Storage behind [`AmxExt::state`]: one value per `(script, type)`.
+///
+/// Every value is boxed, so the `RefCell` handed out by [`get_or_default`]
+/// keeps its address while other scripts or types are inserted.
+///
+/// [`get_or_default`]: AmxStates::get_or_default
+#[derive(Default)]
+pub(crate) struct AmxStates {
+    scripts: HashMap<AmxIdent, HashMap<TypeId, Box<dyn StateSlot>>>,
+}
+
+impl AmxStates {
+    /// The `T` of `ident`, inserting `T::default()` on first access.
+    pub(crate) fn get_or_default<T: Default + 'static>(&mut self, ident: AmxIdent) -> &RefCell<T> {
+        self.scripts
+            .entry(ident)
+            .or_default()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(RefCell::new(T::default())))
+            .as_any()
+            .downcast_ref()
+            .expect("AmxStates slot stored under the TypeId of another type")
+    }
+
+    /// Takes the `T` of `ident` out. Panics if it is borrowed.
+    pub(crate) fn remove<T: 'static>(&mut self, ident: AmxIdent) -> Option<T> {
+        let slots = self.scripts.get_mut(&ident)?;
+        let type_id = TypeId::of::<T>();
+        if slots.get(&type_id)?.in_use() {
+            panic!(
+                "Amx::remove_state::<{}>() called while the state is borrowed",
+                std::any::type_name::<T>()
+            );
+        }
+        let slot = slots.remove(&type_id)?;
+        if slots.is_empty() {
+            self.scripts.remove(&ident);
+        }
+        slot.into_any()
+            .downcast::<RefCell<T>>()
+            .ok()
+            .map(|cell| cell.into_inner())
+    }
+
+    /// Drops every value of `ident`. A value that is still borrowed is
+    /// leaked instead, since freeing it would leave the guard dangling;
+    /// returns how many were leaked.
+    pub(crate) fn clear(&mut self, ident: AmxIdent) -> usize {
+        let Some(slots) = self.scripts.remove(&ident) else {
+            return 0;
+        };
+        let mut leaked = 0;
+        for slot in slots.into_values() {
+            if slot.in_use() {
+                std::mem::forget(slot);
+                leaked += 1;
+            }
+        }
+        leaked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn ident(addr: usize) -> AmxIdent {
+        AmxIdent::from(addr as *mut AMX)
+    }
+
+    #[test]
+    fn state_is_created_lazily_per_script_and_type() {
+        let mut states = AmxStates::default();
+        *states.get_or_default::<i32>(ident(1)).borrow_mut() += 5;
+        states
+            .get_or_default::<String>(ident(1))
+            .borrow_mut()
+            .push('a');
+        *states.get_or_default::<i32>(ident(2)).borrow_mut() += 1;
+
+        assert_eq!(*states.get_or_default::<i32>(ident(1)).borrow(), 5);
+        assert_eq!(*states.get_or_default::<String>(ident(1)).borrow(), "a");
+        assert_eq!(*states.get_or_default::<i32>(ident(2)).borrow(), 1);
+    }
+
+    #[test]
+    fn remove_takes_the_value_out() {
+        let mut states = AmxStates::default();
+        *states.get_or_default::<i32>(ident(1)).borrow_mut() = 7;
+
+        assert_eq!(states.remove::<i32>(ident(1)), Some(7));
+        assert_eq!(states.remove::<i32>(ident(1)), None);
+        assert_eq!(*states.get_or_default::<i32>(ident(1)).borrow(), 0);
+    }
+
+    #[test]
+    fn clear_drops_only_that_script() {
+        let marker = Rc::new(());
+        let mut states = AmxStates::default();
+        *states
+            .get_or_default::<Option<Rc<()>>>(ident(1))
+            .borrow_mut() = Some(marker.clone());
+        *states
+            .get_or_default::<Option<Rc<()>>>(ident(2))
+            .borrow_mut() = Some(marker.clone());
+        assert_eq!(Rc::strong_count(&marker), 3);
+
+        assert_eq!(states.clear(ident(1)), 0);
+        assert_eq!(Rc::strong_count(&marker), 2);
+        assert!(
+            states
+                .get_or_default::<Option<Rc<()>>>(ident(1))
+                .borrow()
+                .is_none()
+        );
+        assert!(
+            states
+                .get_or_default::<Option<Rc<()>>>(ident(2))
+                .borrow()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn clear_leaks_a_borrowed_value() {
+        let mut states = AmxStates::default();
+        let cell: *const RefCell<i32> = states.get_or_default::<i32>(ident(1));
+        // SAFETY: the slot is boxed and `clear` leaks it while borrowed, so
+        // the cell stays valid for the rest of the test.
+        let guard = unsafe { &*cell }.borrow_mut();
+
+        assert_eq!(states.clear(ident(1)), 1);
+        assert_eq!(*guard, 0);
+    }
 }
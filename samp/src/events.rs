@@ -8,27 +8,60 @@
 //! public executes.
 //!
 //! The detour is installed lazily — only when the plugin registered at least one
-//! `#[event]` handler **and** the AMX function table is available. Plugins with
-//! no events never touch `amx_Exec`.
+//! `#[event]` handler (or made a [`subscribe`] call) **and** the AMX function
+//! table is available. Plugins with no events never touch `amx_Exec`.
 //!
 //! Handlers are **observers** by default: a handler returning `AmxResult<T>` /
 //! `T` has its value ignored and the gamemode's public always runs. A handler
 //! that instead returns [`EventReturn`] can cancel the callback
 //! ([`EventReturn::Suppress`]) — the original public is skipped and the supplied
 //! value is returned in its place.
+//!
+//! A handler declared with `#[event(name = "...", after)]` runs **after** the
+//! gamemode's public instead, and receives the value it returned. Returning
+//! [`EventReturn::Suppress`] from such a handler replaces that value; any other
+//! return leaves it as the gamemode set it. A callback a `before` handler
+//! suppressed never reaches the public, so its `after` handlers do not run.
+//!
+//! Besides the `#[event]` handlers fixed in `initialize_plugin!`, a closure can
+//! be attached to a callback at any time with [`subscribe`]; it stays attached
+//! until the returned [`Subscription`] is dropped.
+//!
+//! The server only runs a callback the script defines: it looks the public up
+//! with `amx_FindPublic` and skips it when the lookup fails. A handler
+//! declared with `#[event(name = "...", define)]` is also handed the callback
+//! for a script that lacks it, taken from where the server raises it:
+//!
+//! - on SA-MP, the `CallPublicFs`/`CallPublicGm` entries of the server data
+//!   passed to `Load`, detoured like `amx_Exec`. Only the argument-less
+//!   callbacks run through them reach the handlers;
+//! - on open.mp, the player pool's `PlayerConnectEventHandler` dispatcher,
+//!   for `OnPlayerConnect` and `OnPlayerDisconnect`.
+//!
+//! `amx_FindPublic` is left alone: the script still has no public of that
+//! name, and `funcidx` in it still returns `-1`.
+//!
+//! A handler runs for every script that defines its callback — the gamemode,
+//! filterscripts, open.mp side scripts. `#[event(name = "...", scope = ...)]`
+//! keeps it to the gamemode or to the other scripts ([`EventScope`]), checked
+//! against the script's [`AmxKind`] when the callback runs.
+
+use std::cell::{Cell, RefCell};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use std::ffi::{CStr, c_char};
+use std::marker::PhantomData;
+use std::rc::Rc;
 
 use samp_sdk::amx::Amx;
 use samp_sdk::args::Args;
 use samp_sdk::raw::types::AMX;
 
-use crate::amx::AmxIdent;
+use crate::amx::{AmxIdent, AmxKind};
 use crate::runtime::Runtime;
 
 // Detour machinery is x86/x86_64-only (retour supports no other arch, and
 // SA-MP/open.mp run only on 32-bit x86).
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use std::cell::RefCell;
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use std::collections::HashSet;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use std::sync::OnceLock;
@@ -39,6 +72,8 @@ use retour::GenericDetour;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use samp_sdk::consts::AmxExecIdx;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use samp_sdk::consts::ServerData;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use samp_sdk::exports::{Exec, Export};
 
 /// What the SDK does with the gamemode's public after an event handler runs.
@@ -87,16 +122,289 @@ impl EventReturn {
 /// plugin method, and reports whether to run or suppress the original public.
 pub type EventHandler = fn(&Amx, &mut Args) -> EventReturn;
 
+/// Handler wrapper generated by `#[event(after)]`.
+///
+/// Same as [`EventHandler`], plus the value the gamemode's public returned.
+/// `EventReturn::Suppress(value)` replaces that value; `Continue` keeps it.
+/// Not called when a `before` handler suppressed the public.
+pub type AfterEventHandler = fn(&Amx, &mut Args, i32) -> EventReturn;
+
+/// When a handler runs relative to the gamemode's public.
+#[derive(Debug, Clone, Copy)]
+pub enum EventHook {
+    /// Runs before the public and may skip it (`#[event]`).
+    Before(EventHandler),
+    /// Runs after the public with its return value (`#[event(after)]`).
+    After(AfterEventHandler),
+}
+
 /// Pawn callback name paired with its handler wrapper.
 ///
 /// Produced by the `__samp_event_reg_*` function that `#[event]` generates and
-/// consumed by `initialize_plugin!(events: [...])`.
-#[derive(Clone, Copy)]
+/// consumed by `initialize_plugin!(events: [...])`. Built with
+/// [`EventInfo::new`], as fields may be added.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub struct EventInfo {
     /// Pawn callback name, e.g. `"OnPlayerConnect"`.
     pub name: &'static str,
     /// Wrapper that parses arguments and dispatches into the plugin method.
-    pub handler: EventHandler,
+    pub handler: EventHook,
+    /// `priority = N` of `#[event]` (`0` when omitted). Handlers of the same
+    /// callback run highest priority first; equal priorities run in the order
+    /// of the `events: [...]` list.
+    pub priority: i32,
+    /// `define` of `#[event]`: the handler also runs for a script that does
+    /// not declare the callback, when the server raises it through a channel
+    /// the SDK listens on (see the [module docs](self)).
+    pub define: bool,
+    /// `scope = ...` of `#[event]`: the scripts the handler runs for
+    /// ([`EventScope::All`] when omitted).
+    pub scope: EventScope,
+    /// Path of the plugin method behind the handler, e.g.
+    /// `"my_plugin::on_player_connect"`. For diagnostics only.
+    pub handler_name: &'static str,
+}
+
+/// Which scripts an `#[event]` handler runs for, set with
+/// `#[event(name = "...", scope = gamemode | filterscripts | all)]`.
+///
+/// Compared against the [`AmxKind`] of the script when the callback runs, so
+/// a handler kept to the gamemode does not fire again for a filterscript
+/// that defines the same callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventScope {
+    /// Every script that defines the callback. The default.
+    All,
+    /// The gamemode only.
+    Gamemode,
+    /// Filterscripts and open.mp side scripts, not the gamemode.
+    Filterscripts,
+}
+
+impl EventInfo {
+    /// A handler of `name` with the defaults of a bare `#[event]`: priority
+    /// `0`, no `define`, [`EventScope::All`].
+    #[must_use]
+    pub const fn new(name: &'static str, handler: EventHook, handler_name: &'static str) -> Self {
+        EventInfo {
+            name,
+            handler,
+            priority: 0,
+            define: false,
+            scope: EventScope::All,
+            handler_name,
+        }
+    }
+
+    /// Sets [`priority`](EventInfo::priority).
+    #[must_use]
+    pub const fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets [`define`](EventInfo::define).
+    #[must_use]
+    pub const fn define(mut self, define: bool) -> Self {
+        self.define = define;
+        self
+    }
+
+    /// Sets [`scope`](EventInfo::scope).
+    #[must_use]
+    pub const fn scope(mut self, scope: EventScope) -> Self {
+        self.scope = scope;
+        self
+    }
+}
+
+impl EventScope {
+    /// Whether a handler of this scope runs for a script of `kind`.
+    #[must_use]
+    pub fn includes(self, kind: AmxKind) -> bool {
+        match self {
+            EventScope::All => true,
+            EventScope::Gamemode => kind == AmxKind::Gamemode,
+            EventScope::Filterscripts => kind != AmxKind::Gamemode,
+        }
+    }
+}
+
+/// The `#[event]` handlers bound to `amx`, in the order the dispatcher runs
+/// them — grouped by callback (alphabetically, the order of the script's
+/// publics table), then by priority. A handler whose callback the script does
+/// not define, or whose `scope` leaves the script out, is absent — including
+/// a `define` handler, which only runs when the callback is delivered. Meant
+/// for diagnostics:
+///
+/// ```rust,ignore
+/// for event in samp::events::handler_order(amx) {
+///     log::debug!("{} → {} (priority {})", event.name, event.handler_name, event.priority);
+/// }
+/// ```
+#[must_use]
+pub fn handler_order(amx: &Amx) -> Vec<EventInfo> {
+    let (Some(rt), Some(ptr)) = (Runtime::try_get(), amx.amx()) else {
+        return Vec::new();
+    };
+    let ident = AmxIdent::from(ptr.as_ptr());
+    let kind = rt.amx_kind(ident);
+    let mut events = rt.resolved_events_of(ident);
+    events.retain(|event| event.scope.includes(kind));
+    events
+}
+
+/// Attaches `callback` to the Pawn callback `name` until the returned
+/// [`Subscription`] is dropped.
+///
+/// Unlike an `#[event]` handler, a subscription can be made at any point of
+/// the plugin's life — from a native, when a script turns a feature on. It is
+/// resolved right away against every loaded script that defines `name`, and
+/// against every script loaded later. A callback delivered to a script for
+/// an `#[event(define)]` handler reaches it too. The closure receives the
+/// `Args` cursor unparsed, like a `raw` handler, and runs before the public; returning
+/// `EventReturn::Suppress` cancels the callback. Subscriptions run after the
+/// `#[event]` handlers of priority `0` or more and before the negative ones.
+///
+/// ```rust,ignore
+/// let watch = samp::events::subscribe("OnPlayerUpdate", move |_amx, args| {
+///     let playerid: i32 = args.next_arg().unwrap_or(-1);
+///     tracker.borrow_mut().touch(playerid);
+///     EventReturn::Continue
+/// });
+/// // ...
+/// drop(watch); // unsubscribes
+/// ```
+///
+/// Like the rest of the runtime, subscriptions belong to the main thread;
+/// `Subscription` is neither `Send` nor `Sync`.
+///
+/// # Panics
+/// If called before the plugin is initialised.
+pub fn subscribe<F>(name: &str, callback: F) -> Subscription
+where
+    F: FnMut(&Amx, &mut Args) -> EventReturn + 'static,
+{
+    let rt = Runtime::get();
+    let subscriber = Rc::new(Subscriber {
+        id: rt.next_subscription_id(),
+        name: name.to_owned(),
+        callback: RefCell::new(Box::new(callback)),
+        active: Cell::new(true),
+    });
+    rt.push_subscription(Rc::clone(&subscriber));
+
+    // Scripts already running; those loaded later resolve it in `on_amx_load`.
+    let resolved: Vec<(AmxIdent, i32)> = rt
+        .amx_list()
+        .iter()
+        .filter_map(|(ident, amx)| Some((*ident, public_index(amx, name)?)))
+        .collect();
+    for (ident, index) in resolved {
+        rt.push_resolved_event(ident, index, Resolved::Subscription(Rc::clone(&subscriber)));
+    }
+    install_exec_hook(rt.amx_exports());
+
+    Subscription {
+        id: subscriber.id,
+        _main_thread: PhantomData,
+    }
+}
+
+/// A closure attached to a callback by [`subscribe`]. Dropping it detaches the
+/// closure; a callback already running still completes without it.
+#[must_use = "dropping a Subscription unsubscribes it"]
+pub struct Subscription {
+    id: u64,
+    // Subscriptions live in the main-thread runtime.
+    _main_thread: PhantomData<*const ()>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(rt) = Runtime::try_get() {
+            rt.remove_subscription(self.id);
+        }
+    }
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+/// Closure passed to [`subscribe`].
+type SubscriberFn = Box<dyn FnMut(&Amx, &mut Args) -> EventReturn>;
+
+/// State behind a [`Subscription`], shared between the runtime and the
+/// handler lists it was resolved into.
+pub(crate) struct Subscriber {
+    pub(crate) id: u64,
+    name: String,
+    #[cfg_attr(
+        not(any(target_arch = "x86", target_arch = "x86_64")),
+        // Only the dispatchers call it, and those exist on x86 alone.
+        allow(dead_code)
+    )]
+    callback: RefCell<SubscriberFn>,
+    /// Cleared on unsubscribe, so a dispatch that already cloned the handler
+    /// list skips it.
+    active: Cell<bool>,
+}
+
+impl Subscriber {
+    pub(crate) fn deactivate(&self) {
+        self.active.set(false);
+    }
+
+    /// Runs the closure. A closure that is already running (its callback
+    /// re-entered on another script) or was unsubscribed lets the public run;
+    /// a panic is logged and does the same.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn call(&self, amx: &Amx, args: &mut Args) -> EventReturn {
+        if !self.active.get() {
+            return EventReturn::Continue;
+        }
+        let Ok(mut callback) = self.callback.try_borrow_mut() else {
+            return EventReturn::Continue;
+        };
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(amx, args)))
+            .unwrap_or_else(|_| {
+                log::error!("[{}] panic in event subscription", self.name);
+                EventReturn::Continue
+            })
+    }
+}
+
+/// One entry of a resolved handler list: an `#[event]` handler or a
+/// subscription.
+#[derive(Clone)]
+pub(crate) enum Resolved {
+    Event(EventInfo),
+    Subscription(Rc<Subscriber>),
+}
+
+impl Resolved {
+    /// Run-order key; subscriptions sit at the default priority.
+    pub(crate) fn priority(&self) -> i32 {
+        match self {
+            Resolved::Event(event) => event.priority,
+            Resolved::Subscription(_) => 0,
+        }
+    }
+
+    /// Scripts the entry runs for; subscriptions run for every script.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(crate) fn scope(&self) -> EventScope {
+        match self {
+            Resolved::Event(event) => event.scope,
+            Resolved::Subscription(_) => EventScope::All,
+        }
+    }
 }
 
 /// Signature of the VM's `amx_Exec` — `(amx, retval, public index)`.
@@ -121,15 +429,24 @@ unsafe impl Send for ExecDetour {}
 static EXEC_DETOUR: OnceLock<ExecDetour> = OnceLock::new();
 
 /// Resolves the registered events against a freshly loaded AMX and, on the
-/// first AMX that carries events, installs the `amx_Exec` detour.
+/// first AMX that carries events, installs the `amx_Exec` detour (and the
+/// `CallPublicFs`/`CallPublicGm` ones, when a handler is declared with
+/// `define`).
 ///
-/// No-op when the plugin registered no `#[event]` handlers.
+/// Without `#[event]` handlers or subscriptions, only installs the `amx_Exec`
+/// detour while a SA-MP script waits for its init public to tell its kind.
 pub(crate) fn on_amx_load(rt: &Runtime, amx: &Amx) {
     if !rt.has_events() {
+        if rt.awaits_init() {
+            install_exec_hook(rt.amx_exports());
+        }
         return;
     }
     resolve_events_for_amx(rt, amx);
     install_exec_hook(rt.amx_exports());
+    if rt.has_defined_publics() {
+        install_call_public_hooks(rt);
+    }
 }
 
 /// Drops the resolved handlers for an AMX being unloaded.
@@ -141,7 +458,7 @@ pub(crate) fn on_amx_unload(rt: &Runtime, amx_ptr: *mut AMX) {
 
 /// For each registered event, resolves its public index in `amx` (via
 /// `amx_FindPublic`) and records `(ident, index, handler)` for dispatch. A
-/// callback the gamemode does not define is simply skipped.
+/// callback the script does not define is skipped.
 fn resolve_events_for_amx(rt: &Runtime, amx: &Amx) {
     let Some(ptr) = amx.amx() else {
         return;
@@ -154,12 +471,22 @@ fn resolve_events_for_amx(rt: &Runtime, amx: &Amx) {
     rt.remove_resolved_events(ident);
 
     for event in rt.events_snapshot() {
-        if let Ok(idx) = amx.find_public(event.name) {
-            rt.push_resolved_event(ident, i32::from(idx), event.handler);
+        if let Some(idx) = public_index(amx, event.name) {
+            rt.push_resolved_event(ident, idx, Resolved::Event(event));
+        }
+    }
+    for subscriber in rt.subscriptions_snapshot() {
+        if let Some(idx) = public_index(amx, &subscriber.name) {
+            rt.push_resolved_event(ident, idx, Resolved::Subscription(subscriber));
         }
     }
 }
 
+/// Index of the public `name` in `amx`, `None` when the script lacks it.
+fn public_index(amx: &Amx, name: &str) -> Option<i32> {
+    amx.find_public(name).ok().map(i32::from)
+}
+
 /// Installs the `amx_Exec` detour from the AMX function table. Idempotent —
 /// once the `OnceLock` is set every later call short-circuits.
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -202,7 +529,7 @@ fn install_exec_hook(_fn_table: usize) {}
 
 /// Trampoline installed in place of `amx_Exec`. Dispatches to matching event
 /// handlers; a handler may suppress the gamemode's public, otherwise it runs
-/// unchanged.
+/// unchanged and `after` handlers then see (and may replace) its return value.
 ///
 /// # Safety
 /// Installed by retour as the replacement for the VM's `amx_Exec`; the server
@@ -210,20 +537,264 @@ fn install_exec_hook(_fn_table: usize) {}
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 unsafe extern "C" fn exec_detour(amx: *mut AMX, retval: *mut i32, index: i32) -> i32 {
     // A panic must never cross back into the VM's C code. On panic, fall through
-    // to the original public (no suppression).
-    let suppressed =
-        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| dispatch(amx, index)))
-            .unwrap_or(None);
+    // to the original public (no suppression, no `after` handlers).
+    let frame = std::panic::catch_unwind(|| {
+        // Settled first, so the init public's own scoped handlers see the kind.
+        if let Some(rt) = Runtime::try_get() {
+            rt.note_exec(AmxIdent::from(amx), index);
+        }
+        Frame::capture(amx, index)
+    })
+    .ok()
+    .flatten();
+    let Some(frame) = frame else {
+        // SAFETY: the server's own arguments, forwarded unchanged.
+        return unsafe { call_original(amx, retval, index) };
+    };
 
-    if let Some(value) = suppressed {
+    // `after` handlers need the return value even when the caller discards it.
+    let mut discarded = 0;
+    let out = if retval.is_null() {
+        &raw mut discarded
+    } else {
+        retval
+    };
+
+    let suppressed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| frame.run_before()))
+        .unwrap_or(None);
+
+    let status = if let Some(value) = suppressed {
         // A handler cancelled the callback: skip the original public, hand
         // `value` back as its return value, and report success (AMX_ERR_NONE).
-        if !retval.is_null() {
-            unsafe { *retval = value };
+        unsafe { *out = value };
+        0
+    } else {
+        // SAFETY: `out` is the caller's `retval` or a live local.
+        unsafe { call_original(amx, out, index) }
+    };
+
+    // A suppressed public never ran, and one that failed returned nothing
+    // worth observing.
+    if status == 0 && suppressed.is_none() {
+        let current = unsafe { *out };
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| frame.run_after(current)));
+        if let Ok(value) = result {
+            unsafe { *out = value };
+        }
+    }
+    status
+}
+
+/// Signature of the server's `CallPublicFs`/`CallPublicGm` — `(name) -> retval`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+type CallPublicFn = unsafe extern "C" fn(*const c_char) -> i32;
+
+/// Owns a live `CallPublicFs`/`CallPublicGm` detour, like [`ExecDetour`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+struct CallPublicDetour(GenericDetour<CallPublicFn>);
+
+// SAFETY: see `ExecDetour`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe impl Sync for CallPublicDetour {}
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe impl Send for CallPublicDetour {}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+static CALL_PUBLIC_FS_DETOUR: OnceLock<CallPublicDetour> = OnceLock::new();
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+static CALL_PUBLIC_GM_DETOUR: OnceLock<CallPublicDetour> = OnceLock::new();
+
+/// Installs the `CallPublicFs` and `CallPublicGm` detours from the server data
+/// SA-MP passed to `Load`. Idempotent, like `install_exec_hook`; a no-op on
+/// open.mp, which passes none.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn install_call_public_hooks(rt: &Runtime) {
+    install_call_public_hook(
+        &CALL_PUBLIC_FS_DETOUR,
+        rt.server_data(ServerData::CallPublicFs),
+        call_public_fs_detour,
+        "CallPublicFs",
+    );
+    install_call_public_hook(
+        &CALL_PUBLIC_GM_DETOUR,
+        rt.server_data(ServerData::CallPublicGm),
+        call_public_gm_detour,
+        "CallPublicGm",
+    );
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn install_call_public_hook(
+    cell: &'static OnceLock<CallPublicDetour>,
+    target: usize,
+    detour: CallPublicFn,
+    name: &str,
+) {
+    if cell.get().is_some() || target == 0 {
+        return;
+    }
+
+    // SAFETY: a non-null `CallPublicFs`/`CallPublicGm` entry is the server's
+    // function of that signature; `detour` never unwinds across the boundary.
+    let target: CallPublicFn = unsafe { std::mem::transmute(target) };
+    let detour = match unsafe { GenericDetour::new(target, detour) } {
+        Ok(detour) => detour,
+        Err(err) => {
+            log::warn!(
+                "[rust-samp] failed to build {name} detour: {err}; `define` events only fire for declared callbacks"
+            );
+            return;
+        }
+    };
+
+    let cell = cell.get_or_init(|| CallPublicDetour(detour));
+
+    // SAFETY: as in `install_exec_hook`.
+    if let Err(err) = unsafe { cell.0.enable() } {
+        log::warn!(
+            "[rust-samp] failed to enable {name} detour: {err}; `define` events only fire for declared callbacks"
+        );
+    }
+}
+
+/// Without the detour library `define` handlers never see a callback on SA-MP.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn install_call_public_hooks(_rt: &Runtime) {}
+
+/// Trampoline installed in place of `CallPublicFs`: runs the public in the
+/// filterscripts as usual, then delivers it to those that lack it.
+///
+/// # Safety
+/// Installed by retour as the replacement for the server's `CallPublicFs`;
+/// `name` is the server's NUL-terminated public name.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe extern "C" fn call_public_fs_detour(name: *const c_char) -> i32 {
+    // SAFETY: delegates to retour's preserved trampoline with the original args.
+    let retval = match CALL_PUBLIC_FS_DETOUR.get() {
+        Some(cell) => unsafe { cell.0.call(name) },
+        None => 0,
+    };
+    std::panic::catch_unwind(|| unsafe { deliver_call_public(name, retval, false) })
+        .unwrap_or(retval)
+}
+
+/// Trampoline installed in place of `CallPublicGm`: runs the public in the
+/// gamemode as usual, or delivers it when the gamemode lacks it.
+///
+/// # Safety
+/// See [`call_public_fs_detour`].
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe extern "C" fn call_public_gm_detour(name: *const c_char) -> i32 {
+    // SAFETY: delegates to retour's preserved trampoline with the original args.
+    let retval = match CALL_PUBLIC_GM_DETOUR.get() {
+        Some(cell) => unsafe { cell.0.call(name) },
+        None => 0,
+    };
+    std::panic::catch_unwind(|| unsafe { deliver_call_public(name, retval, true) })
+        .unwrap_or(retval)
+}
+
+/// Delivers the argument-less callback `name` to the gamemode, or to the
+/// filterscripts, when they lack it. `retval` is what the server's own call
+/// returned.
+///
+/// # Safety
+/// `name` must be null or a NUL-terminated C string.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn deliver_call_public(name: *const c_char, retval: i32, gamemode: bool) -> i32 {
+    let Some(rt) = Runtime::try_get() else {
+        return retval;
+    };
+    if name.is_null() {
+        return retval;
+    }
+    let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+        return retval;
+    };
+    let scripts: Vec<*mut AMX> = rt
+        .amx_list()
+        .iter()
+        .filter(|(ident, _)| (rt.amx_kind(*ident) == AmxKind::Gamemode) == gamemode)
+        .filter_map(|(_, amx)| Some(amx.amx()?.as_ptr()))
+        .collect();
+    deliver_missing(rt, name, &scripts, &[], retval)
+}
+
+/// Delivers the open.mp player callback `name` with `args` to every loaded
+/// script that lacks it. Called by the `PlayerConnectEventHandler` the SDK
+/// registers when a handler is declared with `define`.
+#[cfg(all(
+    not(feature = "samp-only"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+pub(crate) fn deliver_player_event(name: &str, args: &[i32]) {
+    let Some(rt) = Runtime::try_get() else {
+        return;
+    };
+    let scripts: Vec<*mut AMX> = rt
+        .amx_list()
+        .iter()
+        .filter_map(|(_, amx)| Some(amx.amx()?.as_ptr()))
+        .collect();
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        deliver_missing(rt, name, &scripts, args, 0)
+    }));
+}
+
+/// Handlers never run off x86, delivered or not.
+#[cfg(all(
+    not(feature = "samp-only"),
+    not(any(target_arch = "x86", target_arch = "x86_64"))
+))]
+pub(crate) fn deliver_player_event(_name: &str, _args: &[i32]) {}
+
+/// Runs the `define` handlers of `name` on each of `scripts` that does not
+/// declare it, as though its public had run with `args` and returned
+/// `retval`. Returns what the last delivery returned, `retval` when none ran.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn deliver_missing(
+    rt: &Runtime,
+    name: &str,
+    scripts: &[*mut AMX],
+    args: &[i32],
+    retval: i32,
+) -> i32 {
+    let Some(name) = rt.defined_public(name) else {
+        return retval;
+    };
+    let mut result = retval;
+    for &amx_ptr in scripts {
+        // An earlier delivery may have unloaded the script.
+        let Some(amx) = crate::amx::get(AmxIdent::from(amx_ptr)) else {
+            continue;
+        };
+        if amx.find_public(name).is_ok() {
+            continue;
         }
-        return 0;
+        let Some(frame) = Frame::delivered(rt, amx_ptr, name, args) else {
+            continue;
+        };
+        let suppressed =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| frame.run_before()))
+                .unwrap_or(None);
+        result = match suppressed {
+            Some(value) => value,
+            None => {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| frame.run_after(retval)))
+                    .unwrap_or(retval)
+            }
+        };
     }
+    result
+}
 
+/// Runs the server's `amx_Exec` through retour's preserved trampoline.
+///
+/// # Safety
+/// Same arguments the original `amx_Exec` expects.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn call_original(amx: *mut AMX, retval: *mut i32, index: i32) -> i32 {
     // SAFETY: delegates to retour's preserved trampoline with the original args.
     match EXEC_DETOUR.get() {
         Some(cell) => unsafe { cell.0.call(amx, retval, index) },
@@ -231,12 +802,22 @@ unsafe extern "C" fn exec_detour(amx: *mut AMX, retval: *mut i32, index: i32) ->
     }
 }
 
-// Tracks the `(amx, public index)` pairs currently being dispatched on this
+/// The callback a [`Frame`] dispatches, as the reentrancy set keys it.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Callback {
+    /// A public of the script, by index.
+    Public(i32),
+    /// A callback delivered to a script that does not declare it.
+    Delivered(&'static str),
+}
+
+// Tracks the `(amx, callback)` pairs currently being dispatched on this
 // thread, so a handler that re-enters the VM on the *same* public does not
 // recurse into dispatch again (which could loop unbounded).
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 thread_local! {
-    static ACTIVE: RefCell<HashSet<(usize, i32)>> = RefCell::new(HashSet::new());
+    static ACTIVE: RefCell<HashSet<(usize, Callback)>> = RefCell::new(HashSet::new());
 }
 
 /// RAII guard for the reentrancy set: [`acquire`] inserts the key (returning
@@ -245,11 +826,11 @@ thread_local! {
 ///
 /// [`acquire`]: ActiveGuard::acquire
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-struct ActiveGuard(usize, i32);
+struct ActiveGuard(usize, Callback);
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl ActiveGuard {
-    fn acquire(key: (usize, i32)) -> Option<Self> {
+    fn acquire(key: (usize, Callback)) -> Option<Self> {
         ACTIVE.with(|active| {
             active
                 .borrow_mut()
@@ -268,45 +849,170 @@ impl Drop for ActiveGuard {
     }
 }
 
-/// Core dispatch: for the public `index` being executed on `amx_ptr`, run every
-/// event handler registered for that `(amx, index)` pair, in registration order.
-///
-/// Returns `Some(value)` if a handler suppressed the callback (the first one to
-/// do so wins and the rest are skipped), `None` to run the gamemode's public.
+/// One public invocation that carries events: its handlers and the callback
+/// arguments, read off the VM stack *before* the public runs (`amx_Exec` pops
+/// them on return, so `after` handlers could not read them later). A
+/// delivered callback carries the arguments the server raised it with.
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-fn dispatch(amx_ptr: *mut AMX, index: i32) -> Option<i32> {
-    // Only user-defined publics carry gamemode callbacks; skip main/continue.
-    let AmxExecIdx::UserDef(idx) = AmxExecIdx::from(index) else {
-        return None;
-    };
-    if amx_ptr.is_null() {
-        return None;
+struct Frame {
+    amx_ptr: *mut AMX,
+    callback: Callback,
+    handlers: Vec<Resolved>,
+    params: Vec<i32>,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl Frame {
+    /// Captures the public `index` being executed on `amx_ptr`, or `None` when
+    /// no handler is registered for it (or a handler is re-entering it).
+    fn capture(amx_ptr: *mut AMX, index: i32) -> Option<Frame> {
+        // Only user-defined publics carry gamemode callbacks; skip main/continue.
+        let AmxExecIdx::UserDef(idx) = AmxExecIdx::from(index) else {
+            return None;
+        };
+        if amx_ptr.is_null() {
+            return None;
+        }
+
+        let rt = Runtime::get();
+        let ident = AmxIdent::from(amx_ptr);
+        let mut handlers = rt.resolved_handlers(ident, idx);
+        // Only a scoped handler needs the script's kind.
+        if handlers
+            .iter()
+            .any(|resolved| resolved.scope() != EventScope::All)
+        {
+            let kind = rt.amx_kind(ident);
+            handlers.retain(|resolved| resolved.scope().includes(kind));
+        }
+        if handlers.is_empty() {
+            return None;
+        }
+
+        // Reentrancy guard: a handler re-entering the same public runs it
+        // directly rather than dispatching again.
+        let _guard = ActiveGuard::acquire((amx_ptr as usize, Callback::Public(idx)))?;
+
+        let amx = crate::amx::get(ident)?;
+        let params = read_stack_params(amx_ptr, amx)?;
+        Some(Frame {
+            amx_ptr,
+            callback: Callback::Public(idx),
+            handlers,
+            params,
+        })
     }
 
-    let rt = Runtime::get();
-    let ident = AmxIdent::from(amx_ptr);
-    let handlers = rt.resolved_handlers(ident, idx);
-    if handlers.is_empty() {
-        return None;
+    /// The callback `name` delivered to `amx_ptr`, which does not declare it,
+    /// with `args`: the `define` handlers whose scope includes the script,
+    /// then the subscriptions to `name`. `None` when no `define` handler runs
+    /// for the script.
+    fn delivered(
+        rt: &Runtime,
+        amx_ptr: *mut AMX,
+        name: &'static str,
+        args: &[i32],
+    ) -> Option<Frame> {
+        let kind = rt.amx_kind(AmxIdent::from(amx_ptr));
+        let mut handlers: Vec<Resolved> = rt
+            .events_snapshot()
+            .into_iter()
+            .filter(|event| event.define && event.name == name && event.scope.includes(kind))
+            .map(Resolved::Event)
+            .collect();
+        if handlers.is_empty() {
+            return None;
+        }
+        for subscriber in rt.subscriptions_snapshot() {
+            if subscriber.name == name {
+                // After the handlers of priority `0` or more, as on a public.
+                let at = handlers.partition_point(|other| other.priority() >= 0);
+                handlers.insert(at, Resolved::Subscription(subscriber));
+            }
+        }
+
+        let mut params = Vec::with_capacity(args.len() + 1);
+        params.push(i32::try_from(args.len()).ok()?.checked_mul(4)?);
+        params.extend_from_slice(args);
+        Some(Frame {
+            amx_ptr,
+            callback: Callback::Delivered(name),
+            handlers,
+            params,
+        })
     }
 
-    // Reentrancy guard: a handler re-entering the same public runs it directly
-    // rather than dispatching again. Dropped (key cleared) on every return path,
-    // including a handler unwind.
-    let _guard = ActiveGuard::acquire((amx_ptr as usize, idx))?;
+    /// Runs every `before` handler, in run order.
+    ///
+    /// Returns `Some(value)` if a handler suppressed the callback (the first one
+    /// to do so wins and the rest are skipped), `None` to run the gamemode's
+    /// public.
+    fn run_before(&self) -> Option<i32> {
+        // Held for the handlers only (dropped on every return path, including
+        // an unwind): the public itself may still call back into its own name.
+        let _guard = ActiveGuard::acquire((self.amx_ptr as usize, self.callback))?;
+        let amx = crate::amx::get(AmxIdent::from(self.amx_ptr))?;
+
+        let mut args = Args::new(amx, self.params.as_ptr());
+        for resolved in &self.handlers {
+            // Each handler reads the same argument list from the start.
+            args.reset();
+            let result = match resolved {
+                Resolved::Event(EventInfo {
+                    handler: EventHook::Before(handler),
+                    ..
+                }) => handler(amx, &mut args),
+                Resolved::Subscription(subscriber) => subscriber.call(amx, &mut args),
+                Resolved::Event(_) => continue,
+            };
+            if let EventReturn::Suppress(value) = result {
+                return Some(value);
+            }
+        }
+        None
+    }
 
-    let amx = crate::amx::get(ident)?;
-    let params = read_stack_params(amx_ptr, amx)?;
+    /// Runs every `after` handler, in run order, and returns the value
+    /// the callback finally returns. Each handler sees the value left by the
+    /// previous one, so the last override wins.
+    fn run_after(&self, retval: i32) -> i32 {
+        if !self
+            .handlers
+            .iter()
+            .any(|resolved| after_handler(resolved).is_some())
+        {
+            return retval;
+        }
+        let Some(_guard) = ActiveGuard::acquire((self.amx_ptr as usize, self.callback)) else {
+            return retval;
+        };
+        // The public may have unloaded its own script.
+        let Some(amx) = crate::amx::get(AmxIdent::from(self.amx_ptr)) else {
+            return retval;
+        };
 
-    let mut args = Args::new(amx, params.as_ptr());
-    for handler in handlers {
-        // Each handler reads the same argument list from the start.
-        args.reset();
-        if let EventReturn::Suppress(value) = handler(amx, &mut args) {
-            return Some(value);
+        let mut args = Args::new(amx, self.params.as_ptr());
+        let mut current = retval;
+        for handler in self.handlers.iter().filter_map(after_handler) {
+            args.reset();
+            if let EventReturn::Suppress(value) = handler(amx, &mut args, current) {
+                current = value;
+            }
         }
+        current
+    }
+}
+
+/// The `after` wrapper of an `#[event(after)]` entry.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn after_handler(resolved: &Resolved) -> Option<AfterEventHandler> {
+    match resolved {
+        Resolved::Event(EventInfo {
+            handler: EventHook::After(handler),
+            ..
+        }) => Some(*handler),
+        _ => None,
     }
-    None
 }
 
 /// Rebuilds the native-style parameter table (`[byte_count, arg0, arg1, …]`)
@@ -355,12 +1061,37 @@ mod tests {
 
     #[test]
     fn event_info_is_copy_and_holds_fields() {
-        let info = EventInfo {
-            name: "OnPlayerConnect",
-            handler: handler_stub,
-        };
+        let info = EventInfo::new(
+            "OnPlayerConnect",
+            EventHook::Before(handler_stub),
+            "tests::handler_stub",
+        )
+        .priority(5)
+        .scope(EventScope::Gamemode);
         let copy = info;
         assert_eq!(copy.name, "OnPlayerConnect");
+        assert_eq!((copy.priority, copy.define), (5, false));
+        assert_eq!(copy.scope, EventScope::Gamemode);
+    }
+
+    #[test]
+    fn event_scope_matches_script_kinds() {
+        use AmxKind::{Filterscript, Gamemode, SideScript};
+        let runs_for = |scope: EventScope| {
+            [Gamemode, Filterscript, SideScript]
+                .into_iter()
+                .filter(|kind| scope.includes(*kind))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            runs_for(EventScope::All),
+            [Gamemode, Filterscript, SideScript]
+        );
+        assert_eq!(runs_for(EventScope::Gamemode), [Gamemode]);
+        assert_eq!(
+            runs_for(EventScope::Filterscripts),
+            [Filterscript, SideScript]
+        );
     }
 
     #[test]
@@ -402,4 +1133,149 @@ mod tests {
         assert_eq!(args.count(), 0);
         assert!(args.get::<Ref<i32>>(0).is_none());
     }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    static PINGS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn ping(_amx: &Amx, args: &mut Args) -> EventReturn {
+        assert_eq!(args.count(), 0);
+        PINGS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        EventReturn::Suppress(5)
+    }
+
+    // One test, as it sets up the runtime every other call here would read.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn define_handlers_run_for_scripts_lacking_the_public() {
+        use samp_sdk::consts::ServerData;
+        use samp_sdk::test_vm::{
+            ImageBuilder, TestVm, add_filterscript, opcodes as op, server_data, set_gamemode,
+        };
+        use std::sync::atomic::Ordering;
+
+        let _lock = crate::runtime::TEST_RUNTIME
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let rt = Runtime::initialize();
+        rt.set_server_exports(server_data());
+        rt.register_events(vec![
+            EventInfo::new("Ping", EventHook::Before(ping), "tests::ping").define(true),
+        ]);
+
+        let load = |image: &ImageBuilder| {
+            let vm = TestVm::from_bytes(&image.build()).unwrap();
+            let amx = rt.insert_amx(vm.as_ptr());
+            on_amx_load(rt, amx);
+            vm
+        };
+        // The gamemode has `main`, `OnGameModeInit` and no `Ping`.
+        let mut gamemode = ImageBuilder::new();
+        gamemode.main().code(&[op::PROC, op::ZERO_PRI, op::RETN]);
+        gamemode
+            .public("OnGameModeInit")
+            .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        let gamemode = load(&gamemode);
+        set_gamemode(Some(&gamemode));
+        // One filterscript declares `Ping` and returns 7, the other lacks it.
+        let mut declaring = ImageBuilder::new();
+        declaring
+            .public("Ping")
+            .code(&[op::PROC, op::CONST_PRI, 7, op::RETN]);
+        let declaring = load(&declaring);
+        let lacking = load(&ImageBuilder::new());
+        add_filterscript(&declaring);
+        add_filterscript(&lacking);
+        assert_eq!(
+            rt.amx_kind(AmxIdent::from(declaring.as_ptr())),
+            AmxKind::Filterscript
+        );
+
+        let entry = |entry: ServerData| -> CallPublicFn {
+            // SAFETY: the test VM's server data holds `CallPublicFs`/`Gm` there.
+            unsafe { std::mem::transmute(server_data().offset(entry.into()).read()) }
+        };
+        let call_public_gm = entry(ServerData::CallPublicGm);
+        let call_public_fs = entry(ServerData::CallPublicFs);
+        // Tells the gamemode apart.
+        assert_eq!(unsafe { call_public_gm(c"OnGameModeInit".as_ptr()) }, 1);
+
+        // The gamemode still has no `Ping`, yet the handler runs for it.
+        assert_eq!(unsafe { call_public_gm(c"Ping".as_ptr()) }, 5);
+        assert_eq!(PINGS.load(Ordering::Relaxed), 1);
+        assert!(matches!(
+            gamemode.call("Ping", &[]),
+            Err(samp_sdk::error::AmxError::NotFound)
+        ));
+        // The filterscript declaring it runs the handler through `amx_Exec`,
+        // the one lacking it through a delivery.
+        assert_eq!(unsafe { call_public_fs(c"Ping".as_ptr()) }, 5);
+        assert_eq!(PINGS.load(Ordering::Relaxed), 3);
+        // Callbacks without a `define` handler are left alone.
+        assert_eq!(unsafe { call_public_gm(c"Pong".as_ptr()) }, 0);
+        assert_eq!(PINGS.load(Ordering::Relaxed), 3);
+
+        set_gamemode(None);
+        rt.set_server_exports(std::ptr::null());
+    }
+
+    // One test, as it sets up the runtime every other call here would read.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn init_callbacks_tell_filterscripts_from_the_gamemode() {
+        use samp_sdk::test_vm::{ImageBuilder, TestVm, opcodes as op, server_data};
+
+        let _lock = crate::runtime::TEST_RUNTIME
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // No events: the `amx_Exec` detour is installed for classification
+        // alone.
+        let rt = Runtime::initialize();
+        rt.set_server_exports(server_data());
+
+        let load = |image: &ImageBuilder| {
+            let vm = TestVm::from_bytes(&image.build()).unwrap();
+            let amx = rt.insert_amx(vm.as_ptr());
+            on_amx_load(rt, amx);
+            vm
+        };
+        let kind = |vm: &TestVm| rt.amx_kind(AmxIdent::from(vm.as_ptr()));
+        let init = |image: &mut ImageBuilder, name: &str| {
+            image
+                .public(name)
+                .code(&[op::PROC, op::CONST_PRI, 1, op::RETN]);
+        };
+
+        // At startup SA-MP loads the filterscripts first. This one has a
+        // `main` of its own and also watches `OnGameModeInit`.
+        let mut filterscript = ImageBuilder::new();
+        filterscript
+            .main()
+            .code(&[op::PROC, op::ZERO_PRI, op::RETN]);
+        init(&mut filterscript, "OnFilterScriptInit");
+        init(&mut filterscript, "OnGameModeInit");
+        let filterscript = load(&filterscript);
+        assert_eq!(filterscript.call("OnFilterScriptInit", &[]).unwrap(), 1);
+        assert_eq!(kind(&filterscript), AmxKind::Filterscript);
+
+        // The gamemode reads as a filterscript until its `OnGameModeInit`.
+        let mut gamemode = ImageBuilder::new();
+        gamemode.main().code(&[op::PROC, op::ZERO_PRI, op::RETN]);
+        init(&mut gamemode, "OnGameModeInit");
+        let gamemode = load(&gamemode);
+        assert_eq!(kind(&gamemode), AmxKind::Filterscript);
+        assert_eq!(filterscript.call("OnGameModeInit", &[]).unwrap(), 1);
+        assert_eq!(gamemode.call("OnGameModeInit", &[]).unwrap(), 1);
+        assert_eq!(kind(&gamemode), AmxKind::Gamemode);
+        assert_eq!(kind(&filterscript), AmxKind::Filterscript);
+
+        // Without `main`, `OnGameModeInit` does not make a gamemode.
+        let mut library = ImageBuilder::new();
+        init(&mut library, "OnGameModeInit");
+        let library = load(&library);
+        assert_eq!(library.call("OnGameModeInit", &[]).unwrap(), 1);
+        assert_eq!(kind(&library), AmxKind::Filterscript);
+
+        rt.set_server_exports(std::ptr::null());
+    }
 }
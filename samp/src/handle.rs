@@ -0,0 +1,539 @@
+//! Handle tables: Rust objects exposed to Pawn as integer IDs.
+//!
+//! Pawn only holds cells, so a plugin that hands out connections, timers or
+//! compiled regexes gives the script an ID and keeps the object on its side.
+//! [`HandleTable<T>`] is that side: it stores the values, recycles freed
+//! slots and tags every ID with a generation, so an ID kept after
+//! `Close(id)` does not silently reach the object that reused the slot.
+//!
+//! ```rust,ignore
+//! use samp::handle::{Handle, HandleTable};
+//!
+//! struct Plugin {
+//!     clients: HandleTable<Client>,
+//! }
+//!
+//! impl Plugin {
+//!     #[native(name = "Client_Connect")]
+//!     fn connect(&mut self, amx: &Amx, address: &AmxString) -> i32 {
+//!         match Client::connect(&address.to_string()) {
+//!             // Closed automatically when the calling script unloads.
+//!             Ok(client) => self.clients.insert_owned(amx, client).id(),
+//!             Err(_) => -1,
+//!         }
+//!     }
+//!
+//!     // A stale or unknown ID fails argument parsing: the native logs the
+//!     // error and returns 0 without running the body.
+//!     #[native(name = "Client_Ping")]
+//!     fn ping(&mut self, _amx: &Amx, client: Handle<Client>) -> bool {
+//!         self.clients.get_mut(client).is_some_and(|mut c| c.ping().is_ok())
+//!     }
+//!
+//!     #[native(name = "Client_Close")]
+//!     fn close(&mut self, _amx: &Amx, client: Handle<Client>) -> bool {
+//!         self.clients.remove(client).is_some()
+//!     }
+//! }
+//! ```
+//!
+//! # IDs
+//!
+//! An ID packs a slot index (low 20 bits) and the slot's generation (next
+//! 11 bits). IDs are always positive, so `0` and `-1` are free to mean
+//! "no handle" in Pawn. Each removal bumps the slot's generation; after
+//! 2047 reuses of the same slot the generation wraps around and a very old
+//! ID could match again.
+//!
+//! # One table per type
+//!
+//! `Handle<T>` finds its table by type, so only one `HandleTable<T>` may be
+//! alive at a time. Wrap the value in a newtype to keep a second table of
+//! the same underlying type.
+
+use std::any::TypeId;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+use samp_sdk::amx::Amx;
+use samp_sdk::args::Args;
+use samp_sdk::cell::AmxCell;
+use samp_sdk::error::{AmxError, AmxResult};
+
+use crate::amx::{AmxExt, AmxIdent};
+use crate::macros::sdk_warn;
+use crate::tasks::AsyncArg;
+
+const INDEX_BITS: u32 = 20;
+const INDEX_MASK: u32 = (1 << INDEX_BITS) - 1;
+const GENERATION_MASK: u32 = (1 << (31 - INDEX_BITS)) - 1;
+
+/// Maximum number of live handles in one table.
+pub const MAX_HANDLES: usize = INDEX_MASK as usize + 1;
+
+thread_local! {
+    /// Live tables by value type. Natives run on the server thread, so the
+    /// registry (and the tables) never leave it.
+    static TABLES: RefCell<HashMap<TypeId, Weak<dyn Registered>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// A typed ID into the [`HandleTable<T>`] of the same `T`.
+///
+/// As a `#[native]` argument, `Handle<T>` only parses when the ID is live
+/// in the table — otherwise the native fails with a parse error and
+/// returns `0`. As a return value it becomes the raw ID.
+pub struct Handle<T> {
+    id: i32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    #[allow(clippy::cast_possible_wrap)] // generation is 11 bits: the top bit stays clear
+    fn new(index: u32, generation: u32) -> Handle<T> {
+        Handle {
+            id: ((generation << INDEX_BITS) | index) as i32,
+            _marker: PhantomData,
+        }
+    }
+
+    fn new_unchecked(id: i32) -> Handle<T> {
+        Handle {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The ID handed to Pawn. Always positive.
+    #[inline]
+    #[must_use]
+    pub fn id(self) -> i32 {
+        self.id
+    }
+
+    #[allow(clippy::cast_sign_loss)] // callers only decode positive IDs
+    fn index(self) -> usize {
+        (self.id as u32 & INDEX_MASK) as usize
+    }
+
+    #[allow(clippy::cast_sign_loss)] // callers only decode positive IDs
+    fn generation(self) -> u32 {
+        self.id as u32 >> INDEX_BITS
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({})", self.id)
+    }
+}
+
+impl<T: 'static> AmxCell<'_> for Handle<T> {
+    /// # Errors
+    /// `AmxError::Params` when no live `HandleTable<T>` holds `cell`.
+    fn from_raw(_amx: &Amx, cell: i32) -> AmxResult<Self> {
+        if is_live::<T>(cell) {
+            Ok(Handle::new_unchecked(cell))
+        } else {
+            Err(AmxError::Params)
+        }
+    }
+
+    fn as_cell(&self) -> i32 {
+        self.id
+    }
+}
+
+/// Validated on the server thread when the native is called. The value may
+/// be removed before the async body finishes — look it up again on the
+/// server thread (e.g. through `MainThread::run_with_plugin`).
+impl<T: 'static> AsyncArg for Handle<T> {
+    fn from_args(args: &mut Args<'_>) -> Option<Self> {
+        args.next_arg::<Handle<T>>()
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+    owner: Option<AmxIdent>,
+}
+
+struct Slots<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Slots<T> {
+    /// Index of the live slot `id` points to.
+    fn lookup(&self, id: i32) -> Option<usize> {
+        if id <= 0 {
+            return None;
+        }
+        let handle = Handle::<T>::new_unchecked(id);
+        let slot = self.slots.get(handle.index())?;
+        (slot.generation == handle.generation() && slot.value.is_some()).then(|| handle.index())
+    }
+
+    fn insert(&mut self, value: T, owner: Option<AmxIdent>) -> Handle<T> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|index| *index <= INDEX_MASK)
+                    .unwrap_or_else(|| panic!("HandleTable is full ({MAX_HANDLES} handles)"));
+                self.slots.push(Slot {
+                    generation: 1,
+                    value: None,
+                    owner: None,
+                });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        slot.owner = owner;
+        self.len += 1;
+        Handle::new(index, slot.generation)
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        let slot = &mut self.slots[index];
+        let value = slot.value.take()?;
+        slot.owner = None;
+        // Generation 0 is never handed out, so an ID is never 0.
+        slot.generation = (slot.generation % GENERATION_MASK) + 1;
+        self.free
+            .push(u32::try_from(index).expect("slot index fits the 20-bit index"));
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+/// Type-erased view of a table for the registry.
+trait Registered {
+    fn contains(&self, id: i32) -> bool;
+
+    /// Removes (and drops) every value owned by `ident`. `false` when the
+    /// table was borrowed and nothing could be removed.
+    fn release_owned_by(&self, ident: AmxIdent) -> bool;
+}
+
+impl<T> Registered for RefCell<Slots<T>> {
+    fn contains(&self, id: i32) -> bool {
+        self.try_borrow()
+            .is_ok_and(|slots| slots.lookup(id).is_some())
+    }
+
+    fn release_owned_by(&self, ident: AmxIdent) -> bool {
+        let Ok(mut slots) = self.try_borrow_mut() else {
+            return false;
+        };
+        let owned: Vec<usize> = slots
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.owner == Some(ident))
+            .map(|(index, _)| index)
+            .collect();
+        let released: Vec<T> = owned
+            .into_iter()
+            .filter_map(|index| slots.remove(index))
+            .collect();
+        // Drop outside the borrow: a value's `Drop` may use the table.
+        drop(slots);
+        drop(released);
+        true
+    }
+}
+
+/// Whether `id` is live in the current `HandleTable<T>`.
+fn is_live<T: 'static>(id: i32) -> bool {
+    TABLES.with(|tables| {
+        tables
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .and_then(Weak::upgrade)
+            .is_some_and(|table| table.contains(id))
+    })
+}
+
+/// Frees every handle owned by the unloaded AMX, in every table. Called by
+/// `interlayer::amx_unload`.
+pub(crate) fn on_amx_unload(ident: AmxIdent) {
+    let tables: Vec<Rc<dyn Registered>> = TABLES.with(|tables| {
+        let mut tables = tables.borrow_mut();
+        tables.retain(|_, table| table.strong_count() > 0);
+        tables.values().filter_map(Weak::upgrade).collect()
+    });
+    for table in tables {
+        if !table.release_owned_by(ident) {
+            sdk_warn!("a HandleTable was borrowed while its script unloaded; owned handles kept");
+        }
+    }
+}
+
+/// Storage for Rust values handed to Pawn as [`Handle<T>`] IDs.
+///
+/// Lives in the plugin struct. Accessors return `Ref`/`RefMut` guards; drop
+/// them before calling back into Pawn, since a nested native may look the
+/// table up again.
+///
+/// # Panics
+/// [`new`](HandleTable::new) panics if another `HandleTable<T>` for the
+/// same `T` is still alive on this thread (see the [module docs](self)).
+pub struct HandleTable<T: 'static> {
+    slots: Rc<RefCell<Slots<T>>>,
+}
+
+impl<T: 'static> HandleTable<T> {
+    /// Creates the table for `T` and registers it, so `Handle<T>` arguments
+    /// resolve against it.
+    #[must_use]
+    pub fn new() -> HandleTable<T> {
+        let slots = Rc::new(RefCell::new(Slots {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }));
+        let registered: Rc<dyn Registered> = slots.clone();
+        TABLES.with(|tables| {
+            let mut tables = tables.borrow_mut();
+            let type_id = TypeId::of::<T>();
+            if tables.get(&type_id).is_some_and(|t| t.strong_count() > 0) {
+                panic!(
+                    "a HandleTable<{}> already exists; wrap the type in a newtype for a second table",
+                    std::any::type_name::<T>()
+                );
+            }
+            tables.insert(type_id, Rc::downgrade(&registered));
+        });
+        HandleTable { slots }
+    }
+
+    /// Stores `value` and returns its handle.
+    ///
+    /// # Panics
+    /// When the table already holds [`MAX_HANDLES`] values.
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.slots.borrow_mut().insert(value, None)
+    }
+
+    /// Like [`insert`](Self::insert), but the value is owned by `amx`: the
+    /// SDK removes and drops it when that script unloads.
+    ///
+    /// # Panics
+    /// When the table already holds [`MAX_HANDLES`] values.
+    pub fn insert_owned(&mut self, amx: &Amx, value: T) -> Handle<T> {
+        self.slots.borrow_mut().insert(value, Some(amx.ident()))
+    }
+
+    /// The value behind `handle`, if it is still live.
+    #[must_use]
+    pub fn get(&self, handle: Handle<T>) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.slots.borrow(), |slots| {
+            let index = slots.lookup(handle.id)?;
+            slots.slots[index].value.as_ref()
+        })
+        .ok()
+    }
+
+    /// Mutable access to the value behind `handle`, if it is still live.
+    #[must_use]
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.slots.borrow_mut(), |slots| {
+            let index = slots.lookup(handle.id)?;
+            slots.slots[index].value.as_mut()
+        })
+        .ok()
+    }
+
+    /// Removes the value, invalidating `handle` and every copy of its ID.
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let mut slots = self.slots.borrow_mut();
+        let index = slots.lookup(handle.id)?;
+        slots.remove(index)
+    }
+
+    /// Whether `handle` is still live.
+    #[must_use]
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.slots.borrow().lookup(handle.id).is_some()
+    }
+
+    /// Turns a raw Pawn ID back into a handle, if it is live.
+    #[must_use]
+    pub fn handle(&self, id: i32) -> Option<Handle<T>> {
+        self.slots
+            .borrow()
+            .lookup(id)
+            .map(|_| Handle::new_unchecked(id))
+    }
+
+    /// The script that owns `handle` (see [`insert_owned`](Self::insert_owned)).
+    #[must_use]
+    pub fn owner(&self, handle: Handle<T>) -> Option<AmxIdent> {
+        let slots = self.slots.borrow();
+        let index = slots.lookup(handle.id)?;
+        slots.slots[index].owner
+    }
+
+    /// Number of live values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.borrow().len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: 'static> Default for HandleTable<T> {
+    fn default() -> Self {
+        HandleTable::new()
+    }
+}
+
+impl<T: 'static> fmt::Debug for HandleTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandleTable")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use samp_sdk::raw::types::AMX;
+
+    fn ident(addr: usize) -> AmxIdent {
+        AmxIdent::from(addr as *mut AMX)
+    }
+
+    #[test]
+    fn ids_are_positive_and_round_trip() {
+        let mut table = HandleTable::<&str>::new();
+        let a = table.insert("a");
+        let b = table.insert("b");
+
+        assert!(a.id() > 0 && b.id() > 0);
+        assert_ne!(a, b);
+        assert_eq!(table.handle(a.id()), Some(a));
+        assert_eq!(*table.get(b).unwrap(), "b");
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn removed_ids_go_stale_when_the_slot_is_reused() {
+        let mut table = HandleTable::<String>::new();
+        let old = table.insert("old".to_owned());
+        assert_eq!(table.remove(old).as_deref(), Some("old"));
+
+        let new = table.insert("new".to_owned());
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new, old);
+        assert!(table.get(old).is_none());
+        assert!(table.remove(old).is_none());
+        assert!(!is_live::<String>(old.id()));
+        assert!(is_live::<String>(new.id()));
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        let mut table = HandleTable::<u8>::new();
+        let handle = table.insert(1);
+
+        for id in [0, -1, handle.id() + 1, handle.id() + (1 << INDEX_BITS)] {
+            assert!(table.handle(id).is_none(), "{id}");
+            assert!(!is_live::<u8>(id), "{id}");
+        }
+        assert!(!is_live::<u16>(handle.id()));
+    }
+
+    #[test]
+    fn generation_wraps_without_reaching_zero() {
+        let mut table = HandleTable::<()>::new();
+        let first = table.insert(());
+        let mut last = first;
+        for _ in 0..GENERATION_MASK {
+            table.remove(last);
+            last = table.insert(());
+            assert!(last.id() > 0);
+        }
+        assert_eq!(last, first);
+    }
+
+    #[test]
+    fn unload_frees_only_that_scripts_handles() {
+        let marker = Rc::new(());
+        let mut table = HandleTable::<Rc<()>>::new();
+        let owned = table
+            .slots
+            .borrow_mut()
+            .insert(marker.clone(), Some(ident(1)));
+        let other = table
+            .slots
+            .borrow_mut()
+            .insert(marker.clone(), Some(ident(2)));
+        let global = table.insert(marker.clone());
+
+        on_amx_unload(ident(1));
+
+        assert!(!table.contains(owned));
+        assert!(table.contains(other));
+        assert!(table.contains(global));
+        assert_eq!(table.owner(other), Some(ident(2)));
+        assert_eq!(Rc::strong_count(&marker), 3);
+    }
+
+    #[test]
+    fn dropping_a_table_unregisters_it() {
+        let handle = {
+            let mut table = HandleTable::<i64>::new();
+            let handle = table.insert(7);
+            assert!(is_live::<i64>(handle.id()));
+            handle
+        };
+        assert!(!is_live::<i64>(handle.id()));
+        let _second = HandleTable::<i64>::new();
+    }
+
+    #[test]
+    #[should_panic(expected = "already exists")]
+    fn second_table_of_the_same_type_panics() {
+        let _first = HandleTable::<i16>::new();
+        let _second = HandleTable::<i16>::new();
+    }
+}
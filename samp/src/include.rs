@@ -0,0 +1,242 @@
+//! Pawn include (`.inc`) generation.
+//!
+//! `#[native]` resolves the Pawn prototype of every native from its Rust
+//! signature at compile time, and `initialize_plugin!` gathers them — plus the
+//! `forwards: [...]` list — into an [`Include`] that `samp::pawn_include!()`
+//! returns in the plugin crate. Write the file from a test (or any dev-only
+//! binary) so it never drifts from the code:
+//!
+//! ```rust,ignore
+//! initialize_plugin!(
+//!     type: MyPlugin,
+//!     natives: [MyPlugin::greet, MyPlugin::get_name],
+//!     forwards: ["OnGreetDone(playerid, const name[])"],
+//! );
+//!
+//! #[test]
+//! fn write_include() {
+//!     samp::pawn_include!().write("pawno/include/my_plugin.inc").unwrap();
+//! }
+//! ```
+//!
+//! Type mapping (arguments after `&Amx`):
+//!
+//! | Rust                         | Pawn                          |
+//! | ---------------------------- | ----------------------------- |
+//! | `i32`, `u32`, `usize`, …     | `name`                        |
+//! | `f32`                        | `Float:name`                  |
+//! | `bool`                       | `bool:name`                   |
+//! | `&AmxString` / `String`      | `const name[]`                |
+//! | `UnsizedBuffer` + `usize`    | `dest[], size = sizeof dest`  |
+//! | `Buffer`                     | `name[]`                      |
+//! | `Ref<T>`                     | `&name` (tagged like `T`)     |
+//! | `#[native(raw)]`             | `...`                         |
+//!
+//! The return type contributes the tag of the native itself
+//! (`AmxResult<f32>` → `native Float:Name(...)`). Any other type is emitted as
+//! an untagged cell.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Contents of a Pawn include file: an include guard, one `native` line per
+/// native and one `forward` line per public the plugin calls.
+#[derive(Debug, Clone, Default)]
+pub struct Include {
+    name: String,
+    natives: Vec<String>,
+    forwards: Vec<String>,
+}
+
+impl Include {
+    /// Empty include. `name` drives the include guard (`_<name>_included`);
+    /// characters that are not valid in a Pawn identifier become `_`.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Builder: appends a full native declaration (`native Foo(a, b);`), as
+    /// produced by `#[native]`.
+    #[must_use]
+    pub fn native(mut self, declaration: &str) -> Self {
+        self.natives.push(declaration.trim().to_owned());
+        self
+    }
+
+    /// Builder: appends a `forward` for a public the plugin calls via
+    /// `exec_public!`. Accepts the bare prototype (`OnFoo(playerid)`); a
+    /// leading `forward` and the trailing `;` are optional.
+    ///
+    /// A second forward of the same public replaces the first in place — so
+    /// the `forwards: [...]` list can name the parameters of the callback an
+    /// async native declares as `arg0`, `arg1`, …
+    #[must_use]
+    pub fn forward(mut self, prototype: &str) -> Self {
+        let prototype = prototype.trim().trim_end_matches(';').trim_end();
+        let prototype = prototype
+            .strip_prefix("forward ")
+            .unwrap_or(prototype)
+            .trim_start();
+        let line = format!("forward {prototype};");
+        let name = public_name(prototype);
+        match self
+            .forwards
+            .iter_mut()
+            .find(|existing| public_name(&existing["forward ".len()..]) == name)
+        {
+            Some(existing) => *existing = line,
+            None => self.forwards.push(line),
+        }
+        self
+    }
+
+    /// Native declarations, in registration order.
+    #[must_use]
+    pub fn natives(&self) -> &[String] {
+        &self.natives
+    }
+
+    /// `forward` declarations, in declaration order.
+    #[must_use]
+    pub fn forwards(&self) -> &[String] {
+        &self.forwards
+    }
+
+    /// Renders the include as Pawn source.
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    /// Writes the rendered include to `path`, skipping the write when the file
+    /// already holds the same contents (keeps mtimes stable for the Pawn
+    /// compiler and for editors watching the directory).
+    ///
+    /// # Errors
+    /// Propagates I/O errors from reading the old file or writing the new one.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let rendered = self.render();
+        match std::fs::read_to_string(path) {
+            Ok(existing) if existing == rendered => return Ok(()),
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        std::fs::write(path, rendered)
+    }
+
+    fn guard(&self) -> String {
+        let ident: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("_{ident}_included")
+    }
+}
+
+/// Name of the public in a prototype, without its tag (`Float:OnFoo(a)` →
+/// `OnFoo`).
+fn public_name(prototype: &str) -> &str {
+    let name = prototype.split('(').next().unwrap_or(prototype).trim();
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+impl fmt::Display for Include {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.guard();
+        writeln!(
+            f,
+            "// Generated by rust-samp from the plugin's #[native] signatures."
+        )?;
+        writeln!(f, "// Do not edit by hand — regenerate instead.")?;
+        writeln!(f)?;
+        writeln!(f, "#if defined {guard}")?;
+        writeln!(f, "\t#endinput")?;
+        writeln!(f, "#endif")?;
+        writeln!(f, "#define {guard}")?;
+
+        if !self.natives.is_empty() {
+            writeln!(f)?;
+            for native in &self.natives {
+                writeln!(f, "{native}")?;
+            }
+        }
+
+        if !self.forwards.is_empty() {
+            writeln!(f)?;
+            for forward in &self.forwards {
+                writeln!(f, "{forward}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_guard_natives_and_forwards() {
+        let inc = Include::new("my-plugin")
+            .native("native Greet(const name[]);")
+            .native("native Float:GetSpeed(playerid);")
+            .forward("OnGreetDone(playerid, const name[])");
+        let text = inc.render();
+        assert!(text.contains("#if defined _my_plugin_included\n\t#endinput\n#endif\n"));
+        assert!(text.contains("#define _my_plugin_included\n"));
+        assert!(text.contains("\nnative Greet(const name[]);\nnative Float:GetSpeed(playerid);\n"));
+        assert!(text.ends_with("\nforward OnGreetDone(playerid, const name[]);\n"));
+    }
+
+    #[test]
+    fn forward_accepts_keyword_and_semicolon() {
+        let inc = Include::new("p")
+            .forward("forward OnA(id);")
+            .forward("  OnB()  ");
+        assert_eq!(inc.forwards(), ["forward OnA(id);", "forward OnB();"]);
+    }
+
+    #[test]
+    fn later_forward_of_the_same_public_replaces_the_first() {
+        let inc = Include::new("p")
+            .forward("forward OnDone(arg0, const arg1[]);")
+            .forward("OnOther()")
+            .forward("OnDone(playerid, const name[])");
+        assert_eq!(
+            inc.forwards(),
+            [
+                "forward OnDone(playerid, const name[]);",
+                "forward OnOther();"
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_include_only_has_guard() {
+        let text = Include::new("p").render();
+        assert!(!text.contains("\nnative "));
+        assert!(!text.contains("\nforward "));
+        assert!(text.ends_with("#define _p_included\n"));
+    }
+
+    #[test]
+    fn write_is_idempotent() {
+        let dir = std::env::temp_dir().join(format!("samp-inc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.inc");
+        let inc = Include::new("test").native("native Foo();");
+        inc.write(&path).unwrap();
+        inc.write(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), inc.render());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
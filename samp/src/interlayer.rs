@@ -11,7 +11,7 @@
 //!
 //! Marked `#[doc(hidden)]` in `lib.rs` — not part of the plugin's public API.
 
-#[cfg(not(feature = "samp-only"))]
+use crate::amx::AmxIdent;
 use crate::macros::sdk_warn;
 use crate::runtime::Runtime;
 use samp_sdk::raw::types::{AMX, AMX_NATIVE_INFO};
@@ -21,6 +21,12 @@ use samp_sdk::omp::component::ICore;
 #[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::events::{PawnEventHandler, PawnEventHandlerVTable};
 #[cfg(not(feature = "samp-only"))]
+use samp_sdk::omp::players::{
+    IPlayer, PlayerConnectEventHandler, PlayerConnectEventHandlerVTable,
+    add_player_connect_handler, core_players, player_connect_dispatcher, player_id,
+    remove_player_connect_handler,
+};
+#[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::server::{
     IPawnScript, PAWN_COMPONENT_UID, ServerComponentList, add_pawn_event_handler,
     get_amx_from_script, get_amx_functions, get_pawn_event_dispatcher, query_component,
@@ -39,6 +45,15 @@ static PAWN_HANDLER_VTABLE: PawnEventHandlerVTable = PawnEventHandlerVTable {
     on_amx_unload: pawn_on_amx_unload,
 };
 
+/// Static vtable of our `PlayerConnectEventHandler`.
+#[cfg(not(feature = "samp-only"))]
+static PLAYER_CONNECT_VTABLE: PlayerConnectEventHandlerVTable = PlayerConnectEventHandlerVTable {
+    on_incoming_connection: player_on_incoming_connection,
+    on_player_connect: player_on_connect,
+    on_player_disconnect: player_on_disconnect,
+    on_player_client_init: player_on_client_init,
+};
+
 /// Vtable of our `TimerTimeOutHandler` to deliver `on_tick` on Open Multiplayer.
 #[cfg(not(feature = "samp-only"))]
 static TICK_HANDLER_VTABLE: TimerHandlerVTable = TimerHandlerVTable {
@@ -162,6 +177,106 @@ unsafe extern "thiscall" fn pawn_on_amx_unload(
     let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| inner_amx_unload(script)));
 }
 
+/// Shared logic of the player connection callbacks — delivers `name` to the
+/// scripts that do not declare it, the player's id first.
+#[cfg(not(feature = "samp-only"))]
+fn inner_player_event(name: &str, player: *mut IPlayer, extra: &[i32]) {
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let Some(id) = (unsafe { player_id(player) }) else {
+            return;
+        };
+        let mut args = vec![id];
+        args.extend_from_slice(extra);
+        crate::events::deliver_player_event(name, &args);
+    }));
+}
+
+/// Callback: player connected (native Open Multiplayer mode) — Itanium ABI.
+///
+/// # Safety
+/// `player` must be a valid pointer to the Open Multiplayer server's `IPlayer`.
+#[cfg(all(not(feature = "samp-only"), not(target_env = "msvc")))]
+unsafe extern "C" fn player_on_connect(
+    _this: *mut PlayerConnectEventHandler,
+    player: *mut IPlayer,
+) {
+    inner_player_event("OnPlayerConnect", player, &[]);
+}
+
+/// Callback: player connected (native Open Multiplayer mode) — MSVC ABI.
+///
+/// # Safety
+/// `player` must be a valid pointer to the Open Multiplayer server's `IPlayer`.
+#[cfg(all(not(feature = "samp-only"), target_env = "msvc"))]
+unsafe extern "thiscall" fn player_on_connect(
+    _this: *mut PlayerConnectEventHandler,
+    player: *mut IPlayer,
+) {
+    inner_player_event("OnPlayerConnect", player, &[]);
+}
+
+/// Callback: player left (native Open Multiplayer mode) — Itanium ABI.
+///
+/// # Safety
+/// `player` must be a valid pointer to the Open Multiplayer server's `IPlayer`.
+#[cfg(all(not(feature = "samp-only"), not(target_env = "msvc")))]
+unsafe extern "C" fn player_on_disconnect(
+    _this: *mut PlayerConnectEventHandler,
+    player: *mut IPlayer,
+    reason: std::os::raw::c_int,
+) {
+    inner_player_event("OnPlayerDisconnect", player, &[reason]);
+}
+
+/// Callback: player left (native Open Multiplayer mode) — MSVC ABI.
+///
+/// # Safety
+/// `player` must be a valid pointer to the Open Multiplayer server's `IPlayer`.
+#[cfg(all(not(feature = "samp-only"), target_env = "msvc"))]
+unsafe extern "thiscall" fn player_on_disconnect(
+    _this: *mut PlayerConnectEventHandler,
+    player: *mut IPlayer,
+    reason: std::os::raw::c_int,
+) {
+    inner_player_event("OnPlayerDisconnect", player, &[reason]);
+}
+
+/// Callback: incoming connection — no Pawn callback to deliver. Itanium ABI.
+#[cfg(all(not(feature = "samp-only"), not(target_env = "msvc")))]
+unsafe extern "C" fn player_on_incoming_connection(
+    _this: *mut PlayerConnectEventHandler,
+    _player: *mut IPlayer,
+    _address: samp_sdk::omp::types::StringView,
+    _port: u16,
+) {
+}
+
+/// Callback: incoming connection — no Pawn callback to deliver. MSVC ABI.
+#[cfg(all(not(feature = "samp-only"), target_env = "msvc"))]
+unsafe extern "thiscall" fn player_on_incoming_connection(
+    _this: *mut PlayerConnectEventHandler,
+    _player: *mut IPlayer,
+    _address: samp_sdk::omp::types::StringView,
+    _port: u16,
+) {
+}
+
+/// Callback: client init — no Pawn callback to deliver. Itanium ABI.
+#[cfg(all(not(feature = "samp-only"), not(target_env = "msvc")))]
+unsafe extern "C" fn player_on_client_init(
+    _this: *mut PlayerConnectEventHandler,
+    _player: *mut IPlayer,
+) {
+}
+
+/// Callback: client init — no Pawn callback to deliver. MSVC ABI.
+#[cfg(all(not(feature = "samp-only"), target_env = "msvc"))]
+unsafe extern "thiscall" fn player_on_client_init(
+    _this: *mut PlayerConnectEventHandler,
+    _player: *mut IPlayer,
+) {
+}
+
 #[must_use]
 pub fn supports() -> u32 {
     let rt = Runtime::get();
@@ -198,6 +313,9 @@ pub fn amx_load(amx: *mut AMX, natives: &[AMX_NATIVE_INFO]) {
     let plugin = Runtime::plugin();
 
     let amx = rt.insert_amx(amx);
+    // Before `register`, so a detour installed here still sees our natives.
+    // Only installs it once `samp::natives` is used.
+    crate::natives::on_amx_load(rt, amx);
     let _ = amx.register(natives); // don't care about errors, that function always raises errors.
 
     // Resolve `#[event]` handlers against this AMX and install the `amx_Exec`
@@ -212,9 +330,24 @@ pub fn amx_unload(amx: *mut AMX) {
     let plugin = Runtime::plugin();
 
     crate::events::on_amx_unload(rt, amx);
+    crate::tasks::on_amx_unload(AmxIdent::from(amx));
 
     if let Some(amx) = rt.remove_amx(amx) {
         plugin.on_amx_unload(&amx);
+        // The next script may be loaded at the same address.
+        samp_sdk::amx::invalidate_natives(&amx);
+        #[cfg(feature = "encoding")]
+        samp_sdk::encoding::reset_amx_encoding(&amx);
+    }
+
+    // After `on_amx_unload`, so the plugin can still read the state and
+    // the owned handles there.
+    crate::handle::on_amx_unload(AmxIdent::from(amx));
+    let leaked = rt.clear_amx_state(AmxIdent::from(amx));
+    if leaked > 0 {
+        sdk_warn!(
+            "{leaked} Amx::state value(s) still borrowed while the script unloaded; leaking them"
+        );
     }
 }
 
@@ -225,14 +358,45 @@ pub fn amx_unload(amx: *mut AMX) {
 ///
 /// [`on_tick`]: crate::plugin::SampPlugin::on_tick
 /// [`TickSource`]: crate::plugin::TickSource
+///
+/// The main-thread queue (closures from `samp::plugin::main_thread` and
+/// finished async natives) is drained first, on every tick. `on_tick`
+/// itself only fires when the plugin enabled the tick for that server —
+/// the tick may be running just for the queue.
 #[inline]
 pub fn tick(source: crate::plugin::TickSource) {
     let rt = Runtime::get();
+    crate::tasks::drain(rt.main_thread_budget());
+    if !tick_enabled_for(rt, source) {
+        return;
+    }
     let elapsed = rt.record_tick();
     let ctx = crate::plugin::TickContext { elapsed, source };
     Runtime::plugin().on_tick(ctx);
 }
 
+/// Whether the plugin opted in to `on_tick` on the server behind `source`.
+fn tick_enabled_for(rt: &Runtime, source: crate::plugin::TickSource) -> bool {
+    match source {
+        crate::plugin::TickSource::SaMp => rt.tick_enabled_for_sa_mp(),
+        #[cfg(not(feature = "samp-only"))]
+        crate::plugin::TickSource::OmpTimer => rt.omp_tick_interval().is_some(),
+        #[cfg(feature = "samp-only")]
+        crate::plugin::TickSource::OmpTimer => false,
+    }
+}
+
+/// Turns on the tick used to deliver async-native results when any of the
+/// plugin's natives is async. Called by the generated `Supports` and
+/// `ComponentEntryPoint` with one `__samp_async_*()` flag per native, after
+/// the plugin is initialized.
+pub fn enable_tasks(async_natives: &[bool]) {
+    let rt = Runtime::get();
+    if async_natives.contains(&true) && !rt.main_thread_enabled() {
+        rt.set_main_thread_budget(crate::plugin::MainThreadBudget::default());
+    }
+}
+
 /// Called by the generated `ComponentEntryPoint` — initializes the runtime in native Open Multiplayer mode.
 ///
 /// Equivalent to SA-MP's `Supports()`: creates the Runtime and instantiates the plugin.
@@ -280,6 +444,12 @@ pub unsafe fn omp_on_init(components: *mut ServerComponentList) {
 
     rt.set_omp_component_list(components);
 
+    // `#[event(define)]` handlers run for players even where no script
+    // declares the callback: listen on the player pool itself.
+    if rt.has_defined_publics() {
+        install_player_connect_handler(rt);
+    }
+
     let pawn = unsafe { query_component(components, PAWN_COMPONENT_UID) };
     if pawn.is_null() {
         sdk_warn!("IPawnComponent not found in on_init — Pawn natives unavailable");
@@ -311,6 +481,29 @@ pub unsafe fn omp_on_init(components: *mut ServerComponentList) {
     }
 }
 
+/// Registers our `PlayerConnectEventHandler` in the player pool's dispatcher.
+#[cfg(not(feature = "samp-only"))]
+fn install_player_connect_handler(rt: &Runtime) {
+    let pool = rt
+        .omp_core()
+        .map_or(std::ptr::null_mut(), |core| unsafe { core_players(core) });
+    let dispatcher = unsafe { player_connect_dispatcher(pool) };
+    if dispatcher.is_null() {
+        sdk_warn!(
+            "null IEventDispatcher<PlayerConnectEventHandler> in on_init — `define` events only fire for declared callbacks"
+        );
+        return;
+    }
+    let handler = Box::into_raw(Box::new(PlayerConnectEventHandler::new(
+        &raw const PLAYER_CONNECT_VTABLE,
+    )));
+    if unsafe { add_player_connect_handler(dispatcher, handler) } {
+        rt.set_player_connect_handler(handler);
+    } else {
+        drop(unsafe { Box::from_raw(handler) });
+    }
+}
+
 /// Called by the vtable's `on_ready` handler — all server components have
 /// finished initializing.
 #[cfg(not(feature = "samp-only"))]
@@ -346,10 +539,11 @@ pub fn omp_on_ready() {
     }
 
     // Tick abstraction: if the plugin opted in to the tick on the Open
-    // Multiplayer side via `enable_tick` / `enable_tick_with`, create a
+    // Multiplayer side via `enable_tick` / `enable_tick_with` (or needs the
+    // main-thread queue drained, e.g. for async natives), create a
     // repeating timer in `ITimersComponent` at the configured interval and
     // route its timeout into `SampPlugin::on_tick`.
-    if let Some(interval) = rt.omp_tick_interval()
+    if let Some(interval) = rt.omp_timer_interval()
         && let Some(components) = rt.omp_component_list()
     {
         let timers = unsafe { query_timers_component(components) };
@@ -390,6 +584,7 @@ pub fn omp_on_free() {
 /// Open Multiplayer cleanup — disables SDK resources before shutdown:
 ///   1. Kills the `on_tick` timer (if it was created in `on_ready`).
 ///   2. Removes the `PawnEventHandler` from the dispatcher.
+///   3. Removes the `PlayerConnectEventHandler` from the player pool.
 ///
 /// Called by `comp_free` before `unload()`. Avoids use-after-free in case the
 /// server tries to fire Pawn events or ticks after the component is released.
@@ -415,4 +610,13 @@ pub fn omp_cleanup() {
         }
         drop(unsafe { Box::from_raw(handler) });
     }
+
+    // 3) Unregister the PlayerConnectEventHandler from the player pool.
+    if let Some(handler) = rt.take_player_connect_handler() {
+        if let Some(core) = rt.omp_core() {
+            let dispatcher = unsafe { player_connect_dispatcher(core_players(core)) };
+            unsafe { remove_player_connect_handler(dispatcher, handler) };
+        }
+        drop(unsafe { Box::from_raw(handler) });
+    }
 }
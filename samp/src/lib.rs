@@ -4,7 +4,8 @@
 //!
 //! - `samp` — main crate; re-exports SDK + codegen and exposes the API the plugin uses.
 //! - `samp-codegen` — proc macros (`#[native]`, `initialize_plugin!`,
-//!   `#[derive(SampPlugin)]`) that generate FFI entry points and argument parsing.
+//!   `#[derive(SampPlugin)]`, `#[derive(AmxCell)]`, `#[derive(AmxStruct)]`)
+//!   that generate FFI entry points and argument parsing.
 //! - `samp-sdk` — low-level bindings for the AMX VM (SA-MP) and for the component
 //!   ABI (Open Multiplayer).
 //!
@@ -57,13 +58,16 @@
 
 pub mod amx;
 pub mod events;
+pub mod handle;
+pub mod include;
 #[doc(hidden)]
 pub mod interlayer;
 pub mod logger;
-#[cfg(not(feature = "samp-only"))]
 pub(crate) mod macros;
+pub mod natives;
 pub mod plugin;
 pub(crate) mod runtime;
+pub mod tasks;
 
 pub use samp_codegen::{event, initialize_plugin, native};
 
@@ -85,8 +89,17 @@ pub use log;
 /// `on_load`/`on_tick`/etc, declare `impl SampPlugin for T { ... }`
 /// manually instead of using the derive.
 pub use samp_codegen::SampPlugin;
-pub use samp_sdk::exec_public;
-pub use samp_sdk::{args, cell, consts, error, exports, raw};
+
+/// Derive macro that implements `AmxCell`, `CellConvert` and
+/// `samp::tasks::AsyncArg` for fieldless enums (`#[amx(fallback)]` marks the
+/// variant unknown cells map to) and single-field newtypes over primitives.
+pub use samp_codegen::AmxCell;
+
+/// Derive macro that implements `AmxStruct` for a struct laid out like an
+/// enum-structured Pawn array, so natives can take it as `&mut T`.
+pub use samp_codegen::AmxStruct;
+pub use samp_sdk::{args, cell, consts, error, exports, format, raw};
+pub use samp_sdk::{call_native, exec_public};
 
 #[cfg(feature = "debug")]
 pub use samp_sdk::debug;
@@ -97,6 +110,9 @@ pub use samp_sdk::encoding;
 #[cfg(not(feature = "samp-only"))]
 pub use samp_sdk::omp;
 
+#[cfg(feature = "test-vm")]
+pub use samp_sdk::test_vm;
+
 pub mod prelude {
     //! Most commonly used imports in plugins.
     pub use crate::amx::{Amx, AmxExt};
@@ -151,6 +167,28 @@ macro_rules! enable_logger_with {
     }};
 }
 
+/// The plugin's Pawn include (`.inc`), as generated by `initialize_plugin!`
+/// from its natives and `forwards: [...]`. Only usable in the crate that
+/// invokes `initialize_plugin!` — typically from a test:
+///
+/// ```rust,ignore
+/// #[test]
+/// fn write_include() {
+///     samp::pawn_include!().write("pawno/include/my_plugin.inc").unwrap();
+/// }
+/// ```
+///
+/// Returns a [`samp::include::Include`](crate::include::Include).
+// `crate` must resolve in the calling crate, where `initialize_plugin!` put
+// the generated module — not in `samp`, as `$crate` would.
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! pawn_include {
+    () => {
+        crate::__samp_include::pawn_include()
+    };
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,342 @@
+//! Every native the server has seen registered, callable from any plugin.
+//!
+//! [`Amx::call_native`] finds a native through the natives table of the
+//! script it is called on, so it only reaches what that script declares —
+//! `GetPlayerName` fails on a gamemode that never calls it. To reach any
+//! native, the natives table of each script is read as it loads, and — once
+//! the registry is used — the SDK detours the VM's `amx_Register`, the
+//! function the server and every plugin register their natives through, and
+//! records each name/function pair passing through it (the same technique
+//! [`events`] uses on `amx_Exec`).
+//!
+//! The detour is installed on the first [`find`] or [`call`], so plugins that
+//! never use the registry leave `amx_Register` alone. Natives registered
+//! before that are only known if a loaded script declares them; call
+//! [`enable_native_registry`] from `on_load` to see every registration.
+//!
+//! [`find`] returns the native's host function. `call_native!` calls it in
+//! any script, whether or not the script declares it, or in the [`scratch`]
+//! AMX when there is no script at hand:
+//!
+//! ```rust,ignore
+//! let get_player_name = samp::natives::find("GetPlayerName")?;
+//! let amx = samp::natives::scratch()?;
+//! let mut name = String::new();
+//! call_native!(amx, get_player_name, playerid, &mut name => ref_string(24), 24)?;
+//! ```
+//!
+//! Like the rest of the runtime, the registry is meant for the main thread.
+//!
+//! [`Amx::call_native`]: samp_sdk::amx::Amx::call_native
+//! [`events`]: crate::events
+
+use samp_sdk::amx::{Amx, NativeTarget};
+use samp_sdk::error::{AmxError, AmxResult};
+use samp_sdk::raw::functions::AmxNative;
+
+use crate::runtime::Runtime;
+
+// Detour machinery is x86/x86_64-only, as in `events`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use std::ffi::CStr;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use std::sync::OnceLock;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use retour::GenericDetour;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use samp_sdk::exports::{Export, Register};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use samp_sdk::raw::types::{AMX, AMX_NATIVE_INFO};
+
+/// Host function of the native `name`, as registered by the server or a
+/// plugin. Pass it to `call_native!` in place of the name.
+///
+/// When several registrations share a name, the last one is returned: a
+/// plugin that registers a native again to hook it is found instead of the
+/// original. A script's natives table only fills names no registration
+/// went through the detour for.
+///
+/// The pointer is only good while whoever registered it stays loaded. The
+/// server's natives live as long as the process; a plugin's go away when
+/// it unloads, which servers only do at shutdown. Do not keep the result
+/// past `Unload` (`on_free` on open.mp).
+///
+/// # Errors
+/// `AmxError::NotFound` if no loaded script declares a native of that name
+/// and none went through `amx_Register` since the registry was enabled.
+pub fn find(name: &str) -> AmxResult<AmxNative> {
+    let rt = Runtime::try_get().ok_or(AmxError::NotFound)?;
+    enable(rt);
+    rt.recorded_native(name).ok_or(AmxError::NotFound)
+}
+
+/// Installs the `amx_Register` detour now rather than on the first [`find`],
+/// so natives registered in between are recorded too. Meant for `on_load`;
+/// on open.mp the detour follows once the AMX functions are handed out.
+///
+/// # Panics
+/// If called before the plugin is initialised.
+pub fn enable_native_registry() {
+    enable(Runtime::get());
+}
+
+/// An AMX with no script in it, for calling natives while no script is
+/// loaded (or none that should hold the arguments). Created on first use
+/// and kept for the plugin's lifetime; its heap holds 4096 cells.
+///
+/// # Errors
+/// `AmxError::InvalidState` before the server handed out the AMX functions
+/// (on open.mp, until `on_omp_ready`), otherwise the error of `amx_Init`.
+pub fn scratch() -> AmxResult<Amx> {
+    Runtime::try_get()
+        .ok_or(AmxError::InvalidState)?
+        .scratch_amx()
+}
+
+/// Calls the native `name` in the [`scratch`] AMX with raw cells, like
+/// [`Amx::call_native`](samp_sdk::amx::Amx::call_native). For typed
+/// arguments, use `call_native!` with [`find`] and [`scratch`].
+///
+/// # Errors
+/// The errors of [`find`] and [`scratch`], and any error the native raises.
+pub fn call(name: &str, params: &[i32]) -> AmxResult<i32> {
+    let native = find(name)?;
+    native.invoke(&scratch()?, params)
+}
+
+/// Marks the registry as used and installs the `amx_Register` detour, if the
+/// AMX functions are known; otherwise the next script load installs it.
+fn enable(rt: &Runtime) {
+    rt.enable_native_registry();
+    install_register_hook(rt.amx_exports());
+}
+
+/// Records the natives a freshly loaded script already has bound, and
+/// installs the detour if the registry was enabled before the AMX functions
+/// were known.
+pub(crate) fn on_amx_load(rt: &Runtime, amx: &Amx) {
+    if rt.native_registry_enabled() {
+        install_register_hook(rt.amx_exports());
+    }
+    if let Ok(natives) = amx.bound_natives() {
+        for (name, native) in natives {
+            rt.record_bound_native(&name, native);
+        }
+    }
+}
+
+/// Signature of the VM's `amx_Register` — `(amx, list, count)`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+type RegisterFn = unsafe extern "C" fn(*mut AMX, *const AMX_NATIVE_INFO, i32) -> i32;
+
+/// Owns the live detour so it stays enabled for the process lifetime.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+struct RegisterDetour(GenericDetour<RegisterFn>);
+
+// SAFETY: see `ExecDetour` — only ever touched on the main thread.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe impl Sync for RegisterDetour {}
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe impl Send for RegisterDetour {}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+static REGISTER_DETOUR: OnceLock<RegisterDetour> = OnceLock::new();
+
+/// Installs the `amx_Register` detour from the AMX function table.
+/// Idempotent, like `install_exec_hook`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn install_register_hook(fn_table: usize) {
+    if REGISTER_DETOUR.get().is_some() || fn_table == 0 {
+        return;
+    }
+
+    let target: RegisterFn = Register::from_table(fn_table);
+
+    // SAFETY: `target` is the server's real `amx_Register`; `register_detour`
+    // never unwinds across the boundary.
+    let detour = match unsafe { GenericDetour::new(target, register_detour) } {
+        Ok(detour) => detour,
+        Err(err) => {
+            log::warn!(
+                "[rust-samp] failed to build amx_Register detour: {err}; samp::natives only sees natives scripts declare"
+            );
+            return;
+        }
+    };
+
+    let cell = REGISTER_DETOUR.get_or_init(|| RegisterDetour(detour));
+
+    // SAFETY: as in `install_exec_hook`.
+    if let Err(err) = unsafe { cell.0.enable() } {
+        log::warn!(
+            "[rust-samp] failed to enable amx_Register detour: {err}; samp::natives only sees natives scripts declare"
+        );
+    }
+}
+
+/// Without the detour library only the natives tables of loaded scripts
+/// feed the registry.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn install_register_hook(_fn_table: usize) {}
+
+/// Trampoline installed in place of `amx_Register`: records the list, then
+/// registers it as usual.
+///
+/// # Safety
+/// Installed by retour as the replacement for the VM's `amx_Register`; the
+/// server calls it with the same arguments the original expects.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe extern "C" fn register_detour(
+    amx: *mut AMX,
+    list: *const AMX_NATIVE_INFO,
+    number: i32,
+) -> i32 {
+    let _ = std::panic::catch_unwind(|| unsafe { record(list, number) });
+
+    // SAFETY: delegates to retour's preserved trampoline with the original args.
+    match REGISTER_DETOUR.get() {
+        Some(cell) => unsafe { cell.0.call(amx, list, number) },
+        None => 0,
+    }
+}
+
+/// Records every named entry of a native list. A negative `number` means the
+/// list ends at the first entry without a name, as in `amx_Register`.
+///
+/// # Safety
+/// `list` must be null or point to `number` entries (or a terminated list).
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn record(list: *const AMX_NATIVE_INFO, number: i32) {
+    let Some(rt) = Runtime::try_get() else {
+        return;
+    };
+    if list.is_null() {
+        return;
+    }
+    let count = usize::try_from(number).unwrap_or(usize::MAX);
+
+    for index in 0..count {
+        // SAFETY: within the list per the caller's contract; `AMX_NATIVE_INFO`
+        // is packed, so its fields are read unaligned.
+        let (name, func) = unsafe {
+            let entry = list.add(index);
+            (
+                std::ptr::addr_of!((*entry).name).read_unaligned(),
+                std::ptr::addr_of!((*entry).func)
+                    .cast::<usize>()
+                    .read_unaligned(),
+            )
+        };
+        if name.is_null() {
+            break;
+        }
+        // Only a bound function can be called; an empty slot is skipped.
+        if func == 0 {
+            continue;
+        }
+        // SAFETY: `func` is the non-null function pointer of the entry.
+        let native: AmxNative = unsafe { std::mem::transmute::<usize, AmxNative>(func) };
+        // SAFETY: native names are NUL-terminated C strings.
+        if let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() {
+            rt.record_native(name, native);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use samp_sdk::raw::types::{AMX, AMX_NATIVE_INFO};
+    use samp_sdk::test_vm::{ImageBuilder, TestVm, server_data};
+
+    /// `Twice(value)`: `value * 2`.
+    extern "C" fn twice(_amx: *mut AMX, params: *mut i32) -> i32 {
+        // SAFETY: the VM passes the argument count followed by the arguments.
+        unsafe { *params.add(1) * 2 }
+    }
+
+    /// `Thrice(value)`: `value * 3`, registered over `Twice` as a hook.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    extern "C" fn thrice(_amx: *mut AMX, params: *mut i32) -> i32 {
+        // SAFETY: the VM passes the argument count followed by the arguments.
+        unsafe { *params.add(1) * 3 }
+    }
+
+    fn declaring(name: &str) -> TestVm {
+        let mut image = ImageBuilder::new();
+        image.native(name);
+        TestVm::from_bytes(&image.build()).unwrap()
+    }
+
+    // One test, as it sets up the runtime every other call here would read.
+    #[test]
+    fn registered_natives_are_found_and_called_in_any_script() {
+        let _lock = crate::runtime::TEST_RUNTIME
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert!(matches!(find("NoSuchNative"), Err(AmxError::NotFound)));
+        assert!(matches!(call("NoSuchNative", &[]), Err(AmxError::NotFound)));
+
+        let rt = Runtime::initialize();
+        rt.set_server_exports(server_data());
+
+        // Bound while nothing uses the registry: read from the natives table
+        // on load, without touching `amx_Register`.
+        let early = declaring("Early");
+        let early_amx = early.amx();
+        early_amx
+            .register(&[AMX_NATIVE_INFO {
+                name: c"Early".as_ptr(),
+                func: twice,
+            }])
+            .unwrap();
+        on_amx_load(rt, &early_amx);
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert!(REGISTER_DETOUR.get().is_none());
+
+        // Registered once the registry is in use: seen by the detour.
+        enable_native_registry();
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            assert!(REGISTER_DETOUR.get().is_some());
+            let late = declaring("Late");
+            late.amx()
+                .register(&[AMX_NATIVE_INFO {
+                    name: c"Late".as_ptr(),
+                    func: twice,
+                }])
+                .unwrap();
+            assert!(find("Late").is_ok());
+        }
+
+        let other = TestVm::from_bytes(&ImageBuilder::new().build()).unwrap();
+        let other_amx = other.amx();
+        let early = find("Early").unwrap();
+        assert_eq!(samp_sdk::call_native!(other_amx, early, 21).unwrap(), 42);
+
+        // A plugin registering `Early` again to hook it replaces the original;
+        // a later script still bound to the original does not bring it back.
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            let hooked = declaring("Early");
+            hooked
+                .amx()
+                .register(&[AMX_NATIVE_INFO {
+                    name: c"Early".as_ptr(),
+                    func: thrice,
+                }])
+                .unwrap();
+            let hook = find("Early").unwrap();
+            assert_eq!(samp_sdk::call_native!(other_amx, hook, 21).unwrap(), 63);
+
+            on_amx_load(rt, &early_amx);
+            let hook = find("Early").unwrap();
+            assert_eq!(samp_sdk::call_native!(other_amx, hook, 21).unwrap(), 63);
+        }
+
+        // The scratch AMX needs the server's `amx_Init`.
+        assert!(matches!(scratch(), Err(AmxError::Init)));
+    }
+}
@@ -1,5 +1,6 @@
 //! API the Rust plugin uses: trait [`SampPlugin`] (lifecycle) + global
-//! functions to enable features (`enable_tick`, `logger`, `omp_query`).
+//! functions to enable features (`enable_tick`, `main_thread`, `logger`,
+//! `omp_query`).
 
 use std::ptr::NonNull;
 use std::time::Duration;
@@ -173,6 +174,92 @@ pub fn enable_tick_with(config: TickConfig) {
     Runtime::get().set_tick_config(config);
 }
 
+/// How much of each tick the SDK may spend running closures queued through
+/// [`MainThread`]. Whatever does not fit waits for the next tick, so a
+/// flood of tasks cannot stall the server. At least one closure runs per
+/// tick regardless of the budget.
+///
+/// [`MainThread`]: crate::tasks::MainThread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MainThreadBudget {
+    /// Maximum number of closures per tick.
+    pub max_tasks: usize,
+    /// Time after which no further closure starts in the current tick.
+    /// A closure that is already running is never interrupted.
+    pub max_time: Duration,
+}
+
+impl Default for MainThreadBudget {
+    /// Default: up to 128 closures or 1 ms per tick, whichever comes first.
+    fn default() -> Self {
+        Self {
+            max_tasks: 128,
+            max_time: Duration::from_millis(1),
+        }
+    }
+}
+
+impl MainThreadBudget {
+    /// Equivalent to `MainThreadBudget::default()`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: sets [`max_tasks`].
+    ///
+    /// [`max_tasks`]: MainThreadBudget::max_tasks
+    #[must_use]
+    pub fn max_tasks(mut self, max_tasks: usize) -> Self {
+        self.max_tasks = max_tasks;
+        self
+    }
+
+    /// Builder: sets [`max_time`].
+    ///
+    /// [`max_time`]: MainThreadBudget::max_time
+    #[must_use]
+    pub fn max_time(mut self, max_time: Duration) -> Self {
+        self.max_time = max_time;
+        self
+    }
+}
+
+/// Handle for queuing work onto the server thread from any thread. See
+/// [`MainThread`].
+///
+/// Can be called from any thread, at any time. The queue is drained on the
+/// server tick, so the plugin must turn that tick on with
+/// [`enable_main_thread`] (or [`enable_tick`]) in `initialize_plugin!`.
+///
+/// [`MainThread`]: crate::tasks::MainThread
+#[must_use]
+pub fn main_thread() -> crate::tasks::MainThread {
+    crate::tasks::MainThread::new()
+}
+
+/// Turns on the server tick that drains the [`main_thread`] queue, with the
+/// default [`MainThreadBudget`]. Does not enable [`SampPlugin::on_tick`].
+///
+/// Call inside `initialize_plugin!`, like [`enable_tick`].
+pub fn enable_main_thread() {
+    enable_main_thread_with(MainThreadBudget::default());
+}
+
+/// Turns on the server tick that drains the [`main_thread`] queue, with an
+/// explicit per-tick budget.
+///
+/// # Example
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use samp::plugin::{enable_main_thread_with, MainThreadBudget};
+/// // Never spend more than 500 µs per tick on queued work.
+/// enable_main_thread_with(MainThreadBudget::new().max_time(Duration::from_micros(500)));
+/// ```
+pub fn enable_main_thread_with(budget: MainThreadBudget) {
+    Runtime::get().set_main_thread_budget(budget);
+}
+
 /// Installs the SDK's debug hook on `amx`, routing every executed line into
 /// [`SampPlugin::on_debug_break`]. Call from [`SampPlugin::on_amx_load`] for
 /// each AMX you want to debug (typically the gamemode).
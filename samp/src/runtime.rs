@@ -9,40 +9,67 @@
 //! mutation — safe because the server is single-threaded and all callbacks
 //! run on the main thread.
 
+use samp_sdk::amx::ScratchAmx;
 use samp_sdk::consts::{ServerData, Supports};
+use samp_sdk::error::{AmxError, AmxResult};
 #[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::component::ICore;
 #[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::events::PawnEventHandler;
 #[cfg(not(feature = "samp-only"))]
+use samp_sdk::omp::players::PlayerConnectEventHandler;
+#[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::server::{ServerComponent, ServerComponentList};
 #[cfg(not(feature = "samp-only"))]
 use samp_sdk::omp::timers::{ITimer, TimerTimeOutHandler};
+use samp_sdk::raw::functions::{AmxNative, Logprintf};
+use samp_sdk::raw::types::AMX;
 #[cfg(not(feature = "samp-only"))]
 use samp_sdk::raw::types::AMX_NATIVE_INFO;
-use samp_sdk::raw::{functions::Logprintf, types::AMX};
 
-use std::cell::UnsafeCell;
+use std::any::TypeId;
+use std::cell::{RefCell, UnsafeCell};
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::ptr::NonNull;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use std::time::{Duration, Instant};
 
-use crate::amx::{Amx, AmxIdent};
-use crate::events::{EventHandler, EventInfo};
-use crate::plugin::{SampPlugin, TickConfig};
+use crate::amx::{Amx, AmxIdent, AmxKind, AmxStates};
+use crate::events::{EventInfo, Resolved, Subscriber};
+use crate::plugin::{MainThreadBudget, SampPlugin, TickConfig};
 
 static RUNTIME: AtomicPtr<Runtime> = AtomicPtr::new(std::ptr::null_mut());
 
+/// Indices of a SA-MP script's `OnGameModeInit` (kept only when the script
+/// has `main`) and `OnFilterScriptInit` publics.
+#[derive(Clone, Copy)]
+struct InitPublics {
+    game_mode: Option<i32>,
+    filter_script: Option<i32>,
+}
+
+/// Held by the unit tests that install a runtime of their own, so they do not
+/// replace it under one another.
+#[cfg(test)]
+pub(crate) static TEST_RUNTIME: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 struct RuntimeInner {
     plugin: Option<NonNull<dyn SampPlugin + 'static>>,
+    /// Concrete type behind `plugin`, checked by `plugin_downcast`.
+    plugin_type: Option<TypeId>,
     /// Set by `samp::plugin::enable_tick` / `enable_tick_with`. `None`
     /// means the tick is disabled on both servers (the default).
     tick_config: Option<TickConfig>,
     /// Wall-clock timestamp of the previous tick dispatch. Used to compute
     /// `TickContext::elapsed`. `None` until the first tick fires.
     last_tick_at: Option<Instant>,
+    /// Set by `samp::plugin::enable_main_thread*`, or implicitly when the
+    /// plugin declares an async native. Keeps the server tick running (to
+    /// drain the main-thread queue) even without `enable_tick`. `None`
+    /// still drains with the default budget whenever the tick runs.
+    main_thread_budget: Option<MainThreadBudget>,
     server_exports: *const usize,
     /// AMX function table obtained from `IPawnComponent` in native Open Multiplayer mode.
     #[cfg(not(feature = "samp-only"))]
@@ -56,6 +83,10 @@ struct RuntimeInner {
     /// Pawn event handler registered in the `IEventDispatcher` of `IPawnComponent`.
     #[cfg(not(feature = "samp-only"))]
     pawn_event_handler: Option<NonNull<PawnEventHandler>>,
+    /// Player connection handler registered in the `IPlayerPool` dispatcher
+    /// when an `#[event(define)]` handler exists.
+    #[cfg(not(feature = "samp-only"))]
+    player_connect_handler: Option<NonNull<PlayerConnectEventHandler>>,
     /// Natives to register on the AMX in native Open Multiplayer mode (via `pawn_on_amx_load`).
     /// In SA-MP/legacy mode this Vec stays empty — natives are passed via `AmxLoad()`.
     #[cfg(not(feature = "samp-only"))]
@@ -72,14 +103,37 @@ struct RuntimeInner {
     #[cfg(not(feature = "samp-only"))]
     omp_tick_handler: Option<NonNull<TimerTimeOutHandler>>,
     amx_list: Vec<(AmxIdent, Amx)>,
-    /// `#[event]` handlers registered at init via `register_events`. Empty when
-    /// the plugin uses no events — the `amx_Exec` detour is then never installed.
+    /// What each loaded script is to the server ([`Runtime::classify`]).
+    /// Backs `AmxExt::kind`.
+    amx_kinds: HashMap<AmxIdent, AmxKind>,
+    /// SA-MP scripts whose kind the server has not shown yet, with the
+    /// indices of the init publics that will show it. An entry leaves once
+    /// one of them runs ([`Runtime::note_exec`]).
+    init_publics: HashMap<AmxIdent, InitPublics>,
+    /// `#[event]` handlers registered at init via `register_events`, in the
+    /// order they run. Empty when the plugin uses no events — the `amx_Exec`
+    /// detour is then never installed.
     events: Vec<EventInfo>,
     /// Per-AMX resolution of the registered events, keyed by
     /// `(amx, public index)` for O(1) lookup on the `amx_Exec` hot path (a
     /// public runs on every callback/timer tick). Filled on `on_amx_load`,
     /// pruned on `on_amx_unload`.
-    resolved_events: HashMap<(AmxIdent, i32), Vec<EventHandler>>,
+    resolved_events: HashMap<(AmxIdent, i32), Vec<Resolved>>,
+    /// Callbacks of the `#[event(define)]` handlers, each once.
+    defined_publics: Vec<&'static str>,
+    /// Closures attached by `samp::events::subscribe`, in subscription order.
+    subscriptions: Vec<Rc<Subscriber>>,
+    /// Id of the next subscription; ids are never reused.
+    next_subscription: u64,
+    /// Host functions seen by the `amx_Register` detour (or read from a
+    /// loaded script's natives table), by name, for `samp::natives`.
+    natives: HashMap<String, AmxNative>,
+    /// Whether `samp::natives` asked for the `amx_Register` detour.
+    native_registry: bool,
+    /// Script-less AMX the `samp::natives` calls run in. Created on first use.
+    scratch_amx: Option<ScratchAmx>,
+    /// Values behind `AmxExt::state`, dropped in `amx_unload`.
+    amx_states: AmxStates,
     logger_enabled: bool,
 }
 
@@ -108,8 +162,10 @@ impl Runtime {
     pub fn initialize() -> &'static Runtime {
         let inner = RuntimeInner {
             plugin: None,
+            plugin_type: None,
             tick_config: None,
             last_tick_at: None,
+            main_thread_budget: None,
             server_exports: std::ptr::null(),
             #[cfg(not(feature = "samp-only"))]
             omp_amx_exports: None,
@@ -120,6 +176,8 @@ impl Runtime {
             #[cfg(not(feature = "samp-only"))]
             pawn_event_handler: None,
             #[cfg(not(feature = "samp-only"))]
+            player_connect_handler: None,
+            #[cfg(not(feature = "samp-only"))]
             omp_natives: Vec::new(),
             #[cfg(not(feature = "samp-only"))]
             omp_pending_amx: Vec::new(),
@@ -128,8 +186,17 @@ impl Runtime {
             #[cfg(not(feature = "samp-only"))]
             omp_tick_handler: None,
             amx_list: Vec::new(),
+            amx_kinds: HashMap::new(),
+            init_publics: HashMap::new(),
             events: Vec::new(),
             resolved_events: HashMap::new(),
+            defined_publics: Vec::new(),
+            subscriptions: Vec::new(),
+            next_subscription: 0,
+            natives: HashMap::new(),
+            native_registry: false,
+            scratch_amx: None,
+            amx_states: AmxStates::default(),
             logger_enabled: true,
         };
 
@@ -155,25 +222,26 @@ impl Runtime {
 
     #[inline]
     pub fn amx_exports(&self) -> usize {
-        let inner = self.inner();
-
         // Native Open Multiplayer mode: exports obtained via IPawnComponent::getAmxFunctions()
         #[cfg(not(feature = "samp-only"))]
-        if let Some(exports) = inner.omp_amx_exports {
+        if let Some(exports) = self.inner().omp_amx_exports {
             return exports;
         }
 
         // SA-MP mode: exports obtained via server_data passed in Load()
+        // Native Open Multiplayer without an AMX table available (on_init failed or has not been called yet) gets 0.
+        self.server_data(ServerData::AmxExports)
+    }
+
+    /// Entry `entry` of the server data SA-MP passed to `Load`, or `0` when
+    /// there is none (native Open Multiplayer).
+    #[inline]
+    pub fn server_data(&self, entry: ServerData) -> usize {
+        let inner = self.inner();
         if inner.server_exports.is_null() {
-            // Native Open Multiplayer without an AMX table available (on_init failed or has not been called yet).
             return 0;
         }
-        unsafe {
-            inner
-                .server_exports
-                .offset(ServerData::AmxExports.into())
-                .read()
-        }
+        unsafe { inner.server_exports.offset(entry.into()).read() }
     }
 
     #[inline]
@@ -268,6 +336,8 @@ impl Runtime {
         let ident = AmxIdent::from(amx);
         let amx = Amx::new(amx, self.amx_exports());
 
+        let kind = self.classify(ident, &amx);
+        inner.amx_kinds.insert(ident, kind);
         inner.amx_list.push((ident, amx));
         &inner
             .amx_list
@@ -277,17 +347,37 @@ impl Runtime {
     }
 
     pub fn remove_amx(&self, amx: *mut AMX) -> Option<Amx> {
-        let list = &mut self.inner().amx_list;
+        let inner = self.inner();
         let ident = AmxIdent::from(amx);
+        inner.amx_kinds.remove(&ident);
+        inner.init_publics.remove(&ident);
+        let list = &mut inner.amx_list;
         list.iter()
             .position(|(k, _)| *k == ident)
             .map(|pos| list.swap_remove(pos).1)
     }
 
+    /// The `T` state of the AMX `ident`, created on first access. Backs
+    /// `AmxExt::state`.
+    pub fn amx_state<T: Default + 'static>(&self, ident: AmxIdent) -> &RefCell<T> {
+        self.inner().amx_states.get_or_default(ident)
+    }
+
+    /// Backs `AmxExt::remove_state`.
+    pub fn remove_amx_state<T: 'static>(&self, ident: AmxIdent) -> Option<T> {
+        self.inner().amx_states.remove(ident)
+    }
+
+    /// Drops every state value of the AMX `ident`. Returns how many were
+    /// still borrowed and had to be leaked.
+    pub fn clear_amx_state(&self, ident: AmxIdent) -> usize {
+        self.inner().amx_states.clear(ident)
+    }
+
     pub fn supports(&self) -> Supports {
         let mut supports = Supports::VERSION | Supports::AMX_NATIVES;
 
-        if self.tick_enabled_for_sa_mp() {
+        if self.tick_enabled_for_sa_mp() || self.main_thread_enabled() {
             supports.insert(Supports::PROCESS_TICK);
         }
 
@@ -299,12 +389,95 @@ impl Runtime {
         &self.inner().amx_list
     }
 
+    /// Classifies a script that is loading, from what the server says of it.
+    ///
+    /// On open.mp the gamemode is the script `IPawnComponent::mainScript()`
+    /// returns; every other script is a side script.
+    ///
+    /// SA-MP says nothing at load time. It raises `OnFilterScriptInit` only in
+    /// a filterscript, right after loading it, and `OnGameModeInit` in the
+    /// gamemode once it starts (and in the filterscripts already loaded, which
+    /// have had their `OnFilterScriptInit` by then). The script reads as a
+    /// filterscript until the first of the two runs through the `amx_Exec`
+    /// detour ([`note_exec`](Self::note_exec)). Only a script with a `main`
+    /// entry point can be the gamemode: SA-MP fails a gamemode without one
+    /// with "bad entry point".
+    fn classify(&self, ident: AmxIdent, amx: &Amx) -> AmxKind {
+        #[cfg(not(feature = "samp-only"))]
+        if self.omp_component_list().is_some() {
+            return if self.omp_main_script() == Some(ident) {
+                AmxKind::Gamemode
+            } else {
+                AmxKind::SideScript
+            };
+        }
+
+        // SAFETY: the server loaded the script, so its header is mapped.
+        let has_main = amx.header().is_some_and(
+            |header| unsafe { (&raw const (*header.as_ptr()).cip).read_unaligned() } >= 0,
+        );
+        let index = |name: &str| amx.find_public(name).ok().map(i32::from);
+        let publics = InitPublics {
+            game_mode: index("OnGameModeInit").filter(|_| has_main),
+            filter_script: index("OnFilterScriptInit"),
+        };
+        if publics.game_mode.is_some() || publics.filter_script.is_some() {
+            self.inner().init_publics.insert(ident, publics);
+        }
+        AmxKind::Filterscript
+    }
+
+    /// True while a SA-MP script waits for its init public to be classified —
+    /// the `amx_Exec` detour is needed for it even without events.
+    pub(crate) fn awaits_init(&self) -> bool {
+        !self.inner().init_publics.is_empty()
+    }
+
+    /// Called by the `amx_Exec` detour before public `index` runs on `ident`:
+    /// settles the script's kind when that public is `OnGameModeInit` or
+    /// `OnFilterScriptInit` (see [`classify`](Self::classify)).
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(crate) fn note_exec(&self, ident: AmxIdent, index: i32) {
+        let inner = self.inner();
+        if inner.init_publics.is_empty() {
+            return;
+        }
+        let Some(publics) = inner.init_publics.get(&ident) else {
+            return;
+        };
+        let kind = if publics.game_mode == Some(index) {
+            AmxKind::Gamemode
+        } else if publics.filter_script == Some(index) {
+            AmxKind::Filterscript
+        } else {
+            return;
+        };
+        inner.init_publics.remove(&ident);
+        inner.amx_kinds.insert(ident, kind);
+    }
+
+    /// Backs `AmxExt::kind`: the kind `ident` was classified as. A script the
+    /// runtime never saw load reads as a filterscript (a side script on
+    /// open.mp).
+    pub fn amx_kind(&self, ident: AmxIdent) -> AmxKind {
+        if let Some(kind) = self.inner().amx_kinds.get(&ident) {
+            return *kind;
+        }
+        #[cfg(not(feature = "samp-only"))]
+        if self.omp_component_list().is_some() {
+            return AmxKind::SideScript;
+        }
+        AmxKind::Filterscript
+    }
+
     pub fn set_plugin<T>(&self, plugin: T)
     where
         T: SampPlugin + 'static,
     {
         let boxed = Box::new(plugin);
-        self.inner().plugin = NonNull::new(Box::into_raw(boxed));
+        let inner = self.inner();
+        inner.plugin = NonNull::new(Box::into_raw(boxed));
+        inner.plugin_type = Some(TypeId::of::<T>());
     }
 
     pub fn set_server_exports(&self, exports: *const usize) {
@@ -340,6 +513,35 @@ impl Runtime {
         self.tick_config().filter(|c| c.omp).map(|c| c.omp_interval)
     }
 
+    /// Turns on the server tick for the main-thread queue. Called by
+    /// `samp::plugin::enable_main_thread*`.
+    pub fn set_main_thread_budget(&self, budget: MainThreadBudget) {
+        self.inner().main_thread_budget = Some(budget);
+    }
+
+    /// True once the main-thread queue asked for the tick.
+    #[inline]
+    pub fn main_thread_enabled(&self) -> bool {
+        self.inner().main_thread_budget.is_some()
+    }
+
+    /// Per-tick budget for draining the main-thread queue.
+    #[inline]
+    pub fn main_thread_budget(&self) -> MainThreadBudget {
+        self.inner().main_thread_budget.unwrap_or_default()
+    }
+
+    /// Interval of the `ITimersComponent` timer: the plugin's own tick
+    /// interval, or the default one when only the main-thread queue needs
+    /// the timer.
+    #[cfg(not(feature = "samp-only"))]
+    pub fn omp_timer_interval(&self) -> Option<Duration> {
+        self.omp_tick_interval().or_else(|| {
+            self.main_thread_enabled()
+                .then(|| TickConfig::default().omp_interval)
+        })
+    }
+
     /// Records the current instant as the latest tick dispatch and returns
     /// the elapsed time since the previous one (zero on the first call).
     pub fn record_tick(&self) -> Duration {
@@ -398,21 +600,63 @@ impl Runtime {
             .cast()
     }
 
+    /// The plugin as a `T`, or `None` when no plugin is set or it is not a
+    /// `T`. Checked counterpart of [`plugin_cast`](Self::plugin_cast).
+    pub fn plugin_downcast<T: SampPlugin + 'static>() -> Option<NonNull<T>> {
+        let inner = Runtime::try_get()?.inner();
+        if inner.plugin_type != Some(TypeId::of::<T>()) {
+            return None;
+        }
+        inner.plugin.map(NonNull::cast)
+    }
+
     // -----------------------------------------------------------------------
     // `#[event]` support — callback interception registry.
     // -----------------------------------------------------------------------
 
-    /// Appends the `#[event]` handlers declared in `initialize_plugin!`. Called
-    /// once at init (SA-MP `Load` / Open Multiplayer `ComponentEntryPoint`).
+    /// Appends the `#[event]` handlers declared in `initialize_plugin!` and
+    /// sorts them into run order: highest `priority` first, handlers of equal
+    /// priority in the order they were listed. The callbacks of `define`
+    /// handlers are recorded for delivery. Called once at init (SA-MP `Load` /
+    /// Open Multiplayer `ComponentEntryPoint`).
     pub fn register_events(&self, mut events: Vec<EventInfo>) {
-        self.inner().events.append(&mut events);
+        let inner = self.inner();
+        inner.events.append(&mut events);
+        // Stable, so equal priorities keep their list order.
+        inner
+            .events
+            .sort_by_key(|event| std::cmp::Reverse(event.priority));
+
+        for event in inner.events.iter().filter(|event| event.define) {
+            if !inner.defined_publics.contains(&event.name) {
+                inner.defined_publics.push(event.name);
+            }
+        }
+    }
+
+    /// The callback `name`, when an `#[event(define)]` handler watches it.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(crate) fn defined_public(&self, name: &str) -> Option<&'static str> {
+        self.inner()
+            .defined_publics
+            .iter()
+            .copied()
+            .find(|defined| *defined == name)
+    }
+
+    /// True when an `#[event(define)]` handler was registered — the gate of
+    /// the hooks that deliver callbacks scripts do not declare.
+    pub(crate) fn has_defined_publics(&self) -> bool {
+        !self.inner().defined_publics.is_empty()
     }
 
-    /// True when at least one `#[event]` handler was registered — the gate that
-    /// decides whether the `amx_Exec` detour is ever installed.
+    /// True when at least one `#[event]` handler was registered or a
+    /// subscription is live — the gate that decides whether the `amx_Exec`
+    /// detour is ever installed.
     #[inline]
     pub fn has_events(&self) -> bool {
-        !self.inner().events.is_empty()
+        let inner = self.inner();
+        !inner.events.is_empty() || !inner.subscriptions.is_empty()
     }
 
     /// Snapshot of the registered events, for per-AMX resolution.
@@ -420,26 +664,28 @@ impl Runtime {
         self.inner().events.clone()
     }
 
-    /// Records a resolved handler for a `(amx, public index)` pair, appending to
-    /// any already registered for that key (multiple handlers per callback run
-    /// in registration order).
-    pub fn push_resolved_event(&self, ident: AmxIdent, index: i32, handler: EventHandler) {
-        self.inner()
+    /// Records a resolved handler for a `(amx, public index)` pair, after every
+    /// handler of that key with the same or a higher priority — so the list
+    /// stays in run order whenever the handler is added.
+    pub(crate) fn push_resolved_event(&self, ident: AmxIdent, index: i32, handler: Resolved) {
+        let list = self
+            .inner()
             .resolved_events
             .entry((ident, index))
-            .or_default()
-            .push(handler);
+            .or_default();
+        let at = list.partition_point(|other| other.priority() >= handler.priority());
+        list.insert(at, handler);
     }
 
-    /// Handlers registered for a given `(amx, public index)` pair, in
-    /// registration order. Empty (no allocation) when the public carries no
-    /// event — the common case on the `amx_Exec` hot path.
+    /// Handlers registered for a given `(amx, public index)` pair, in run
+    /// order. Empty (no allocation) when the public carries no event — the
+    /// common case on the `amx_Exec` hot path.
     ///
     /// Only the `amx_Exec` dispatcher consumes this, and that path exists on
     /// x86/x86_64 alone (the detour library targets no other arch), so the
     /// method is compiled only there — it has no caller elsewhere.
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-    pub fn resolved_handlers(&self, ident: AmxIdent, index: i32) -> Vec<EventHandler> {
+    pub(crate) fn resolved_handlers(&self, ident: AmxIdent, index: i32) -> Vec<Resolved> {
         self.inner()
             .resolved_events
             .get(&(ident, index))
@@ -447,12 +693,120 @@ impl Runtime {
             .unwrap_or_default()
     }
 
+    /// Every `#[event]` handler resolved on an AMX, by public index (so by
+    /// callback name, the order of the publics table) and then in run order.
+    pub fn resolved_events_of(&self, ident: AmxIdent) -> Vec<EventInfo> {
+        let mut resolved: Vec<_> = self
+            .inner()
+            .resolved_events
+            .iter()
+            .filter(|((amx, _), _)| *amx == ident)
+            .collect();
+        resolved.sort_by_key(|((_, index), _)| *index);
+        resolved
+            .into_iter()
+            .flat_map(|(_, handlers)| handlers.iter())
+            .filter_map(|handler| match handler {
+                Resolved::Event(event) => Some(*event),
+                Resolved::Subscription(_) => None,
+            })
+            .collect()
+    }
+
     /// Drops every resolved handler bound to an AMX. Called on `on_amx_unload`,
     /// and also before re-resolving an AMX so a second `on_amx_load` for the
     /// same script cannot register duplicate handlers.
     pub fn remove_resolved_events(&self, ident: AmxIdent) {
         self.inner().resolved_events.retain(|(k, _), _| *k != ident);
     }
+
+    /// Id for a new `samp::events::subscribe` call.
+    pub(crate) fn next_subscription_id(&self) -> u64 {
+        let inner = self.inner();
+        inner.next_subscription += 1;
+        inner.next_subscription
+    }
+
+    /// Keeps a subscription so scripts loaded later resolve it too.
+    pub(crate) fn push_subscription(&self, subscriber: Rc<Subscriber>) {
+        self.inner().subscriptions.push(subscriber);
+    }
+
+    /// Snapshot of the live subscriptions, for per-AMX resolution.
+    pub(crate) fn subscriptions_snapshot(&self) -> Vec<Rc<Subscriber>> {
+        self.inner().subscriptions.clone()
+    }
+
+    /// Forgets subscription `id` and drops it from every handler list.
+    pub(crate) fn remove_subscription(&self, id: u64) {
+        let inner = self.inner();
+        let is_it = |handler: &Resolved| matches!(handler, Resolved::Subscription(subscriber) if subscriber.id == id);
+        // Held until the lists are updated: dropping the closure runs user
+        // code (its captures' `Drop`), which must not find them mid-update.
+        let removed: Vec<_> = inner
+            .subscriptions
+            .extract_if(.., |subscriber| subscriber.id == id)
+            .collect();
+        for handlers in inner.resolved_events.values_mut() {
+            handlers.retain(|handler| !is_it(handler));
+        }
+        inner
+            .resolved_events
+            .retain(|_, handlers| !handlers.is_empty());
+        for subscriber in removed {
+            subscriber.deactivate();
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // `samp::natives` support — the global native registry.
+    // -----------------------------------------------------------------------
+
+    /// Records the host function registered for a native. The last
+    /// registration wins: a plugin that registers a native again to hook it
+    /// replaces the original, as it does for the scripts it registers on.
+    pub fn record_native(&self, name: &str, native: AmxNative) {
+        self.inner().natives.insert(name.to_owned(), native);
+    }
+
+    /// Records a native found bound in a script's natives table, unless a
+    /// registration was already recorded: the table holds what was bound
+    /// when the script loaded, which a later registration supersedes.
+    pub fn record_bound_native(&self, name: &str, native: AmxNative) {
+        self.inner()
+            .natives
+            .entry(name.to_owned())
+            .or_insert(native);
+    }
+
+    /// Marks the `amx_Register` detour as wanted by `samp::natives`.
+    pub fn enable_native_registry(&self) {
+        self.inner().native_registry = true;
+    }
+
+    /// Whether `samp::natives` asked for the `amx_Register` detour.
+    pub fn native_registry_enabled(&self) -> bool {
+        self.inner().native_registry
+    }
+
+    /// Host function recorded for `name`, if any.
+    pub fn recorded_native(&self, name: &str) -> Option<AmxNative> {
+        self.inner().natives.get(name).copied()
+    }
+
+    /// The script-less AMX for `samp::natives`, loaded on first use.
+    pub fn scratch_amx(&self) -> AmxResult<Amx> {
+        let fn_table = self.amx_exports();
+        if fn_table == 0 {
+            return Err(AmxError::InvalidState);
+        }
+        let slot = &mut self.inner().scratch_amx;
+        if let Some(scratch) = slot {
+            return Ok(scratch.amx());
+        }
+        let scratch = ScratchAmx::new(fn_table, ScratchAmx::DEFAULT_CELLS)?;
+        Ok(slot.insert(scratch).amx())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -507,6 +861,18 @@ impl Runtime {
         if ptr.is_null() { None } else { Some(ptr) }
     }
 
+    /// The AMX of `IPawnComponent::mainScript()` — the gamemode — or `None`
+    /// while none is loaded or the Pawn component is missing.
+    fn omp_main_script(&self) -> Option<AmxIdent> {
+        let pawn = self.omp_query_component(samp_sdk::omp::server::PAWN_COMPONENT_UID)?;
+        let script = unsafe { samp_sdk::omp::server::get_main_script(pawn) };
+        if script.is_null() {
+            return None;
+        }
+        let amx = unsafe { samp_sdk::omp::server::get_amx_from_script(script) };
+        (!amx.is_null()).then(|| AmxIdent::from(amx))
+    }
+
     /// Stores the Pawn event handler (keeps it alive for as long as the plugin exists).
     pub fn set_pawn_event_handler(&self, handler: *mut PawnEventHandler) {
         self.inner().pawn_event_handler = NonNull::new(handler);
@@ -522,6 +888,21 @@ impl Runtime {
             .map(std::ptr::NonNull::as_ptr)
     }
 
+    /// Stores the player connection handler that delivers `define` callbacks.
+    pub fn set_player_connect_handler(&self, handler: *mut PlayerConnectEventHandler) {
+        self.inner().player_connect_handler = NonNull::new(handler);
+    }
+
+    /// Removes and returns the stored player connection handler, or `None` if
+    /// there was none. Used in `omp_cleanup`, like
+    /// [`take_pawn_event_handler`](Self::take_pawn_event_handler).
+    pub fn take_player_connect_handler(&self) -> Option<*mut PlayerConnectEventHandler> {
+        self.inner()
+            .player_connect_handler
+            .take()
+            .map(std::ptr::NonNull::as_ptr)
+    }
+
     /// Stores the list of natives to register on the AMX in native Open Multiplayer mode.
     ///
     /// Called by the generated `ComponentEntryPoint` before registering the `PawnEventHandler`,
@@ -0,0 +1,522 @@
+//! Work that crosses threads: async natives and the main-thread queue.
+//!
+//! [`MainThread`] (from `samp::plugin::main_thread`) lets any thread queue a
+//! closure for the server thread. The SDK drains that queue at the start of
+//! every tick, within a per-tick [`MainThreadBudget`].
+//!
+//! # Async natives — `#[native(name = "...", async = "OnDone")]`
+//!
+//! An async native does not run its body on the server thread. The generated
+//! wrapper parses the Pawn arguments into owned values ([`AsyncArg`]), queues
+//! the body on an SDK-managed worker pool and returns `1` to Pawn right away.
+//! When the body finishes, its result joins the main-thread queue, and on
+//! the next server tick the SDK calls the named public on the AMX that
+//! invoked the native, pushing the result as the public's arguments
+//! ([`CallbackArgs`]).
+//!
+//! ```rust,ignore
+//! impl MyPlugin {
+//!     // Pawn: forward OnHttpGet(playerid, status, const body[]);
+//!     #[native(name = "Http_Get", async = "OnHttpGet")]
+//!     fn http_get(playerid: i32, url: String) -> Result<(i32, i32, String), MyError> {
+//!         let response = blocking_http_get(&url)?;
+//!         Ok((playerid, response.status, response.body))
+//!     }
+//! }
+//! ```
+//!
+//! Delivery rules:
+//!
+//! - Results are delivered on the server thread, from the SA-MP
+//!   `ProcessTick` export or the Open Multiplayer tick timer. Declaring an
+//!   async native is enough to turn that tick on — `enable_tick` is not
+//!   required, and `on_tick` still only fires when the plugin asked for it.
+//! - If the originating AMX was unloaded while the body ran, the result is
+//!   dropped silently.
+//! - A body returning `Err` (or panicking) is logged with the native's name
+//!   and the callback is not called.
+//! - A callback public missing from the script is logged as an error.
+
+use std::collections::{HashMap, VecDeque};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+use samp_sdk::amx::{Allocator, Amx};
+use samp_sdk::args::Args;
+use samp_sdk::cell::AmxString;
+use samp_sdk::error::AmxResult;
+
+use crate::amx::{AmxExt, AmxIdent};
+use crate::plugin::{MainThreadBudget, SampPlugin};
+use crate::runtime::Runtime;
+
+/// Upper bound on worker threads. Async natives are meant for blocking I/O
+/// (database, HTTP), not CPU-bound work, so a handful is plenty.
+const MAX_WORKERS: usize = 8;
+
+/// A native argument that can be copied out of the AMX and moved to a worker
+/// thread. The async counterpart of `AmxCell`: borrowed views such as
+/// `&AmxString` cannot outlive the native call, so async natives take owned
+/// values instead (`String` for `const name[]`).
+pub trait AsyncArg: Sized + Send + 'static {
+    /// Reads the next argument. `None` when it is missing or malformed.
+    fn from_args(args: &mut Args<'_>) -> Option<Self>;
+}
+
+macro_rules! impl_async_arg {
+    ($($type:ty),*) => {
+        $(
+            impl AsyncArg for $type {
+                #[inline]
+                fn from_args(args: &mut Args<'_>) -> Option<Self> {
+                    args.next_arg::<$type>()
+                }
+            }
+        )*
+    };
+}
+
+impl_async_arg!(i8, u8, i16, u16, i32, u32, isize, usize, f32, bool);
+
+impl AsyncArg for String {
+    fn from_args(args: &mut Args<'_>) -> Option<Self> {
+        args.next_arg::<AmxString>()
+            .map(|string| string.to_string())
+    }
+}
+
+/// A single value pushed onto the callback public.
+pub trait CallbackArg: Send + 'static {
+    /// Pushes the value onto the AMX stack. Strings are copied into the AMX
+    /// heap through `allocator`, released after the public returns.
+    ///
+    /// # Errors
+    /// Whatever `amx_Push`/`amx_Allot` report — typically a full heap.
+    fn push(self, amx: &Amx, allocator: &Allocator<'_>) -> AmxResult<()>;
+}
+
+macro_rules! impl_callback_arg {
+    ($($type:ty),*) => {
+        $(
+            impl CallbackArg for $type {
+                #[inline]
+                fn push(self, amx: &Amx, _allocator: &Allocator<'_>) -> AmxResult<()> {
+                    amx.push(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_callback_arg!(i8, u8, i16, u16, i32, u32, isize, usize, f32, bool);
+
+impl CallbackArg for String {
+    fn push(self, amx: &Amx, allocator: &Allocator<'_>) -> AmxResult<()> {
+        let string = allocator.allot_string(&self)?;
+        amx.push(string)
+    }
+}
+
+/// The full argument list of the callback public — what an async native's
+/// body returns. `()` calls the public with no arguments, a single
+/// [`CallbackArg`] with one, and a tuple with one argument per element in
+/// declaration order.
+pub trait CallbackArgs: Send + 'static {
+    /// Pushes every argument, last first (the AMX calling convention).
+    ///
+    /// # Errors
+    /// The first failing [`CallbackArg::push`].
+    fn push_args(self, amx: &Amx, allocator: &Allocator<'_>) -> AmxResult<()>;
+}
+
+impl CallbackArgs for () {
+    #[inline]
+    fn push_args(self, _amx: &Amx, _allocator: &Allocator<'_>) -> AmxResult<()> {
+        Ok(())
+    }
+}
+
+impl<T: CallbackArg> CallbackArgs for T {
+    #[inline]
+    fn push_args(self, amx: &Amx, allocator: &Allocator<'_>) -> AmxResult<()> {
+        self.push(amx, allocator)
+    }
+}
+
+macro_rules! impl_callback_args_tuple {
+    ($($name:ident),+ ; $($rev:ident),+) => {
+        impl<$($name: CallbackArg),+> CallbackArgs for ($($name,)+) {
+            #[allow(non_snake_case)] // Bindings reuse the type parameter names.
+            fn push_args(self, amx: &Amx, allocator: &Allocator<'_>) -> AmxResult<()> {
+                let ($($name,)+) = self;
+                $($rev.push(amx, allocator)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_callback_args_tuple!(A; A);
+impl_callback_args_tuple!(A, B; B, A);
+impl_callback_args_tuple!(A, B, C; C, B, A);
+impl_callback_args_tuple!(A, B, C, D; D, C, B, A);
+impl_callback_args_tuple!(A, B, C, D, E; E, D, C, B, A);
+impl_callback_args_tuple!(A, B, C, D, E, F; F, E, D, C, B, A);
+impl_callback_args_tuple!(A, B, C, D, E, F, G; G, F, E, D, C, B, A);
+impl_callback_args_tuple!(A, B, C, D, E, F, G, H; H, G, F, E, D, C, B, A);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Queue of closures waiting for the server thread — fed by [`MainThread`]
+/// handles and by finished async natives, drained on every tick.
+static MAIN_QUEUE: Mutex<VecDeque<Job>> = Mutex::new(VecDeque::new());
+
+/// Job queue shared by the workers. Started on the first `spawn`.
+static POOL: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+/// Liveness flag per AMX that has async work in flight. Flipped to `false`
+/// in `on_amx_unload`, so completions for a dead AMX are discarded even if
+/// the server reuses the same `AMX*` address for the next script.
+static ALIVE: OnceLock<Mutex<HashMap<AmxIdent, Arc<AtomicBool>>>> = OnceLock::new();
+
+/// Handle for getting work onto the server thread from any other thread.
+///
+/// Obtained with [`samp::plugin::main_thread`]. `Send + Clone` — clone it
+/// into worker threads, async runtimes or callbacks of other libraries.
+/// Queued closures run on the server thread at the start of the next tick
+/// (SA-MP `ProcessTick` or the Open Multiplayer tick timer), in FIFO order,
+/// within the per-tick [`MainThreadBudget`]; whatever does not fit waits for
+/// the following tick.
+///
+/// The queue is drained only while the tick runs: turn it on with
+/// [`enable_main_thread`] (or [`enable_tick`]) in the constructor.
+///
+/// ```rust,ignore
+/// let main = samp::plugin::main_thread();
+/// std::thread::spawn(move || {
+///     let rows = load_from_db();
+///     main.run_with_plugin(move |plugin: &mut MyPlugin| plugin.cache = rows);
+/// });
+/// ```
+///
+/// [`samp::plugin::main_thread`]: crate::plugin::main_thread
+/// [`MainThreadBudget`]: crate::plugin::MainThreadBudget
+/// [`enable_main_thread`]: crate::plugin::enable_main_thread
+/// [`enable_tick`]: crate::plugin::enable_tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MainThread {
+    _private: (),
+}
+
+impl MainThread {
+    pub(crate) fn new() -> Self {
+        MainThread { _private: () }
+    }
+
+    /// Runs `task` on the server thread. A panic inside it is caught and
+    /// logged.
+    pub fn run<F>(&self, task: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        enqueue(Box::new(task));
+    }
+
+    /// Runs `task` with the AMX identified by `amx` — e.g. to call a public
+    /// via `exec_public!`. Skipped when that script is no longer loaded.
+    pub fn run_on<F>(&self, amx: AmxIdent, task: F)
+    where
+        F: FnOnce(&Amx) + Send + 'static,
+    {
+        self.run(move || {
+            if let Some(amx) = crate::amx::get(amx) {
+                task(amx);
+            }
+        });
+    }
+
+    /// Runs `task` with mutable access to the plugin instance.
+    ///
+    /// `T` must be the plugin type passed to `initialize_plugin!`; for any
+    /// other type the task is skipped and an error logged.
+    pub fn run_with_plugin<T, F>(&self, task: F)
+    where
+        T: SampPlugin + 'static,
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        self.run(move || {
+            let Some(mut plugin) = Runtime::plugin_downcast::<T>() else {
+                log::error!(
+                    "run_with_plugin: {} is not the plugin type; task skipped",
+                    std::any::type_name::<T>()
+                );
+                return;
+            };
+            // SAFETY: the plugin is a `T` (checked above), and this runs on
+            // the server thread, like every native and lifecycle callback —
+            // nothing else holds the plugin right now.
+            task(unsafe { plugin.as_mut() });
+        });
+    }
+
+    /// Number of closures waiting for the server thread.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        MAIN_QUEUE.lock().map_or(0, |queue| queue.len())
+    }
+}
+
+fn enqueue(job: Job) {
+    if let Ok(mut queue) = MAIN_QUEUE.lock() {
+        queue.push_back(job);
+    }
+}
+
+fn alive_map() -> &'static Mutex<HashMap<AmxIdent, Arc<AtomicBool>>> {
+    ALIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn pool() -> &'static Mutex<Sender<Job>> {
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = thread::available_parallelism()
+            .map_or(2, std::num::NonZeroUsize::get)
+            .clamp(2, MAX_WORKERS);
+        for id in 0..workers {
+            let receiver = Arc::clone(&receiver);
+            let _ = thread::Builder::new()
+                .name(format!("samp-async-{id}"))
+                .spawn(move || worker(&receiver));
+        }
+        Mutex::new(sender)
+    })
+}
+
+fn worker(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The guard is dropped at the end of the statement — the job itself
+        // runs without holding the lock.
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+/// Queues the body of an async native. Called by the wrapper that
+/// `#[native(async = "...")]` generates, after the arguments were parsed.
+///
+/// `job` runs on a worker thread; its `Ok` value is pushed onto `callback`
+/// from the main-thread queue, its `Err` is logged there. A panic inside
+/// `job` is caught and logged like an `Err`.
+#[doc(hidden)]
+pub fn spawn<F, R>(amx: &Amx, native: &'static str, callback: &'static str, job: F)
+where
+    F: FnOnce() -> Result<R, String> + Send + 'static,
+    R: CallbackArgs,
+{
+    let ident = amx.ident();
+    let alive = match alive_map().lock() {
+        Ok(mut map) => Arc::clone(
+            map.entry(ident)
+                .or_insert_with(|| Arc::new(AtomicBool::new(true))),
+        ),
+        Err(_) => return,
+    };
+
+    let task: Job = Box::new(move || {
+        let result = match catch_unwind(AssertUnwindSafe(job)) {
+            Ok(result) => result,
+            Err(panic) => Err(format!("panic in async native: {}", panic_message(&*panic))),
+        };
+        enqueue(Box::new(move || {
+            if !alive.load(Ordering::Acquire) {
+                return;
+            }
+            let value = match result {
+                Ok(value) => value,
+                Err(err) => {
+                    log::error!("[{native}] {err}");
+                    return;
+                }
+            };
+            let Some(amx) = crate::amx::get(ident) else {
+                return;
+            };
+            if let Err(err) = deliver(amx, callback, value) {
+                log::error!("[{native}] failed to call {callback}: {err}");
+            }
+        }));
+    });
+
+    let sent = pool().lock().is_ok_and(|sender| sender.send(task).is_ok());
+    if !sent {
+        log::error!("[{native}] async worker pool unavailable");
+    }
+}
+
+fn deliver<R: CallbackArgs>(amx: &Amx, callback: &str, value: R) -> AmxResult<()> {
+    amx.exec_public_scope(callback, |allocator, index| {
+        value.push_args(amx, allocator)?;
+        amx.exec(index).map(drop)
+    })
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("(non-string payload)")
+}
+
+/// Runs queued closures on the server thread until the queue is empty or
+/// `budget` is spent. Called at the start of each tick, before `on_tick`.
+/// At least one closure runs per call, so the queue always makes progress.
+pub(crate) fn drain(budget: MainThreadBudget) {
+    let started = Instant::now();
+    let mut ran = 0;
+    while ran < budget.max_tasks.max(1) {
+        if ran > 0 && started.elapsed() >= budget.max_time {
+            break;
+        }
+        // Pop under the lock, run without it — the closure may queue more.
+        let Some(job) = MAIN_QUEUE
+            .lock()
+            .ok()
+            .and_then(|mut queue| queue.pop_front())
+        else {
+            break;
+        };
+        if let Err(panic) = catch_unwind(AssertUnwindSafe(job)) {
+            log::error!("panic in main-thread task: {}", panic_message(&*panic));
+        }
+        ran += 1;
+    }
+}
+
+/// Marks the AMX as gone so its pending results are dropped. Called from
+/// `interlayer::amx_unload`.
+pub(crate) fn on_amx_unload(ident: AmxIdent) {
+    let Some(map) = ALIVE.get() else {
+        return;
+    };
+    if let Ok(mut map) = map.lock()
+        && let Some(alive) = map.remove(&ident)
+    {
+        alive.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// The main-thread queue is process-wide; tests that drain it run one
+    /// at a time.
+    static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn worker_runs_jobs_off_the_calling_thread() {
+        let caller = thread::current().id();
+        let (tx, rx) = mpsc::channel();
+        let sent = pool().lock().unwrap().send(Box::new(move || {
+            let _ = tx.send(thread::current().id());
+        }));
+        assert!(sent.is_ok());
+        let worker = rx.recv().unwrap();
+        assert_ne!(worker, caller);
+    }
+
+    #[test]
+    fn panic_message_reads_str_and_string_payloads() {
+        let from_str = catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(panic_message(&*from_str), "boom");
+        let from_string = catch_unwind(|| panic!("{}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*from_string), "42");
+    }
+
+    #[test]
+    fn unload_flips_the_liveness_flag() {
+        let ident = AmxIdent::from(0x1000 as *mut samp_sdk::raw::types::AMX);
+        let flag = Arc::new(AtomicBool::new(true));
+        alive_map().lock().unwrap().insert(ident, Arc::clone(&flag));
+
+        on_amx_unload(ident);
+
+        assert!(!flag.load(Ordering::Acquire));
+        assert!(!alive_map().lock().unwrap().contains_key(&ident));
+    }
+
+    #[test]
+    fn drain_runs_in_order_within_the_task_budget() {
+        let _guard = QUEUE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let main = MainThread::new();
+        for i in 0..5 {
+            let seen = Arc::clone(&seen);
+            thread::spawn(move || main.run(move || seen.lock().unwrap().push(i)))
+                .join()
+                .unwrap();
+        }
+        assert_eq!(main.pending(), 5);
+
+        let budget = MainThreadBudget::new()
+            .max_tasks(3)
+            .max_time(Duration::from_secs(5));
+        drain(budget);
+        assert_eq!(*seen.lock().unwrap(), [0, 1, 2]);
+        assert_eq!(main.pending(), 2);
+
+        drain(budget);
+        assert_eq!(*seen.lock().unwrap(), [0, 1, 2, 3, 4]);
+        assert_eq!(main.pending(), 0);
+    }
+
+    #[test]
+    fn drain_stops_when_the_time_budget_is_spent() {
+        let _guard = QUEUE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let main = MainThread::new();
+        for _ in 0..3 {
+            main.run(|| thread::sleep(Duration::from_millis(5)));
+        }
+
+        // The first task always runs, even when it alone blows the budget.
+        drain(MainThreadBudget::new().max_time(Duration::from_millis(1)));
+        assert_eq!(main.pending(), 2);
+
+        drain(MainThreadBudget::new().max_time(Duration::from_secs(5)));
+        assert_eq!(main.pending(), 0);
+    }
+
+    #[test]
+    fn drain_survives_a_panicking_task() {
+        let _guard = QUEUE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let main = MainThread::new();
+        let ran = Arc::new(AtomicBool::new(false));
+        main.run(|| panic!("task failed"));
+        let flag = Arc::clone(&ran);
+        main.run(move || flag.store(true, Ordering::Release));
+
+        // Unwinding (and the panic hook's output) can exceed the default 1 ms.
+        drain(MainThreadBudget::new().max_time(Duration::from_secs(5)));
+
+        assert!(ran.load(Ordering::Acquire));
+    }
+}